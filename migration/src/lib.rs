@@ -7,6 +7,24 @@ mod m20251114_000003_create_invite_codes;
 mod m20251114_000004_create_config_audit_logs;
 mod m20251114_000005_add_user_management_fields;
 mod m20251114_000006_add_runtime_config;
+mod m20251115_000001_add_pkce_support;
+mod m20251115_000002_make_access_token_user_nullable;
+mod m20251115_000003_create_device_codes;
+mod m20251115_000004_create_signing_keys;
+mod m20251115_000005_create_consents;
+mod m20251115_000006_add_oidc_auth_params;
+mod m20251115_000007_add_client_jwks;
+mod m20251115_000008_add_client_registration;
+mod m20251115_000009_add_client_logout_uris;
+mod m20251115_000010_add_client_backchannel_logout;
+mod m20251115_000011_add_client_response_modes;
+mod m20251115_000012_add_client_require_par;
+mod m20251115_000013_add_client_require_signed_request;
+mod m20251115_000014_add_client_token_exchange_policy;
+mod m20251115_000015_add_pairwise_subjects;
+mod m20251115_000016_add_user_profile_claims;
+mod m20251115_000017_add_access_token_auth_time;
+mod m20251115_000018_add_device_code_auth_time;
 
 pub struct Migrator;
 
@@ -21,6 +39,24 @@ impl MigratorTrait for Migrator {
             Box::new(m20251114_000004_create_config_audit_logs::Migration),
             Box::new(m20251114_000005_add_user_management_fields::Migration),
             Box::new(m20251114_000006_add_runtime_config::Migration),
+            Box::new(m20251115_000001_add_pkce_support::Migration),
+            Box::new(m20251115_000002_make_access_token_user_nullable::Migration),
+            Box::new(m20251115_000003_create_device_codes::Migration),
+            Box::new(m20251115_000004_create_signing_keys::Migration),
+            Box::new(m20251115_000005_create_consents::Migration),
+            Box::new(m20251115_000006_add_oidc_auth_params::Migration),
+            Box::new(m20251115_000007_add_client_jwks::Migration),
+            Box::new(m20251115_000008_add_client_registration::Migration),
+            Box::new(m20251115_000009_add_client_logout_uris::Migration),
+            Box::new(m20251115_000010_add_client_backchannel_logout::Migration),
+            Box::new(m20251115_000011_add_client_response_modes::Migration),
+            Box::new(m20251115_000012_add_client_require_par::Migration),
+            Box::new(m20251115_000013_add_client_require_signed_request::Migration),
+            Box::new(m20251115_000014_add_client_token_exchange_policy::Migration),
+            Box::new(m20251115_000015_add_pairwise_subjects::Migration),
+            Box::new(m20251115_000016_add_user_profile_claims::Migration),
+            Box::new(m20251115_000017_add_access_token_auth_time::Migration),
+            Box::new(m20251115_000018_add_device_code_auth_time::Migration),
         ]
     }
 }
@@ -0,0 +1,111 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次添加多个字段，需要分别执行
+
+        // 授权码保存 PKCE challenge
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(string_null(AuthorizationCodes::CodeChallenge))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(string_null(AuthorizationCodes::CodeChallengeMethod))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 客户端是否强制 PKCE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(boolean(OAuthClients::RequirePkce).default(false).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 客户端认证方式: client_secret_post / none（公共客户端）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(
+                        string(OAuthClients::TokenEndpointAuthMethod)
+                            .default("client_secret_post")
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次删除多个字段，需要分别执行
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::TokenEndpointAuthMethod)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::RequirePkce)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::CodeChallengeMethod)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::CodeChallenge)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuthorizationCodes {
+    Table,
+    CodeChallenge,
+    CodeChallengeMethod,
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    RequirePkce,
+    TokenEndpointAuthMethod,
+}
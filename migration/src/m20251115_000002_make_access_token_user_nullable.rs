@@ -0,0 +1,186 @@
+use sea_orm_migration::sea_orm::{DbBackend, TransactionTrait};
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // client_credentials 签发的 token 不属于任何用户，user_id 需要允许为空
+        set_user_id_nullable(manager, true).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 无用户的 token 无法保留
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(AccessTokens::Table)
+                    .and_where(Expr::col(AccessTokens::UserId).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        set_user_id_nullable(manager, false).await
+    }
+}
+
+async fn set_user_id_nullable(manager: &SchemaManager<'_>, nullable: bool) -> Result<(), DbErr> {
+    let user_id = if nullable {
+        integer_null(AccessTokens::UserId)
+    } else {
+        integer(AccessTokens::UserId)
+    };
+
+    if manager.get_database_backend() != DbBackend::Sqlite {
+        return manager
+            .alter_table(
+                Table::alter()
+                    .table(AccessTokens::Table)
+                    .modify_column(user_id)
+                    .to_owned(),
+            )
+            .await;
+    }
+
+    // SQLite 不支持修改字段约束，需要重建表。
+    // 迁移在 SQLite 上不会自动开启事务，连接池中的其他连接可能看到过期的 schema，
+    // 所以整个重建过程放在同一个事务（同一个连接）中执行。
+    let txn = manager.get_connection().begin().await?;
+    rebuild_sqlite_access_tokens(&SchemaManager::new(&txn), user_id).await?;
+    txn.commit().await
+}
+
+async fn rebuild_sqlite_access_tokens(
+    manager: &SchemaManager<'_>,
+    user_id: ColumnDef,
+) -> Result<(), DbErr> {
+    // refresh_tokens 通过外键引用 access_tokens：直接删除 access_tokens 会级联删除
+    // refresh_tokens，重命名则会改写其外键，所以先把 refresh_tokens 备份并删除。
+    let db = manager.get_connection();
+
+    db.execute_unprepared("CREATE TABLE refresh_tokens_backup AS SELECT * FROM refresh_tokens")
+        .await?;
+    manager
+        .drop_table(Table::drop().table(RefreshTokens::Table).to_owned())
+        .await?;
+
+    manager
+        .create_table(access_tokens_table(AccessTokensNew::Table, user_id))
+        .await?;
+    db.execute_unprepared(
+        "INSERT INTO access_tokens_new \
+         (id, token, token_type, client_id, user_id, scopes, expires_at, created_at) \
+         SELECT id, token, token_type, client_id, user_id, scopes, expires_at, created_at \
+         FROM access_tokens",
+    )
+    .await?;
+    manager
+        .drop_table(Table::drop().table(AccessTokens::Table).to_owned())
+        .await?;
+    manager
+        .rename_table(
+            Table::rename()
+                .table(AccessTokensNew::Table, AccessTokens::Table)
+                .to_owned(),
+        )
+        .await?;
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_access_tokens_expires_at")
+                .table(AccessTokens::Table)
+                .col(AccessTokens::ExpiresAt)
+                .to_owned(),
+        )
+        .await?;
+
+    // 恢复 refresh_tokens
+    manager
+        .create_table(
+            Table::create()
+                .table(RefreshTokens::Table)
+                .if_not_exists()
+                .col(pk_auto(RefreshTokens::Id))
+                .col(text(RefreshTokens::Token))
+                .col(integer(RefreshTokens::AccessTokenId))
+                .col(timestamp_with_time_zone(RefreshTokens::ExpiresAt))
+                .col(timestamp_with_time_zone(RefreshTokens::CreatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(RefreshTokens::Table, RefreshTokens::AccessTokenId)
+                        .to(AccessTokens::Table, AccessTokens::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+    db.execute_unprepared(
+        "INSERT INTO refresh_tokens (id, token, access_token_id, expires_at, created_at) \
+         SELECT id, token, access_token_id, expires_at, created_at FROM refresh_tokens_backup",
+    )
+    .await?;
+    db.execute_unprepared("DROP TABLE refresh_tokens_backup")
+        .await?;
+
+    Ok(())
+}
+
+fn access_tokens_table<T: IntoIden + 'static>(
+    table: T,
+    user_id: ColumnDef,
+) -> TableCreateStatement {
+    Table::create()
+        .table(table)
+        .if_not_exists()
+        .col(pk_auto(AccessTokens::Id))
+        .col(text(AccessTokens::Token))
+        .col(string(AccessTokens::TokenType).default("Bearer"))
+        .col(string(AccessTokens::ClientId))
+        .col(user_id)
+        .col(text(AccessTokens::Scopes)) // JSON array
+        .col(timestamp_with_time_zone(AccessTokens::ExpiresAt))
+        .col(timestamp_with_time_zone(AccessTokens::CreatedAt))
+        .foreign_key(
+            ForeignKey::create()
+                .from(AccessTokens::Table, AccessTokens::UserId)
+                .to(Users::Table, Users::Id)
+                .on_delete(ForeignKeyAction::Cascade),
+        )
+        .to_owned()
+}
+
+#[derive(DeriveIden)]
+enum AccessTokens {
+    Table,
+    Id,
+    Token,
+    TokenType,
+    ClientId,
+    UserId,
+    Scopes,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AccessTokensNew {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum RefreshTokens {
+    Table,
+    Id,
+    Token,
+    AccessTokenId,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
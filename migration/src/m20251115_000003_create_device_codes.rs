@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 创建 device_codes 表（RFC 8628 设备授权）
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeviceCodes::Table)
+                    .if_not_exists()
+                    .col(pk_auto(DeviceCodes::Id))
+                    .col(string_uniq(DeviceCodes::DeviceCode)) // 设备轮询使用
+                    .col(string_uniq(DeviceCodes::UserCode)) // 用户在浏览器中输入
+                    .col(string(DeviceCodes::ClientId))
+                    .col(text(DeviceCodes::Scopes))
+                    .col(integer_null(DeviceCodes::UserId)) // 批准/拒绝的用户
+                    .col(string(DeviceCodes::Status).default("pending")) // pending / approved / denied
+                    .col(integer(DeviceCodes::Interval).default(5)) // 最小轮询间隔（秒）
+                    .col(timestamp_with_time_zone_null(DeviceCodes::LastPolledAt))
+                    .col(timestamp_with_time_zone(DeviceCodes::ExpiresAt))
+                    .col(timestamp_with_time_zone(DeviceCodes::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(DeviceCodes::Table, DeviceCodes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建索引
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_device_codes_expires_at")
+                    .table(DeviceCodes::Table)
+                    .col(DeviceCodes::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeviceCodes::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeviceCodes {
+    Table,
+    Id,
+    DeviceCode,
+    UserCode,
+    ClientId,
+    Scopes,
+    UserId,
+    Status,
+    Interval,
+    LastPolledAt,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
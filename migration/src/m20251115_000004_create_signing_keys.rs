@@ -0,0 +1,61 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 创建 signing_keys 表（JWT 签名密钥轮换）
+        manager
+            .create_table(
+                Table::create()
+                    .table(SigningKeys::Table)
+                    .if_not_exists()
+                    .col(pk_auto(SigningKeys::Id))
+                    .col(string_uniq(SigningKeys::Kid)) // JWK Thumbprint
+                    .col(string(SigningKeys::Algorithm)) // RS256 / ES256 / EdDSA
+                    .col(text(SigningKeys::PrivateKey)) // PKCS#8 PEM
+                    .col(string(SigningKeys::Status)) // next / active / retired
+                    .col(timestamp_with_time_zone(SigningKeys::CreatedAt))
+                    .col(timestamp_with_time_zone_null(SigningKeys::ActivatedAt))
+                    .col(timestamp_with_time_zone_null(SigningKeys::RetiredAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建索引
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_signing_keys_status")
+                    .table(SigningKeys::Table)
+                    .col(SigningKeys::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SigningKeys::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SigningKeys {
+    Table,
+    Id,
+    Kid,
+    Algorithm,
+    PrivateKey,
+    Status,
+    CreatedAt,
+    ActivatedAt,
+    RetiredAt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 创建 consents 表（用户对客户端已同意的 scope）
+        manager
+            .create_table(
+                Table::create()
+                    .table(Consents::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Consents::Id))
+                    .col(integer(Consents::UserId))
+                    .col(string(Consents::ClientId))
+                    .col(text(Consents::Scopes)) // 空格分隔
+                    .col(timestamp_with_time_zone(Consents::CreatedAt))
+                    .col(timestamp_with_time_zone(Consents::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Consents::Table, Consents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 每个用户对每个客户端只有一条记录
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_consents_user_client")
+                    .table(Consents::Table)
+                    .col(Consents::UserId)
+                    .col(Consents::ClientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Consents::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Consents {
+    Table,
+    Id,
+    UserId,
+    ClientId,
+    Scopes,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
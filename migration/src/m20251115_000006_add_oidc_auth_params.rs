@@ -0,0 +1,83 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次添加多个字段，需要分别执行
+
+        // 授权请求中的 OIDC nonce（原样写入 ID Token）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(text_null(AuthorizationCodes::Nonce))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 授权请求中的 max_age（秒）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(big_integer_null(AuthorizationCodes::MaxAge))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 用户完成认证的时间（ID Token 的 auth_time）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(timestamp_with_time_zone_null(AuthorizationCodes::AuthTime))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次删除多个字段，需要分别执行
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::AuthTime)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::MaxAge)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::Nonce)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuthorizationCodes {
+    Table,
+    Nonce,
+    MaxAge,
+    AuthTime,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次添加多个字段，需要分别执行
+
+        // 客户端公钥集（JSON 格式的 JWKS，用于 private_key_jwt）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(text_null(OAuthClients::Jwks))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 客户端公钥集地址
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(string_null(OAuthClients::JwksUri))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次删除多个字段，需要分别执行
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::JwksUri)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::Jwks)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    Jwks,
+    JwksUri,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次添加多个字段，需要分别执行
+
+        // 客户端允许使用的授权类型（JSON 数组，为空表示不限制）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(text_null(OAuthClients::GrantTypes))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 动态注册客户端的 registration_access_token（哈希）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(string_null(OAuthClients::RegistrationAccessToken))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次删除多个字段，需要分别执行
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::RegistrationAccessToken)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::GrantTypes)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    GrantTypes,
+    RegistrationAccessToken,
+}
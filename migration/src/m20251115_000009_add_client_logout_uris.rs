@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 登出后允许重定向的地址（JSON 数组，用于 RP 发起的登出）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(text_null(OAuthClients::PostLogoutRedirectUris))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::PostLogoutRedirectUris)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    PostLogoutRedirectUris,
+}
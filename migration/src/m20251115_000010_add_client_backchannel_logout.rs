@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Back-Channel Logout 通知地址
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(string_null(OAuthClients::BackchannelLogoutUri))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::BackchannelLogoutUri)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    BackchannelLogoutUri,
+}
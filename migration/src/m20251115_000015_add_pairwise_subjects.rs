@@ -0,0 +1,117 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 客户端的 subject_type（public / pairwise）及扇区标识 URI（OpenID Connect Core 8）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(
+                        string(OAuthClients::SubjectType)
+                            .default("public")
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .add_column(string_null(OAuthClients::SectorIdentifierUri))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 创建 pairwise_subjects 表（pairwise 标识到用户的映射）
+        manager
+            .create_table(
+                Table::create()
+                    .table(PairwiseSubjects::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PairwiseSubjects::Id))
+                    .col(integer(PairwiseSubjects::UserId))
+                    .col(string(PairwiseSubjects::SectorIdentifier))
+                    .col(string_uniq(PairwiseSubjects::Subject))
+                    .col(timestamp_with_time_zone(PairwiseSubjects::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PairwiseSubjects::Table, PairwiseSubjects::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 每个用户在每个扇区只有一个 pairwise 标识
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_pairwise_subjects_sector_user")
+                    .table(PairwiseSubjects::Table)
+                    .col(PairwiseSubjects::SectorIdentifier)
+                    .col(PairwiseSubjects::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PairwiseSubjects::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::SectorIdentifierUri)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClients::Table)
+                    .drop_column(OAuthClients::SubjectType)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClients {
+    Table,
+    SubjectType,
+    SectorIdentifierUri,
+}
+
+#[derive(DeriveIden)]
+enum PairwiseSubjects {
+    Table,
+    Id,
+    UserId,
+    SectorIdentifier,
+    Subject,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
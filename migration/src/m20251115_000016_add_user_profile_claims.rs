@@ -0,0 +1,141 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次添加多个字段，需要分别执行
+
+        // 用户的标准 OIDC 资料（OpenID Connect Core 5.1），address 为 JSON 对象
+        for column in [
+            string_null(Users::GivenName),
+            string_null(Users::FamilyName),
+            text_null(Users::Picture),
+            string_null(Users::Locale),
+            string_null(Users::Zoneinfo),
+            string_null(Users::PhoneNumber),
+            text_null(Users::Address),
+            boolean(Users::EmailVerified)
+                .default(false)
+                .not_null()
+                .to_owned(),
+            boolean(Users::PhoneNumberVerified)
+                .default(false)
+                .not_null()
+                .to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Users::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 之前 userinfo 对所有用户返回 email_verified=true，已有用户保持原有结果；
+        // 之后注册的用户默认未验证
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Users::Table)
+                    .value(Users::EmailVerified, true)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 授权请求中的 claims 参数（OpenID Connect Core 5.5），随授权码和 Token 保存
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .add_column(text_null(AuthorizationCodes::Claims))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AccessTokens::Table)
+                    .add_column(text_null(AccessTokens::Claims))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持一次删除多个字段，需要分别执行
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AccessTokens::Table)
+                    .drop_column(AccessTokens::Claims)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthorizationCodes::Table)
+                    .drop_column(AuthorizationCodes::Claims)
+                    .to_owned(),
+            )
+            .await?;
+
+        for column in [
+            Users::PhoneNumberVerified,
+            Users::EmailVerified,
+            Users::Address,
+            Users::PhoneNumber,
+            Users::Zoneinfo,
+            Users::Locale,
+            Users::Picture,
+            Users::FamilyName,
+            Users::GivenName,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Users::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    GivenName,
+    FamilyName,
+    Picture,
+    Locale,
+    Zoneinfo,
+    PhoneNumber,
+    Address,
+    EmailVerified,
+    PhoneNumberVerified,
+}
+
+#[derive(DeriveIden)]
+enum AuthorizationCodes {
+    Table,
+    Claims,
+}
+
+#[derive(DeriveIden)]
+enum AccessTokens {
+    Table,
+    Claims,
+}
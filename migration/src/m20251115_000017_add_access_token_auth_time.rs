@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户完成认证的时间，刷新 Token 时沿用（ID Token 的 auth_time）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AccessTokens::Table)
+                    .add_column(timestamp_with_time_zone_null(AccessTokens::AuthTime))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AccessTokens::Table)
+                    .drop_column(AccessTokens::AuthTime)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AccessTokens {
+    Table,
+    AuthTime,
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 批准设备授权的会话的认证时间，签发 Token 时使用（ID Token 的 auth_time）
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeviceCodes::Table)
+                    .add_column(timestamp_with_time_zone_null(DeviceCodes::AuthTime))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeviceCodes::Table)
+                    .drop_column(DeviceCodes::AuthTime)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeviceCodes {
+    Table,
+    AuthTime,
+}
@@ -12,11 +12,14 @@ pub struct Model {
     pub token: String,
     pub token_type: String,
     pub client_id: String,
-    pub user_id: i64,
+    pub user_id: Option<i64>,
     #[sea_orm(column_type = "Text")]
     pub scopes: String,
     pub expires_at: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub claims: Option<String>,
+    pub auth_time: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -19,6 +19,14 @@ pub struct Model {
     pub expires_at: DateTimeWithTimeZone,
     pub used: bool,
     pub created_at: DateTimeWithTimeZone,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub nonce: Option<String>,
+    pub max_age: Option<i64>,
+    pub auth_time: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub claims: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
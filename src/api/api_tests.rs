@@ -0,0 +1,539 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, http::StatusCode, test, web};
+    use jsonwebtoken::{Algorithm, Header, encode};
+    use sea_orm::{Database, DatabaseConnection};
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    use crate::api::services::dpop_service;
+    use crate::api::services::oauth_service::{AuthContext, TokenResponse, issue_tokens};
+    use crate::cache::{CompositeCache, MemoryCache};
+    use crate::config::{get_config, init_config};
+    use crate::runtime::server::configure_routes;
+    use crate::security::dpop::{DPOP_HEADER, DPOP_PROOF_TYPE, access_token_hash};
+    use crate::security::{ClientSecretManager, JwtManager, PasswordManager, SigningKey};
+    use crate::storage::{
+        ClientRepository, ClientUpdateFields, NewClient, SeaOrmBackend, TokenRepository,
+        UserRepository, run_migrations,
+    };
+
+    const PASSWORD: &str = "TestPassword123!";
+    const CLIENT_SECRET: &str = "test-client-secret";
+
+    /// 接口测试使用的共享状态
+    struct TestState {
+        db: Arc<DatabaseConnection>,
+        storage: Arc<SeaOrmBackend>,
+        cache: Arc<CompositeCache>,
+        jwt_manager: Arc<JwtManager>,
+    }
+
+    /// 创建内存数据库、缓存和 JWT 管理器
+    async fn setup_state() -> TestState {
+        init_config(None);
+
+        let db = Database::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        run_migrations(&db).await.expect("Failed to run migrations");
+        let db = Arc::new(db);
+
+        let l1 = Arc::new(MemoryCache::new(1000));
+        let l2 = Arc::new(MemoryCache::new(1000));
+        let cache = Arc::new(CompositeCache::new(l1, l2));
+
+        TestState {
+            storage: Arc::new(SeaOrmBackend::with_cache(db.clone(), cache.clone())),
+            db,
+            cache,
+            jwt_manager: Arc::new(JwtManager::new("test-secret".into())),
+        }
+    }
+
+    /// 按服务器配置初始化应用
+    macro_rules! init_app {
+        ($state:expr) => {{
+            let state = &$state;
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::new(get_config().clone()))
+                    .app_data(web::Data::new(state.db.clone()))
+                    .app_data(web::Data::new(state.storage.clone()))
+                    .app_data(web::Data::new(state.cache.clone()))
+                    .app_data(web::Data::new(state.jwt_manager.clone()))
+                    .configure(|cfg| {
+                        configure_routes(
+                            cfg,
+                            state.jwt_manager.clone(),
+                            state.cache.clone(),
+                            state.storage.clone(),
+                        )
+                    }),
+            )
+            .await
+        }};
+    }
+
+    /// 创建测试用户
+    async fn create_user(state: &TestState, username: &str) -> i64 {
+        let password_hash = PasswordManager::hash_password(PASSWORD).unwrap();
+        state
+            .storage
+            .create(
+                username,
+                &format!("{}@example.com", username),
+                &password_hash,
+            )
+            .await
+            .expect("Failed to create test user")
+            .id
+    }
+
+    /// 创建使用 client_secret_post 认证的机密客户端
+    async fn create_client(
+        state: &TestState,
+        client_id: &str,
+        token_exchange_policy: Option<&str>,
+    ) {
+        state
+            .storage
+            .create_client(NewClient {
+                client_id: client_id.to_string(),
+                client_secret: ClientSecretManager::hash_secret(CLIENT_SECRET).unwrap(),
+                name: client_id.to_string(),
+                redirect_uris: r#"["https://app.example/cb"]"#.to_string(),
+                allowed_scopes: r#"["openid", "profile"]"#.to_string(),
+                require_pkce: false,
+                token_endpoint_auth_method: "client_secret_post".to_string(),
+                jwks: None,
+                jwks_uri: None,
+                grant_types: None,
+                registration_access_token: None,
+                post_logout_redirect_uris: None,
+                backchannel_logout_uri: None,
+                response_modes: None,
+                require_pushed_authorization_requests: false,
+                require_signed_request_object: false,
+                token_exchange_policy: token_exchange_policy.map(str::to_string),
+                subject_type: "public".to_string(),
+                sector_identifier_uri: None,
+            })
+            .await
+            .expect("Failed to create client");
+    }
+
+    /// 按授权码流程的方式为客户端签发用户 Token
+    async fn issue_client_tokens(
+        state: &TestState,
+        client_id: &str,
+        user_id: i64,
+        scopes: &str,
+    ) -> TokenResponse {
+        let user = state.storage.find_by_id(user_id).await.unwrap().unwrap();
+        issue_tokens(
+            &state.storage,
+            &state.jwt_manager,
+            &state.cache,
+            client_id,
+            &user,
+            scopes,
+            AuthContext::default(),
+        )
+        .await
+        .expect("Failed to issue tokens")
+    }
+
+    /// 为请求签名 DPoP 证明（携带服务器签发的 nonce，访问资源时包含 ath）
+    async fn dpop_proof(
+        state: &TestState,
+        key: &SigningKey,
+        htm: &str,
+        path: &str,
+        access_token: Option<&str>,
+    ) -> String {
+        let mut header = Header::new(key.algorithm());
+        header.typ = Some(DPOP_PROOF_TYPE.to_string());
+        header.jwk =
+            Some(serde_json::from_value(serde_json::json!(key.public_jwk().unwrap())).unwrap());
+        let mut claims = serde_json::json!({
+            "jti": uuid::Uuid::new_v4().to_string(),
+            "htm": htm,
+            "htu": format!("{}{}", get_config().issuer(), path),
+            "iat": chrono::Utc::now().timestamp(),
+            "nonce": dpop_service::issue_nonce(&state.cache).await,
+        });
+        if let Some(access_token) = access_token {
+            claims["ath"] = serde_json::json!(access_token_hash(access_token));
+        }
+        encode(&header, &claims, key.encoding_key()).unwrap()
+    }
+
+    /// 登录并返回响应（包含 access_token 和 refresh_token）
+    macro_rules! login {
+        ($app:expr, $username:expr) => {{
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(serde_json::json!({"username": $username, "password": PASSWORD}))
+                .to_request();
+            let body: Value = test::call_and_read_body_json(&$app, req).await;
+            body
+        }};
+    }
+
+    /// 发送请求并返回状态码（中间件返回的错误转换为对应的响应状态）
+    macro_rules! status {
+        ($app:expr, $req:expr) => {{
+            match test::try_call_service(&$app, $req.to_request()).await {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            }
+        }};
+    }
+
+    /// 使用 Bearer token 请求 GET /api/user/me，返回状态码
+    macro_rules! get_profile {
+        ($app:expr, $token:expr) => {
+            status!(
+                $app,
+                test::TestRequest::get()
+                    .uri("/api/user/me")
+                    .insert_header(("Authorization", format!("Bearer {}", $token)))
+            )
+        };
+    }
+
+    #[actix_web::test]
+    async fn test_refresh_token_rejected_as_access_token() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        create_user(&state, "alice").await;
+
+        let tokens = login!(app, "alice");
+        let access_token = tokens["access_token"].as_str().unwrap();
+        let refresh_token = tokens["refresh_token"].as_str().unwrap();
+
+        // access token 可以访问用户接口，refresh token 不能
+        assert_eq!(get_profile!(app, access_token), StatusCode::OK);
+        assert_eq!(get_profile!(app, refresh_token), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_access_token_revokes_refresh_token() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let user_id = create_user(&state, "alice").await;
+        create_client(&state, "app", None).await;
+        let tokens = issue_client_tokens(&state, "app", user_id, "openid profile").await;
+        let refresh_token = tokens.refresh_token.unwrap();
+
+        // 1. 撤销 access token
+        let revoke = test::TestRequest::post()
+            .uri("/oauth/revoke")
+            .set_json(serde_json::json!({
+                "token": tokens.access_token,
+                "client_id": "app",
+                "client_secret": CLIENT_SECRET,
+            }));
+        assert_eq!(status!(app, revoke), StatusCode::OK);
+
+        // 2. 关联的 refresh token 被加入黑名单，不能再换取 Token
+        assert!(
+            state
+                .cache
+                .exists(&format!("blacklist:{}", refresh_token))
+                .await
+        );
+        let refresh = test::TestRequest::post()
+            .uri("/oauth/token")
+            .set_json(serde_json::json!({
+                "grant_type": "refresh_token",
+                "refresh_token": refresh_token,
+                "client_id": "app",
+                "client_secret": CLIENT_SECRET,
+            }));
+        assert_eq!(status!(app, refresh), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            get_profile!(app, tokens.access_token),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(get_profile!(app, refresh_token), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_introspect_reports_revoked_and_disabled_tokens() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let user_id = create_user(&state, "alice").await;
+        create_client(&state, "app", None).await;
+        create_client(&state, "resource", None).await;
+        let tokens = issue_client_tokens(&state, "app", user_id, "openid profile").await;
+        let second = issue_client_tokens(&state, "app", user_id, "openid profile").await;
+
+        let introspect = |token: &str| {
+            test::TestRequest::post()
+                .uri("/oauth/introspect")
+                .set_json(serde_json::json!({
+                    "token": token,
+                    "client_id": "resource",
+                    "client_secret": CLIENT_SECRET,
+                }))
+                .to_request()
+        };
+
+        // 1. 有效的 access token 和 refresh token
+        let body: Value =
+            test::call_and_read_body_json(&app, introspect(&tokens.access_token)).await;
+        assert_eq!(body["active"], true);
+        assert_eq!(body["client_id"], "app");
+        assert_eq!(body["scope"], "openid profile");
+        let refresh = tokens.refresh_token.clone().unwrap();
+        let body: Value = test::call_and_read_body_json(&app, introspect(&refresh)).await;
+        assert_eq!(body["token_type"], "refresh_token");
+
+        // 2. 撤销后 access token 和 refresh token 都不再有效
+        let revoke = test::TestRequest::post()
+            .uri("/oauth/revoke")
+            .set_json(serde_json::json!({
+                "token": refresh,
+                "client_id": "app",
+                "client_secret": CLIENT_SECRET,
+            }));
+        assert_eq!(status!(app, revoke), StatusCode::OK);
+        for token in [&tokens.access_token, &refresh] {
+            let body: Value = test::call_and_read_body_json(&app, introspect(token)).await;
+            assert_eq!(body, serde_json::json!({"active": false}));
+        }
+
+        // 3. 用户被禁用后其他 Token 同样无效
+        let body: Value =
+            test::call_and_read_body_json(&app, introspect(&second.access_token)).await;
+        assert_eq!(body["active"], true);
+        state.storage.disable_user(user_id).await.unwrap();
+        let body: Value =
+            test::call_and_read_body_json(&app, introspect(&second.access_token)).await;
+        assert_eq!(body["active"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_update_client_clears_fields_and_rotation_revokes_tokens() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let admin_id = create_user(&state, "admin").await;
+        state.storage.update_role(admin_id, "admin").await.unwrap();
+        let admin_token = login!(app, "admin")["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let user_id = create_user(&state, "alice").await;
+        create_client(&state, "app", None).await;
+        let client = state
+            .storage
+            .find_by_client_id("app")
+            .await
+            .unwrap()
+            .unwrap();
+        state
+            .storage
+            .update_client(
+                client,
+                ClientUpdateFields {
+                    backchannel_logout_uri: Some(Some("https://app.example/logout".into())),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // 1. 显式传 null 清除字段，省略的字段保持不变
+        let update = test::TestRequest::put()
+            .uri("/api/admin/clients/app")
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+            .set_json(serde_json::json!({"backchannel_logout_uri": null}));
+        assert_eq!(status!(app, update), StatusCode::OK);
+        let client = state
+            .storage
+            .find_by_client_id("app")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(client.backchannel_logout_uri, None);
+        assert_eq!(client.name, "app");
+
+        // 2. 轮换密钥撤销已签发的 Token
+        let tokens = issue_client_tokens(&state, "app", user_id, "openid profile").await;
+        let refresh_token = tokens.refresh_token.unwrap();
+        let rotate = test::TestRequest::post()
+            .uri("/api/admin/clients/app/rotate-secret")
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)));
+        assert_eq!(status!(app, rotate), StatusCode::OK);
+        assert!(
+            state
+                .storage
+                .find_access_token(&tokens.access_token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        for token in [&tokens.access_token, &refresh_token] {
+            assert!(state.cache.exists(&format!("blacklist:{}", token)).await);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_exchanged_token_with_audience_rejected() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let admin_id = create_user(&state, "admin").await;
+        state.storage.update_role(admin_id, "admin").await.unwrap();
+        let admin_token = login!(app, "admin")["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        create_client(
+            &state,
+            "gateway",
+            Some(r#"{"audiences": ["orders-api"], "allow_impersonation": true}"#),
+        )
+        .await;
+
+        // 1. 用管理员的 Token 换取下游服务的 Token
+        let exchange = test::TestRequest::post()
+            .uri("/oauth/token")
+            .set_json(serde_json::json!({
+                "grant_type": "urn:ietf:params:oauth:grant-type:token-exchange",
+                "subject_token": admin_token,
+                "subject_token_type": "urn:ietf:params:oauth:token-type:access_token",
+                "audience": "orders-api",
+                "client_id": "gateway",
+                "client_secret": CLIENT_SECRET,
+            }))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, exchange).await;
+        let exchanged = body["access_token"].as_str().unwrap();
+
+        // 2. 换取的 Token 不能访问本服务的用户接口和管理接口
+        assert_eq!(get_profile!(app, admin_token), StatusCode::OK);
+        assert_eq!(get_profile!(app, exchanged), StatusCode::UNAUTHORIZED);
+        let list_clients = test::TestRequest::get()
+            .uri("/api/admin/clients")
+            .insert_header(("Authorization", format!("Bearer {}", exchanged)));
+        assert_eq!(status!(app, list_clients), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_dpop_bound_token_requires_proof() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        create_user(&state, "alice").await;
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+
+        // 1. 携带 DPoP 证明登录，签发的 Token 绑定证明的公钥
+        let proof = dpop_proof(&state, &key, "POST", "/api/auth/login", None).await;
+        let req = test::TestRequest::post()
+            .uri("/api/auth/login")
+            .insert_header((DPOP_HEADER, proof))
+            .set_json(serde_json::json!({"username": "alice", "password": PASSWORD}))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["token_type"], "DPoP");
+        let access_token = body["access_token"].as_str().unwrap();
+
+        // 2. 不携带证明时被拒绝（无论使用 DPoP 还是 Bearer 方案）
+        let without_proof = test::TestRequest::get()
+            .uri("/api/user/me")
+            .insert_header(("Authorization", format!("DPoP {}", access_token)));
+        assert_eq!(status!(app, without_proof), StatusCode::UNAUTHORIZED);
+        assert_eq!(get_profile!(app, access_token), StatusCode::UNAUTHORIZED);
+
+        // 3. 携带有效证明时通过，同一证明不能重放
+        let proof = dpop_proof(&state, &key, "GET", "/api/user/me", Some(access_token)).await;
+        let with_proof = || {
+            test::TestRequest::get()
+                .uri("/api/user/me")
+                .insert_header(("Authorization", format!("DPoP {}", access_token)))
+                .insert_header((DPOP_HEADER, proof.clone()))
+        };
+        assert_eq!(status!(app, with_proof()), StatusCode::OK);
+        assert_eq!(status!(app, with_proof()), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_user_api_requires_first_party_token() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let user_id = create_user(&state, "alice").await;
+        create_client(&state, "app", None).await;
+        let first_party = login!(app, "alice")["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let third_party = issue_client_tokens(&state, "app", user_id, "openid profile")
+            .await
+            .access_token;
+
+        let requests = |token: &str| {
+            let auth = ("Authorization", format!("Bearer {}", token));
+            [
+                test::TestRequest::get()
+                    .uri("/api/user/me")
+                    .insert_header(auth.clone()),
+                test::TestRequest::put()
+                    .uri("/api/user/me")
+                    .insert_header(auth.clone())
+                    .set_json(serde_json::json!({"given_name": "Alice"})),
+                test::TestRequest::get()
+                    .uri("/api/user/authorizations")
+                    .insert_header(auth.clone()),
+                test::TestRequest::delete()
+                    .uri("/api/user/authorizations/app")
+                    .insert_header(auth),
+            ]
+        };
+
+        // 1. 第三方客户端获得的用户 Token 不能访问用户自助接口
+        for req in requests(&third_party) {
+            assert_eq!(status!(app, req), StatusCode::FORBIDDEN);
+        }
+        assert!(
+            state
+                .storage
+                .find_access_token(&third_party)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // 2. 第一方登录签发的 Token 可以访问
+        for req in requests(&first_party) {
+            assert!(status!(app, req).is_success());
+        }
+        let user = state.storage.find_by_id(user_id).await.unwrap().unwrap();
+        assert_eq!(user.given_name.as_deref(), Some("Alice"));
+    }
+
+    #[actix_web::test]
+    async fn test_userinfo_requires_openid_scope() {
+        let state = setup_state().await;
+        let app = init_app!(state);
+        let user_id = create_user(&state, "alice").await;
+        create_client(&state, "app", None).await;
+
+        let userinfo = |token: &str| {
+            test::TestRequest::get()
+                .uri("/oauth/userinfo")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+        };
+
+        // 1. 获得 openid scope 的 Token 可以访问 UserInfo
+        let tokens = issue_client_tokens(&state, "app", user_id, "openid profile").await;
+        assert_eq!(status!(app, userinfo(&tokens.access_token)), StatusCode::OK);
+
+        // 2. 未获得 openid scope 时返回 insufficient_scope
+        let tokens = issue_client_tokens(&state, "app", user_id, "profile").await;
+        let res = test::call_service(&app, userinfo(&tokens.access_token).to_request()).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let body: Value = test::read_body_json(res).await;
+        assert_eq!(body["error"], "insufficient_scope");
+    }
+}
@@ -7,6 +7,7 @@ use std::future::{Ready, ready};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::api::middleware::auth::{authenticate_token, dpop_challenge};
 use crate::cache::CompositeCache;
 use crate::errors::AppError;
 use crate::security::JwtManager;
@@ -81,22 +82,16 @@ where
         let storage = self.storage.clone();
         let service = self.service.clone();
 
-        // 提取 Authorization header
-        let token = match extract_bearer_token(&req) {
-            Ok(t) => t,
-            Err(e) => return Box::pin(async move { Err(e.into()) }),
-        };
-
         Box::pin(async move {
-            // 检查黑名单
-            if cache.exists(&format!("blacklist:{}", token)).await {
-                return Err(AppError::TokenExpired.into());
-            }
+            // 验证 access token（DPoP 绑定的 token 同时验证证明）
+            let claims = authenticate_token(req.request(), &jwt_manager, &cache)
+                .await
+                .map_err(dpop_challenge)?;
 
-            // 验证 JWT
-            let claims = jwt_manager
-                .verify_token(&token)
-                .map_err(|e| -> Error { e.into() })?;
+            // 客户端 Token（client_credentials）不代表任何用户
+            if claims.is_client() {
+                return Err(AppError::Unauthorized.into());
+            }
 
             // 从数据库查询用户以确认 role
             let user_id = claims
@@ -135,12 +130,3 @@ where
         })
     }
 }
-
-/// 从请求中提取 Bearer Token
-fn extract_bearer_token(req: &ServiceRequest) -> Result<String, AppError> {
-    req.headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|h| h.strip_prefix("Bearer ").map(|h_strip| h_strip.to_string()))
-        .ok_or(AppError::Unauthorized)
-}
@@ -1,5 +1,8 @@
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{self, HeaderValue};
 use actix_web::{
-    Error, HttpMessage,
+    Error, HttpMessage, HttpRequest, ResponseError,
     dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
 };
 use futures_util::future::LocalBoxFuture;
@@ -7,8 +10,12 @@ use std::future::{Ready, ready};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::api::services::dpop_service;
 use crate::cache::CompositeCache;
+use crate::config::get_config;
 use crate::errors::AppError;
+use crate::security::dpop::SUPPORTED_DPOP_ALGORITHMS;
+use crate::security::keys::algorithm_name;
 use crate::security::{Claims, JwtManager};
 
 /// JWT 认证中间件
@@ -67,23 +74,17 @@ where
         let cache = self.cache.clone();
         let service = self.service.clone();
 
-        // 提取 Authorization header
-        let token = match extract_bearer_token(&req) {
-            Ok(t) => t,
-            Err(e) => return Box::pin(async move { Err(e.into()) }),
-        };
-
         Box::pin(async move {
-            // 检查黑名单
-            if cache.exists(&format!("blacklist:{}", token)).await {
-                return Err(AppError::TokenExpired.into());
+            // 验证 access token（DPoP 绑定的 token 同时验证证明）
+            let claims = authenticate_token(req.request(), &jwt_manager, &cache)
+                .await
+                .map_err(dpop_challenge)?;
+
+            // 客户端 Token（client_credentials）不代表任何用户
+            if claims.is_client() {
+                return Err(AppError::Unauthorized.into());
             }
 
-            // 验证 JWT
-            let claims = jwt_manager
-                .verify_token(&token)
-                .map_err(|e| -> Error { e.into() })?;
-
             // 将 Claims 注入到请求扩展中
             req.extensions_mut().insert(claims);
 
@@ -93,13 +94,107 @@ where
     }
 }
 
-/// 从请求中提取 Bearer Token
-fn extract_bearer_token(req: &ServiceRequest) -> Result<String, AppError> {
-    req.headers()
-        .get("Authorization")
+/// Authorization 头中的 token 类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenScheme {
+    Bearer,
+    Dpop,
+}
+
+/// 从请求中提取 Bearer 或 DPoP Token
+fn extract_access_token(req: &HttpRequest) -> Result<(TokenScheme, String), AppError> {
+    let header = req
+        .headers()
+        .get(header::AUTHORIZATION)
         .and_then(|h| h.to_str().ok())
-        .and_then(|h| h.strip_prefix("Bearer ").map(|h_strip| h_strip.to_string()))
-        .ok_or(AppError::Unauthorized)
+        .ok_or(AppError::Unauthorized)?;
+
+    if let Some(token) = header.strip_prefix("Bearer ") {
+        Ok((TokenScheme::Bearer, token.to_string()))
+    } else if let Some(token) = header.strip_prefix("DPoP ") {
+        Ok((TokenScheme::Dpop, token.to_string()))
+    } else {
+        Err(AppError::Unauthorized)
+    }
+}
+
+/// 请求携带的 access token（Bearer 或 DPoP 方案）
+pub(crate) fn bearer_token(req: &HttpRequest) -> Result<String, AppError> {
+    extract_access_token(req).map(|(_, token)| token)
+}
+
+/// 验证请求携带的 access token 并返回其 Claims
+///
+/// 绑定了 DPoP 密钥的 token 必须使用 DPoP 方案，并携带由同一密钥签名的证明（RFC 9449 7）
+pub(crate) async fn authenticate_token(
+    req: &HttpRequest,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+) -> Result<Claims, AppError> {
+    let (scheme, token) = extract_access_token(req)?;
+
+    // 检查黑名单
+    if cache.exists(&format!("blacklist:{}", token)).await {
+        return Err(AppError::TokenExpired);
+    }
+
+    // 验证 JWT（refresh token 只能用于换取新 token）
+    let claims = jwt_manager.verify_token(&token)?;
+    if claims.is_refresh() {
+        return Err(AppError::InvalidToken);
+    }
+
+    // Token Exchange 为下游服务签发的 Token（aud 不是本服务）不能访问本服务的接口
+    if claims
+        .aud
+        .as_ref()
+        .is_some_and(|aud| *aud != get_config().issuer())
+    {
+        return Err(AppError::InvalidToken);
+    }
+
+    // 验证 DPoP 证明
+    match (scheme, &claims.cnf) {
+        (TokenScheme::Bearer, None) => {}
+        (TokenScheme::Dpop, Some(cnf)) => {
+            let jkt = dpop_service::check_proof(req, cache, Some(&token))
+                .await?
+                .ok_or_else(|| AppError::InvalidDpopProof("Missing DPoP proof".into()))?;
+            if jkt != cnf.jkt {
+                return Err(AppError::InvalidDpopProof(
+                    "DPoP key does not match the access token".into(),
+                ));
+            }
+        }
+        _ => return Err(AppError::InvalidToken),
+    }
+
+    Ok(claims)
+}
+
+/// 访问资源时的 DPoP 错误以 401 和 WWW-Authenticate 质询返回（RFC 9449 7.1）
+pub(crate) fn dpop_challenge(error: AppError) -> Error {
+    let code = match &error {
+        AppError::InvalidDpopProof(_) => "invalid_dpop_proof",
+        AppError::UseDpopNonce(_) => "use_dpop_nonce",
+        _ => return error.into(),
+    };
+
+    let algs = SUPPORTED_DPOP_ALGORITHMS
+        .iter()
+        .map(|alg| algorithm_name(*alg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut response = error.error_response();
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    if let Ok(value) = HeaderValue::from_str(&format!("DPoP error=\"{}\", algs=\"{}\"", code, algs))
+    {
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, value);
+    }
+
+    InternalError::from_response(error, response).into()
 }
 
 /// 从请求扩展中提取 Claims
@@ -1,3 +1,6 @@
 // API 层模块
 pub mod middleware;
 pub mod services;
+
+#[cfg(test)]
+mod api_tests;
@@ -2,8 +2,10 @@ use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::api::services::logout_service::end_user_sessions;
+use crate::cache::CompositeCache;
 use crate::errors::AppError;
-use crate::security::{Claims, PasswordManager};
+use crate::security::{Claims, JwtManager, PasswordManager};
 use crate::storage::repository::{Pagination, UserListFilter, UserUpdateFields};
 use crate::storage::{SeaOrmBackend, UserRepository};
 
@@ -250,6 +252,8 @@ pub async fn reset_password(
     req: HttpRequest,
     user_id: web::Path<i64>,
     storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
 ) -> Result<HttpResponse, AppError> {
     // 获取当前管理员信息
     let claims = req
@@ -280,6 +284,9 @@ pub async fn reset_password(
     };
     storage.update_user(*user_id, fields).await?;
 
+    // 结束用户的所有会话（使用旧密码登录签发的 Token 失效）
+    end_user_sessions(&storage, &jwt_manager, &cache, *user_id).await?;
+
     tracing::info!("Password reset for user {} by admin {}", user_id, admin_id);
 
     Ok(HttpResponse::Ok().json(ResetPasswordResponse { new_password }))
@@ -292,6 +299,8 @@ pub async fn update_status(
     user_id: web::Path<i64>,
     body: web::Json<UpdateStatusRequest>,
     storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
 ) -> Result<HttpResponse, AppError> {
     // 获取当前管理员信息
     let claims = req
@@ -316,6 +325,7 @@ pub async fn update_status(
         tracing::info!("User {} enabled by admin {}", user_id, admin_id);
     } else {
         storage.disable_user(*user_id).await?;
+        end_user_sessions(&storage, &jwt_manager, &cache, *user_id).await?;
         tracing::info!("User {} disabled by admin {}", user_id, admin_id);
     }
 
@@ -346,6 +356,8 @@ pub async fn delete_user(
     user_id: web::Path<i64>,
     query: web::Query<DeleteUserRequest>,
     storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
 ) -> Result<HttpResponse, AppError> {
     // 获取当前管理员信息
     let claims = req
@@ -379,7 +391,9 @@ pub async fn delete_user(
         }
     }
 
-    // 删除用户
+    // 结束用户的所有会话，再删除用户
+    end_user_sessions(&storage, &jwt_manager, &cache, *user_id).await?;
+
     let permanent = query.permanent.unwrap_or(false);
     if permanent {
         // 硬删除
@@ -1,10 +1,14 @@
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpRequest, HttpResponse, web};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::api::services::dpop_service;
+use crate::api::services::oauth_service::{AuthContext, issue_tokens, refresh_token_grant};
 use crate::cache::CompositeCache;
 use crate::errors::AppError;
 use crate::security::{JwtManager, PasswordManager};
+use crate::storage::repository::FIRST_PARTY_CLIENT_ID;
 use crate::storage::{SeaOrmBackend, UserRepository};
 
 #[derive(Debug, Deserialize)]
@@ -27,10 +31,16 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub access_token: String,
-    pub refresh_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub token_type: String,
     pub expires_in: i64,
 }
@@ -165,7 +175,9 @@ pub async fn register(
 }
 
 /// POST /api/auth/login
+/// 携带 DPoP 证明时签发绑定该公钥的 token
 pub async fn login(
+    http_req: HttpRequest,
     req: web::Json<LoginRequest>,
     storage: web::Data<Arc<SeaOrmBackend>>,
     jwt_manager: web::Data<Arc<JwtManager>>,
@@ -191,42 +203,64 @@ pub async fn login(
         return Err(AppError::InvalidCredentials);
     }
 
-    // 4. 更新登录信息
+    // 4. 验证 DPoP 证明（如果有）
+    let dpop_jkt = dpop_service::check_proof(&http_req, &cache, None).await?;
+
+    // 5. 更新登录信息
     let _ = storage.update_login_info(user.id).await; // 忽略错误，不影响登录
 
-    // 5. 读取认证策略配置（从数据库）
-    let auth_policy = storage.get_auth_policy_config().await?;
-
-    // 6. 生成 Token
-    let access_token = jwt_manager.generate_token(
-        user.id as i64,
-        auth_policy.access_token_expire,
-        Some(vec!["read".to_string(), "write".to_string()]),
-        &user.role,
-    )?;
-
-    let refresh_token = jwt_manager.generate_token(
-        user.id as i64,
-        auth_policy.refresh_token_expire,
-        Some(vec!["refresh".to_string()]),
-        &user.role,
-    )?;
-
-    // 7. 缓存 Token -> UserID 映射
-    cache
-        .set(
-            &format!("token:{}", access_token),
-            user.id.to_string(),
-            Some(auth_policy.access_token_expire as u64),
-        )
-        .await;
+    // 6. 签发并保存 Token
+    let tokens = issue_tokens(
+        &storage,
+        &jwt_manager,
+        &cache,
+        FIRST_PARTY_CLIENT_ID,
+        &user,
+        "read write",
+        AuthContext {
+            // 会话的认证时间随 Token 记录保存，授权端点据此判断 max_age
+            auth_time: Some(Utc::now().timestamp()),
+            dpop_jkt: dpop_jkt.as_deref(),
+            ..AuthContext::default()
+        },
+    )
+    .await?;
 
     tracing::info!("User logged in: {} (id: {})", user.username, user.id);
 
     Ok(HttpResponse::Ok().json(LoginResponse {
-        access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: auth_policy.access_token_expire,
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        token_type: tokens.token_type,
+        expires_in: tokens.expires_in,
+    }))
+}
+
+/// POST /api/auth/refresh
+/// 使用登录时获得的 refresh token 换取新 token
+pub async fn refresh(
+    http_req: HttpRequest,
+    req: web::Json<RefreshRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    let dpop_jkt = dpop_service::check_proof(&http_req, &cache, None).await?;
+    let tokens = refresh_token_grant(
+        &storage,
+        &jwt_manager,
+        &cache,
+        FIRST_PARTY_CLIENT_ID,
+        &req.refresh_token,
+        None,
+        dpop_jkt.as_deref(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(LoginResponse {
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        token_type: tokens.token_type,
+        expires_in: tokens.expires_in,
     }))
 }
@@ -0,0 +1,862 @@
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use jsonwebtoken::jwk::JwkSet;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use url::Url;
+
+use crate::api::services::oauth_service::{
+    PRIVATE_KEY_JWT, SUPPORTED_GRANT_TYPES, SUPPORTED_RESPONSE_MODES, TOKEN_ENDPOINT_AUTH_METHODS,
+    parse_scopes,
+};
+use crate::api::services::token_exchange_service::TokenExchangePolicy;
+use crate::api::services::token_service::blacklist_token;
+use crate::cache::CompositeCache;
+use crate::config::AppConfig;
+use crate::errors::AppError;
+use crate::security::pairwise::{
+    SUBJECT_TYPE_PAIRWISE, SUBJECT_TYPE_PUBLIC, SUPPORTED_SUBJECT_TYPES, sector_identifier,
+};
+use crate::security::{
+    Claims, ClientSecretManager, JwtManager, generate_client_id, generate_client_secret,
+};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::{ClientRepository, ClientUpdateFields, NewClient, SeaOrmBackend};
+use crate::utils::http_client;
+
+// ============= 请求/响应结构体 =============
+
+#[derive(Debug, Deserialize)]
+pub struct CreateClientRequest {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub post_logout_redirect_uris: Option<Vec<String>>,
+    pub backchannel_logout_uri: Option<String>,
+    pub allowed_scopes: Option<Vec<String>>,
+    pub token_endpoint_auth_method: Option<String>,
+    pub require_pkce: Option<bool>,
+    pub require_pushed_authorization_requests: Option<bool>,
+    pub require_signed_request_object: Option<bool>,
+    pub grant_types: Option<Vec<String>>,
+    pub response_modes: Option<Vec<String>>,
+    pub token_exchange_policy: Option<TokenExchangePolicy>,
+    pub subject_type: Option<String>,
+    pub sector_identifier_uri: Option<String>,
+    pub jwks: Option<serde_json::Value>,
+    pub jwks_uri: Option<String>,
+}
+
+/// 更新客户端请求：字段省略时不修改；可选字段显式传 `null` 时清除
+#[derive(Debug, Deserialize)]
+pub struct UpdateClientRequest {
+    pub name: Option<String>,
+    pub redirect_uris: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub post_logout_redirect_uris: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub backchannel_logout_uri: Option<Option<String>>,
+    pub allowed_scopes: Option<Vec<String>>,
+    pub token_endpoint_auth_method: Option<String>,
+    pub require_pkce: Option<bool>,
+    pub require_pushed_authorization_requests: Option<bool>,
+    pub require_signed_request_object: Option<bool>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub grant_types: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub response_modes: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub token_exchange_policy: Option<Option<TokenExchangePolicy>>,
+    pub subject_type: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub sector_identifier_uri: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub jwks: Option<Option<serde_json::Value>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub jwks_uri: Option<Option<String>>,
+}
+
+/// 区分省略的字段（`None`）和显式的 `null`（`Some(None)`）
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientInfo {
+    pub id: i64,
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub post_logout_redirect_uris: Vec<String>,
+    pub backchannel_logout_uri: Option<String>,
+    pub allowed_scopes: Vec<String>,
+    pub token_endpoint_auth_method: String,
+    pub require_pkce: bool,
+    pub require_pushed_authorization_requests: bool,
+    pub require_signed_request_object: bool,
+    /// 为空表示不限制授权类型
+    pub grant_types: Option<Vec<String>>,
+    /// 为空表示不限制授权响应模式
+    pub response_modes: Option<Vec<String>>,
+    /// 为空表示不允许使用 Token Exchange
+    pub token_exchange_policy: Option<TokenExchangePolicy>,
+    pub subject_type: String,
+    pub sector_identifier_uri: Option<String>,
+    pub jwks: Option<serde_json::Value>,
+    pub jwks_uri: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientListResponse {
+    pub clients: Vec<ClientInfo>,
+}
+
+/// 包含明文 client_secret 的响应（只在创建和轮换时返回一次）
+#[derive(Debug, Serialize)]
+pub struct ClientSecretResponse {
+    #[serde(flatten)]
+    pub client: ClientInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+}
+
+impl From<o_auth_clients::Model> for ClientInfo {
+    fn from(client: o_auth_clients::Model) -> Self {
+        Self {
+            id: client.id,
+            token_exchange_policy: TokenExchangePolicy::of(&client),
+            redirect_uris: serde_json::from_str(&client.redirect_uris).unwrap_or_default(),
+            post_logout_redirect_uris: client
+                .post_logout_redirect_uris
+                .as_deref()
+                .and_then(|uris| serde_json::from_str(uris).ok())
+                .unwrap_or_default(),
+            backchannel_logout_uri: client.backchannel_logout_uri,
+            allowed_scopes: parse_scopes(&client.allowed_scopes),
+            grant_types: client
+                .grant_types
+                .as_deref()
+                .and_then(|grant_types| serde_json::from_str(grant_types).ok()),
+            response_modes: client
+                .response_modes
+                .as_deref()
+                .and_then(|modes| serde_json::from_str(modes).ok()),
+            jwks: client
+                .jwks
+                .as_deref()
+                .and_then(|jwks| serde_json::from_str(jwks).ok()),
+            client_id: client.client_id,
+            name: client.name,
+            token_endpoint_auth_method: client.token_endpoint_auth_method,
+            require_pkce: client.require_pkce,
+            require_pushed_authorization_requests: client.require_pushed_authorization_requests,
+            require_signed_request_object: client.require_signed_request_object,
+            subject_type: client.subject_type,
+            sector_identifier_uri: client.sector_identifier_uri,
+            jwks_uri: client.jwks_uri,
+            created_at: client.created_at.to_rfc3339(),
+        }
+    }
+}
+
+// ============= 处理函数 =============
+
+/// GET /api/admin/clients
+/// 获取客户端列表（不包含 client_secret）
+pub async fn list_clients(
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    let clients = storage
+        .list_clients()
+        .await?
+        .into_iter()
+        .map(ClientInfo::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ClientListResponse { clients }))
+}
+
+/// GET /api/admin/clients/{client_id}
+/// 获取客户端详情
+pub async fn get_client(
+    client_id: web::Path<String>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    let client = storage
+        .find_by_client_id(&client_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(HttpResponse::Ok().json(ClientInfo::from(client)))
+}
+
+/// POST /api/admin/clients
+/// 创建客户端（自动生成 client_id 和 client_secret）
+pub async fn create_client(
+    req: HttpRequest,
+    body: web::Json<CreateClientRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    let admin_id = admin_id(&req)?;
+    let body = body.into_inner();
+
+    // 1. 验证客户端元数据
+    let name = validate_name(&body.name)?;
+    let redirect_uris = validate_redirect_uris(&body.redirect_uris)?;
+    let post_logout_redirect_uris = body
+        .post_logout_redirect_uris
+        .as_deref()
+        .map(validate_post_logout_redirect_uris)
+        .transpose()?
+        .flatten();
+    let backchannel_logout_uri = body
+        .backchannel_logout_uri
+        .map(validate_backchannel_logout_uri)
+        .transpose()?;
+    let allowed_scopes = match body.allowed_scopes {
+        Some(scopes) => validate_allowed_scopes(&scopes, &config.auth.supported_scopes)?,
+        None => serde_json::to_string(&config.auth.supported_scopes).unwrap_or_default(),
+    };
+    let method = body
+        .token_endpoint_auth_method
+        .unwrap_or_else(|| "client_secret_basic".to_string());
+    let grant_types = body
+        .grant_types
+        .as_deref()
+        .map(validate_grant_types)
+        .transpose()?;
+    let response_modes = body
+        .response_modes
+        .as_deref()
+        .map(validate_response_modes)
+        .transpose()?;
+    let token_exchange_policy = body
+        .token_exchange_policy
+        .as_ref()
+        .map(validate_token_exchange_policy)
+        .transpose()?;
+    let (jwks, jwks_uri) = validate_key_set(body.jwks, body.jwks_uri)?;
+    validate_auth_method(&method, jwks.as_deref(), jwks_uri.as_deref())?;
+    let subject_type = body
+        .subject_type
+        .unwrap_or_else(|| SUBJECT_TYPE_PUBLIC.to_string());
+    validate_subject_type(
+        &subject_type,
+        body.sector_identifier_uri.as_deref(),
+        &body.redirect_uris,
+    )
+    .await?;
+
+    // 2. 生成凭证（公共客户端和 private_key_jwt 客户端不使用 secret）
+    let client_secret = uses_client_secret(&method).then(generate_client_secret);
+    let secret_hash = match &client_secret {
+        Some(secret) => ClientSecretManager::hash_secret(secret)?,
+        None => String::new(),
+    };
+
+    // 3. 保存客户端（公共客户端必须使用 PKCE）
+    let client = storage
+        .create_client(NewClient {
+            client_id: generate_client_id(),
+            client_secret: secret_hash,
+            name,
+            redirect_uris,
+            allowed_scopes,
+            require_pkce: body.require_pkce.unwrap_or(false) || method == "none",
+            token_endpoint_auth_method: method,
+            jwks,
+            jwks_uri,
+            grant_types,
+            registration_access_token: None,
+            post_logout_redirect_uris,
+            backchannel_logout_uri,
+            response_modes,
+            require_pushed_authorization_requests: body
+                .require_pushed_authorization_requests
+                .unwrap_or(false),
+            require_signed_request_object: body.require_signed_request_object.unwrap_or(false),
+            token_exchange_policy,
+            subject_type,
+            sector_identifier_uri: body.sector_identifier_uri,
+        })
+        .await?;
+
+    // 4. 记录审计日志
+    let client = ClientInfo::from(client);
+    storage
+        .log_config_change(
+            &audit_key(&client.client_id),
+            None,
+            Some(audit_value(&client)),
+            admin_id,
+            "create",
+        )
+        .await?;
+
+    tracing::info!("Client {} created by admin {}", client.client_id, admin_id);
+
+    Ok(HttpResponse::Created().json(ClientSecretResponse {
+        client,
+        client_secret,
+    }))
+}
+
+/// PUT /api/admin/clients/{client_id}
+/// 更新客户端信息（只更新提供的字段）
+pub async fn update_client(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    body: web::Json<UpdateClientRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    let admin_id = admin_id(&req)?;
+    let body = body.into_inner();
+
+    let existing = storage
+        .find_by_client_id(&client_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // 1. 验证提供的字段
+    let mut fields = ClientUpdateFields {
+        name: body.name.as_deref().map(validate_name).transpose()?,
+        redirect_uris: body
+            .redirect_uris
+            .as_deref()
+            .map(validate_redirect_uris)
+            .transpose()?,
+        post_logout_redirect_uris: body
+            .post_logout_redirect_uris
+            .as_ref()
+            .map(|uris| {
+                uris.as_deref()
+                    .map(validate_post_logout_redirect_uris)
+                    .transpose()
+                    .map(Option::flatten)
+            })
+            .transpose()?,
+        backchannel_logout_uri: body
+            .backchannel_logout_uri
+            .clone()
+            .map(|uri| uri.map(validate_backchannel_logout_uri).transpose())
+            .transpose()?,
+        allowed_scopes: body
+            .allowed_scopes
+            .as_deref()
+            .map(|scopes| validate_allowed_scopes(scopes, &config.auth.supported_scopes))
+            .transpose()?,
+        require_pkce: body.require_pkce,
+        require_pushed_authorization_requests: body.require_pushed_authorization_requests,
+        require_signed_request_object: body.require_signed_request_object,
+        grant_types: body
+            .grant_types
+            .as_ref()
+            .map(|types| types.as_deref().map(validate_grant_types).transpose())
+            .transpose()?,
+        response_modes: body
+            .response_modes
+            .as_ref()
+            .map(|modes| modes.as_deref().map(validate_response_modes).transpose())
+            .transpose()?,
+        token_exchange_policy: body
+            .token_exchange_policy
+            .as_ref()
+            .map(|policy| {
+                policy
+                    .as_ref()
+                    .map(validate_token_exchange_policy)
+                    .transpose()
+            })
+            .transpose()?,
+        ..Default::default()
+    };
+
+    // jwks 和 jwks_uri 互斥，只提供其中一个时另一个被清除
+    if body.jwks.is_some() || body.jwks_uri.is_some() {
+        let (jwks, jwks_uri) = validate_key_set(body.jwks.flatten(), body.jwks_uri.flatten())?;
+        fields.jwks = Some(jwks);
+        fields.jwks_uri = Some(jwks_uri);
+    }
+
+    let method = body
+        .token_endpoint_auth_method
+        .clone()
+        .unwrap_or_else(|| existing.token_endpoint_auth_method.clone());
+    validate_auth_method(
+        &method,
+        fields
+            .jwks
+            .clone()
+            .unwrap_or(existing.jwks.clone())
+            .as_deref(),
+        fields
+            .jwks_uri
+            .clone()
+            .unwrap_or(existing.jwks_uri.clone())
+            .as_deref(),
+    )?;
+    if method == "none" {
+        fields.require_pkce = Some(true);
+    }
+    fields.token_endpoint_auth_method = body.token_endpoint_auth_method;
+
+    // pairwise 客户端的扇区由 sector_identifier_uri 或 redirect_uris 决定，任一改变时重新验证
+    if body.subject_type.is_some()
+        || body.sector_identifier_uri.is_some()
+        || body.redirect_uris.is_some()
+    {
+        let subject_type = body
+            .subject_type
+            .clone()
+            .unwrap_or_else(|| existing.subject_type.clone());
+        let sector_identifier_uri = body
+            .sector_identifier_uri
+            .clone()
+            .unwrap_or_else(|| existing.sector_identifier_uri.clone());
+        let redirect_uris = body
+            .redirect_uris
+            .clone()
+            .unwrap_or_else(|| serde_json::from_str(&existing.redirect_uris).unwrap_or_default());
+        validate_subject_type(
+            &subject_type,
+            sector_identifier_uri.as_deref(),
+            &redirect_uris,
+        )
+        .await?;
+        fields.subject_type = body.subject_type;
+        fields.sector_identifier_uri = body.sector_identifier_uri;
+    }
+
+    // 2. 改为使用 secret 的认证方式且尚无 secret 时生成新的 secret
+    let client_secret = (uses_client_secret(&method) && existing.client_secret.is_empty())
+        .then(generate_client_secret);
+    if let Some(secret) = &client_secret {
+        storage
+            .update_client_secret(
+                &existing.client_id,
+                &ClientSecretManager::hash_secret(secret)?,
+            )
+            .await?;
+    }
+
+    // 3. 保存并清除缓存的客户端公钥集
+    let old_value = audit_value(&ClientInfo::from(existing.clone()));
+    let client = ClientInfo::from(storage.update_client(existing, fields).await?);
+    cache
+        .delete(&format!("client_jwks:{}", client.client_id))
+        .await;
+
+    // 4. 记录审计日志
+    storage
+        .log_config_change(
+            &audit_key(&client.client_id),
+            Some(old_value),
+            Some(audit_value(&client)),
+            admin_id,
+            "update",
+        )
+        .await?;
+
+    tracing::info!("Client {} updated by admin {}", client.client_id, admin_id);
+
+    Ok(HttpResponse::Ok().json(ClientSecretResponse {
+        client,
+        client_secret,
+    }))
+}
+
+/// POST /api/admin/clients/{client_id}/rotate-secret
+/// 轮换客户端密钥（旧密钥立即失效），并撤销已签发给该客户端的 Token
+pub async fn rotate_secret(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    let admin_id = admin_id(&req)?;
+
+    let client = storage
+        .find_by_client_id(&client_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    if !uses_client_secret(&client.token_endpoint_auth_method) {
+        return Err(AppError::BadRequest(format!(
+            "Client using {} has no client_secret",
+            client.token_endpoint_auth_method
+        )));
+    }
+
+    // 1. 生成并保存新密钥
+    let client_secret = generate_client_secret();
+    storage
+        .update_client_secret(
+            &client.client_id,
+            &ClientSecretManager::hash_secret(&client_secret)?,
+        )
+        .await?;
+
+    // 2. 撤销已签发的 Token：密钥可能已泄露，用旧密钥换取的 Token 同样不可信
+    let tokens = storage.revoke_client_tokens(&client.client_id).await?;
+    for token in &tokens {
+        blacklist_token(&jwt_manager, &cache, token).await;
+    }
+
+    // 3. 记录审计日志（不记录密钥本身）
+    storage
+        .log_config_change(
+            &audit_key(&client.client_id),
+            None,
+            None,
+            admin_id,
+            "rotate_secret",
+        )
+        .await?;
+
+    tracing::info!(
+        "Client {} secret rotated by admin {} ({} tokens revoked)",
+        client.client_id,
+        admin_id,
+        tokens.len()
+    );
+
+    Ok(HttpResponse::Ok().json(ClientSecretResponse {
+        client: ClientInfo::from(client),
+        client_secret: Some(client_secret),
+    }))
+}
+
+/// DELETE /api/admin/clients/{client_id}
+/// 删除客户端，并撤销其签发的所有 Token
+pub async fn delete_client(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    let admin_id = admin_id(&req)?;
+
+    let client = storage
+        .find_by_client_id(&client_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // 1. 删除客户端及相关数据
+    let tokens = storage.delete_client(&client.client_id).await?;
+
+    // 2. 已签发的 JWT 加入黑名单直到过期
+    for token in &tokens {
+        blacklist_token(&jwt_manager, &cache, token).await;
+    }
+    cache
+        .delete(&format!("client_jwks:{}", client.client_id))
+        .await;
+
+    // 3. 记录审计日志
+    let client = ClientInfo::from(client);
+    storage
+        .log_config_change(
+            &audit_key(&client.client_id),
+            Some(audit_value(&client)),
+            None,
+            admin_id,
+            "delete",
+        )
+        .await?;
+
+    tracing::info!(
+        "Client {} deleted by admin {} ({} tokens revoked)",
+        client.client_id,
+        admin_id,
+        tokens.len()
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// ============= 校验函数 =============
+
+/// 验证 redirect_uri：绝对 URL、不含 fragment 和通配符；
+/// http 只允许本机回环地址，原生应用可以使用自定义 scheme（RFC 8252）
+pub(crate) fn validate_redirect_uri(uri: &str) -> Result<(), AppError> {
+    let invalid =
+        |reason: &str| AppError::BadRequest(format!("Invalid redirect_uri {}: {}", uri, reason));
+
+    let url = Url::parse(uri).map_err(|e| invalid(&e.to_string()))?;
+    if url.fragment().is_some() {
+        return Err(invalid("must not contain a fragment"));
+    }
+    if uri.contains('*') {
+        return Err(invalid("wildcards are not allowed"));
+    }
+
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" => match url.host_str() {
+            Some("localhost" | "127.0.0.1" | "[::1]") => Ok(()),
+            _ => Err(invalid("http is only allowed for loopback addresses")),
+        },
+        "javascript" | "data" | "file" | "vbscript" => Err(invalid("scheme is not allowed")),
+        scheme if scheme.contains('.') => Ok(()),
+        _ => Err(invalid("custom schemes must use reverse domain notation")),
+    }
+}
+
+/// 验证 redirect_uris 列表，返回 JSON 数组字符串
+pub(crate) fn validate_redirect_uris(uris: &[String]) -> Result<String, AppError> {
+    if uris.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one redirect_uri is required".into(),
+        ));
+    }
+    for uri in uris {
+        validate_redirect_uri(uri)?;
+    }
+    Ok(serde_json::to_string(uris).unwrap_or_default())
+}
+
+/// 验证登出后的重定向地址（规则与 redirect_uri 相同），空列表返回 None
+pub(crate) fn validate_post_logout_redirect_uris(
+    uris: &[String],
+) -> Result<Option<String>, AppError> {
+    if uris.is_empty() {
+        return Ok(None);
+    }
+    for uri in uris {
+        validate_redirect_uri(uri)?;
+    }
+    Ok(Some(serde_json::to_string(uris).unwrap_or_default()))
+}
+
+/// Back-Channel Logout 通知地址：不含 fragment、指向公网主机的 https URL
+pub(crate) fn validate_backchannel_logout_uri(uri: String) -> Result<String, AppError> {
+    let valid = http_client::validate_url(&uri).is_ok_and(|url| url.fragment().is_none());
+    if !valid {
+        return Err(AppError::BadRequest(format!(
+            "Invalid backchannel_logout_uri: {}",
+            uri
+        )));
+    }
+    Ok(uri)
+}
+
+/// 客户端名称不能为空
+pub(crate) fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::BadRequest(
+            "Client name must be 1-100 characters".into(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// 客户端可申请的 scope 必须是服务器支持的 scope，返回 JSON 数组字符串
+pub(crate) fn validate_allowed_scopes(
+    scopes: &[String],
+    supported: &[String],
+) -> Result<String, AppError> {
+    if let Some(scope) = scopes.iter().find(|scope| !supported.contains(scope)) {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported scope: {}",
+            scope
+        )));
+    }
+    Ok(serde_json::to_string(scopes).unwrap_or_default())
+}
+
+/// 授权类型必须是 Token 端点支持的类型，返回 JSON 数组字符串
+pub(crate) fn validate_grant_types(grant_types: &[String]) -> Result<String, AppError> {
+    if grant_types.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one grant_type is required".into(),
+        ));
+    }
+    if let Some(grant_type) = grant_types
+        .iter()
+        .find(|grant_type| !SUPPORTED_GRANT_TYPES.contains(&grant_type.as_str()))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported grant_type: {}",
+            grant_type
+        )));
+    }
+    Ok(serde_json::to_string(grant_types).unwrap_or_default())
+}
+
+/// 授权响应模式必须是授权端点支持的模式，返回 JSON 数组字符串
+fn validate_response_modes(response_modes: &[String]) -> Result<String, AppError> {
+    if response_modes.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one response_mode is required".into(),
+        ));
+    }
+    if let Some(mode) = response_modes
+        .iter()
+        .find(|mode| !SUPPORTED_RESPONSE_MODES.contains(&mode.as_str()))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported response_mode: {}",
+            mode
+        )));
+    }
+    Ok(serde_json::to_string(response_modes).unwrap_or_default())
+}
+
+/// 验证 Token Exchange 策略，返回 JSON 字符串
+fn validate_token_exchange_policy(policy: &TokenExchangePolicy) -> Result<String, AppError> {
+    if let Some(audience) = policy
+        .audiences
+        .iter()
+        .find(|audience| audience.is_empty() || audience.contains(char::is_whitespace))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Invalid token exchange audience: {:?}",
+            audience
+        )));
+    }
+    serde_json::to_string(policy)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize policy: {}", e)))
+}
+
+/// 验证 subject_type 及 sector_identifier_uri（OpenID Connect Core 8.1、Registration 5）
+///
+/// sector_identifier_uri 必须是指向公网主机的 https URL，其内容为包含全部 redirect_uri 的 JSON 数组；
+/// pairwise 客户端未登记 sector_identifier_uri 时，所有 redirect_uri 必须使用同一主机名
+pub(crate) async fn validate_subject_type(
+    subject_type: &str,
+    sector_identifier_uri: Option<&str>,
+    redirect_uris: &[String],
+) -> Result<(), AppError> {
+    if !SUPPORTED_SUBJECT_TYPES.contains(&subject_type) {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported subject_type: {}",
+            subject_type
+        )));
+    }
+
+    if let Some(uri) = sector_identifier_uri {
+        if http_client::validate_url(uri).is_err() {
+            return Err(AppError::BadRequest(
+                "sector_identifier_uri must be an https URL of a public host".into(),
+            ));
+        }
+
+        let response = http_client::get(uri).await.map_err(|e| {
+            AppError::BadRequest(format!("Failed to fetch sector_identifier_uri: {}", e))
+        })?;
+        let listed = response
+            .is_success()
+            .then(|| serde_json::from_slice::<Vec<String>>(&response.body).ok())
+            .flatten()
+            .ok_or(AppError::BadRequest(
+                "sector_identifier_uri must return a JSON array of redirect URIs".into(),
+            ))?;
+        if let Some(uri) = redirect_uris.iter().find(|uri| !listed.contains(uri)) {
+            return Err(AppError::BadRequest(format!(
+                "redirect_uri {} is not listed in sector_identifier_uri",
+                uri
+            )));
+        }
+    }
+
+    if subject_type == SUBJECT_TYPE_PAIRWISE
+        && sector_identifier(sector_identifier_uri, redirect_uris).is_none()
+    {
+        return Err(AppError::BadRequest(
+            "pairwise clients require sector_identifier_uri unless all redirect_uris share one host"
+                .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 验证客户端公钥集，返回 (jwks JSON 字符串, jwks_uri)
+pub(crate) fn validate_key_set(
+    jwks: Option<serde_json::Value>,
+    jwks_uri: Option<String>,
+) -> Result<(Option<String>, Option<String>), AppError> {
+    if jwks.is_some() && jwks_uri.is_some() {
+        return Err(AppError::BadRequest(
+            "jwks and jwks_uri cannot be used together".into(),
+        ));
+    }
+
+    let jwks = jwks
+        .map(|jwks| {
+            serde_json::from_value::<JwkSet>(jwks.clone())
+                .map(|_| jwks.to_string())
+                .map_err(|e| AppError::BadRequest(format!("Invalid jwks: {}", e)))
+        })
+        .transpose()?;
+
+    if let Some(uri) = &jwks_uri
+        && http_client::validate_url(uri).is_err()
+    {
+        return Err(AppError::BadRequest(
+            "jwks_uri must be an https URL of a public host".into(),
+        ));
+    }
+
+    Ok((jwks, jwks_uri))
+}
+
+/// 验证认证方式（private_key_jwt 需要登记公钥集）
+pub(crate) fn validate_auth_method(
+    method: &str,
+    jwks: Option<&str>,
+    jwks_uri: Option<&str>,
+) -> Result<(), AppError> {
+    if !TOKEN_ENDPOINT_AUTH_METHODS.contains(&method) {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported token_endpoint_auth_method: {}",
+            method
+        )));
+    }
+    if method == PRIVATE_KEY_JWT && jwks.is_none() && jwks_uri.is_none() {
+        return Err(AppError::BadRequest(
+            "private_key_jwt requires jwks or jwks_uri".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// 认证方式是否使用 client_secret
+pub(crate) fn uses_client_secret(method: &str) -> bool {
+    method == "client_secret_basic" || method == "client_secret_post"
+}
+
+// ============= 辅助函数 =============
+
+/// 从请求扩展中提取管理员 ID（由 AdminOnly 中间件注入）
+fn admin_id(req: &HttpRequest) -> Result<i64, AppError> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppError::Unauthorized)?;
+
+    claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))
+}
+
+/// 审计日志中客户端的配置键
+fn audit_key(client_id: &str) -> String {
+    format!("client:{}", client_id)
+}
+
+/// 审计日志中记录的客户端配置（不包含 client_secret）
+fn audit_value(client: &ClientInfo) -> String {
+    serde_json::to_string(client).unwrap_or_default()
+}
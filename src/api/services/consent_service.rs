@@ -0,0 +1,214 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::api::services::oauth_service::{
+    AuthorizationResponse, PendingAuthorization, authenticated_user_id, issue_authorization_code,
+    parse_scopes,
+};
+use crate::cache::CompositeCache;
+use crate::errors::AppError;
+use crate::security::claims::ClaimsRequest;
+use crate::security::{JwtManager, generate_random_token};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::{ClientRepository, SeaOrmBackend};
+
+/// consent_ticket 有效期（秒）
+const CONSENT_TICKET_EXPIRE: u64 = 600;
+
+#[derive(Debug, Serialize)]
+pub struct ConsentRequiredResponse {
+    pub consent_required: bool,
+    pub consent_ticket: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+    /// 通过 claims 参数单独请求的用户 claims
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub claims: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ui_locales: Option<String>,
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsentRequest {
+    pub consent_ticket: String,
+    pub approve: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsentResponse {
+    pub redirect_to: String,
+    /// response_mode=form_post 时需要以表单 POST 到 redirect_to 的参数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form_params: Option<BTreeMap<String, String>>,
+}
+
+impl From<AuthorizationResponse> for ConsentResponse {
+    fn from(response: AuthorizationResponse) -> Self {
+        let form_params = response.is_form_post().then(|| {
+            response
+                .form_params()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        });
+
+        Self {
+            redirect_to: response.redirect_url(),
+            form_params,
+        }
+    }
+}
+
+/// POST /oauth/authorize/consent
+/// 登录用户同意或拒绝授权请求
+pub async fn consent(
+    req: HttpRequest,
+    body: web::Json<ConsentRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证用户身份
+    let user_id = authenticated_user_id(&req, &jwt_manager, &cache).await?;
+
+    // 2. 取出暂存的授权请求（只能使用一次，且只能由发起请求的用户处理）
+    let key = format!("consent:{}", body.consent_ticket);
+    let invalid_ticket = || AppError::BadRequest("Invalid or expired consent_ticket".into());
+    let pending = cache
+        .get(&key)
+        .await
+        .and_then(|value| serde_json::from_str::<PendingAuthorization>(&value).ok())
+        .filter(|pending| pending.user_id == user_id)
+        .ok_or_else(invalid_ticket)?;
+    // 并发提交同一 ticket 时只有一个请求能取到
+    if cache.take(&key).await.is_none() {
+        return Err(invalid_ticket());
+    }
+
+    let client = storage
+        .find_by_client_id(&pending.client_id)
+        .await?
+        .ok_or(AppError::InvalidClient)?;
+
+    // 3. 用户拒绝：按 RFC 6749 4.1.2.1 返回 access_denied
+    if !body.approve {
+        tracing::info!(
+            "Consent denied for client: {} user: {}",
+            client.name,
+            user_id
+        );
+
+        let response = AuthorizationResponse::error(
+            &pending.redirect_uri,
+            pending.response_mode.as_deref(),
+            "access_denied",
+            pending.state.as_deref(),
+        );
+        return Ok(HttpResponse::Ok().json(ConsentResponse::from(response)));
+    }
+
+    // 4. 保存同意记录（与之前已同意的 scope 合并）
+    let mut scopes = match storage.find_consent(user_id, &client.client_id).await? {
+        Some(existing) => parse_scopes(&existing.scopes),
+        None => vec![],
+    };
+    for scope in consent_scopes(&pending) {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    storage
+        .save_consent(user_id, &client.client_id, &scopes.join(" "))
+        .await?;
+
+    tracing::info!(
+        "Consent granted for client: {} user: {}",
+        client.name,
+        user_id
+    );
+
+    // 5. 签发授权码
+    let response = issue_authorization_code(&storage, &cache, &client, &pending).await?;
+
+    Ok(HttpResponse::Ok().json(ConsentResponse::from(response)))
+}
+
+/// 用户之前的同意是否已覆盖本次请求的全部 scope（包括 claims 参数请求的 claims 所属的 scope）
+pub(crate) async fn has_consent(
+    storage: &SeaOrmBackend,
+    pending: &PendingAuthorization,
+) -> Result<bool, AppError> {
+    let Some(consent) = storage
+        .find_consent(pending.user_id, &pending.client_id)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    let granted = parse_scopes(&consent.scopes);
+    Ok(consent_scopes(pending)
+        .iter()
+        .all(|scope| granted.contains(scope)))
+}
+
+/// 需要用户同意的 scope：授予的 scope 加上 claims 参数请求的 claims 所属的 scope
+fn consent_scopes(pending: &PendingAuthorization) -> Vec<String> {
+    let mut scopes = parse_scopes(&pending.scope);
+    for scope in claims_request(pending)
+        .scopes()
+        .into_iter()
+        .map(str::to_string)
+    {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    scopes
+}
+
+/// 授权请求中的 claims 参数
+fn claims_request(pending: &PendingAuthorization) -> ClaimsRequest {
+    pending
+        .claims
+        .as_deref()
+        .and_then(ClaimsRequest::parse)
+        .unwrap_or_default()
+}
+
+/// 暂存授权请求，返回同意页面所需的客户端名称、scope 和单独请求的 claims
+pub(crate) async fn request_consent(
+    cache: &CompositeCache,
+    client: &o_auth_clients::Model,
+    pending: PendingAuthorization,
+) -> Result<HttpResponse, AppError> {
+    let consent_ticket = generate_random_token(32);
+    let value = serde_json::to_string(&pending)
+        .map_err(|e| AppError::Internal(format!("Failed to store consent request: {}", e)))?;
+
+    cache
+        .set(
+            &format!("consent:{}", consent_ticket),
+            value,
+            Some(CONSENT_TICKET_EXPIRE),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(ConsentRequiredResponse {
+        consent_required: true,
+        consent_ticket,
+        client_id: client.client_id.clone(),
+        client_name: client.name.clone(),
+        scopes: parse_scopes(&pending.scope),
+        claims: claims_request(&pending)
+            .requested_claims()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        ui_locales: pending.ui_locales.clone(),
+        expires_in: CONSENT_TICKET_EXPIRE,
+    }))
+}
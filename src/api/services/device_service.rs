@@ -0,0 +1,324 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::services::oauth_service::{
+    AuthContext, ClientCredentials, TokenResponse, authenticate_client, authenticated_session,
+    authenticated_user_id, check_grant_type, grant_scopes, issue_tokens, parse_scopes,
+};
+use crate::cache::CompositeCache;
+use crate::config::AppConfig;
+use crate::errors::AppError;
+use crate::security::{JwtManager, generate_random_token, generate_user_code, normalize_user_code};
+use crate::storage::entities::{device_codes, o_auth_clients};
+use crate::storage::{
+    ClientRepository, DEVICE_CODE_APPROVED, DEVICE_CODE_DENIED, DEVICE_CODE_PENDING, SeaOrmBackend,
+    UserRepository,
+};
+
+/// 设备授权 grant_type（RFC 8628）
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// device_code 有效期（秒）
+const DEVICE_CODE_EXPIRE: i64 = 600;
+
+/// 默认最小轮询间隔（秒）
+const DEVICE_CODE_INTERVAL: i64 = 5;
+
+/// 收到 slow_down 后轮询间隔的增量（秒）
+const SLOW_DOWN_INCREMENT: i64 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceAuthorizationRequest {
+    #[serde(flatten)]
+    pub credentials: ClientCredentials,
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceLookupQuery {
+    pub user_code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceLookupResponse {
+    pub user_code: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceVerifyRequest {
+    pub user_code: String,
+    pub approve: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceVerifyResponse {
+    pub status: String,
+}
+
+/// POST /oauth/device_authorization
+/// 设备发起授权请求，获取 device_code 和 user_code
+pub async fn device_authorization(
+    http_req: HttpRequest,
+    req: web::Json<DeviceAuthorizationRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证客户端身份及其是否允许使用设备授权
+    let client = authenticate_client(&storage, &cache, &http_req, &req.credentials).await?;
+    check_grant_type(&client, DEVICE_CODE_GRANT_TYPE)?;
+
+    // 2. 确定授予的 scope
+    let scopes = grant_scopes(&client, req.scope.as_deref(), &config.auth.supported_scopes)?;
+
+    // 3. 清理过期请求后生成 user_code（避免与未过期的请求冲突）
+    storage.delete_expired_device_codes().await?;
+
+    let mut user_code = generate_user_code();
+    while storage
+        .find_device_code_by_user_code(&user_code)
+        .await?
+        .is_some()
+    {
+        user_code = generate_user_code();
+    }
+
+    // 4. 生成 device_code 并保存
+    let device_code = generate_random_token(48);
+    storage
+        .create_device_code(
+            &device_code,
+            &user_code,
+            &client.client_id,
+            &scopes.join(" "),
+            DEVICE_CODE_INTERVAL,
+            Utc::now() + Duration::seconds(DEVICE_CODE_EXPIRE),
+        )
+        .await?;
+
+    tracing::info!("Device authorization started for client: {}", client.name);
+
+    // 5. 构造验证地址
+    let verification_uri = format!("{}/oauth/device", config.issuer());
+    let verification_uri_complete = format!("{}?user_code={}", verification_uri, user_code);
+
+    Ok(HttpResponse::Ok().json(DeviceAuthorizationResponse {
+        device_code,
+        user_code,
+        verification_uri,
+        verification_uri_complete,
+        expires_in: DEVICE_CODE_EXPIRE,
+        interval: DEVICE_CODE_INTERVAL,
+    }))
+}
+
+/// GET /oauth/device
+/// 登录用户查看 user_code 对应的授权请求
+pub async fn lookup(
+    req: HttpRequest,
+    query: web::Query<DeviceLookupQuery>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证用户身份
+    authenticated_user_id(&req, &jwt_manager, &cache).await?;
+
+    // 2. 查找待处理的设备授权
+    let record = find_pending(&storage, &query.user_code).await?;
+
+    let client = storage
+        .find_by_client_id(&record.client_id)
+        .await?
+        .ok_or(AppError::InvalidClient)?;
+
+    Ok(HttpResponse::Ok().json(DeviceLookupResponse {
+        user_code: record.user_code,
+        client_id: client.client_id,
+        client_name: client.name,
+        scopes: parse_scopes(&record.scopes),
+        expires_at: record.expires_at.to_rfc3339(),
+    }))
+}
+
+/// POST /oauth/device
+/// 登录用户批准或拒绝设备授权
+pub async fn verify(
+    req: HttpRequest,
+    body: web::Json<DeviceVerifyRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证用户身份（批准时记录会话的认证时间）
+    let session = authenticated_session(&req, &storage, &jwt_manager, &cache).await?;
+    let user_id = session.user_id;
+
+    storage
+        .find_by_id(user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // 2. 查找待处理的设备授权
+    let record = find_pending(&storage, &body.user_code).await?;
+
+    // 3. 记录用户的决定（并发请求中只有一个能成功）
+    if !storage
+        .resolve_device_code(
+            record.id,
+            user_id,
+            body.approve,
+            session
+                .auth_time
+                .and_then(|at| DateTime::from_timestamp(at, 0)),
+        )
+        .await?
+    {
+        return Err(AppError::BadRequest("Invalid or expired user_code".into()));
+    }
+
+    tracing::info!(
+        "Device authorization {} for client: {} user: {}",
+        if body.approve { "approved" } else { "denied" },
+        record.client_id,
+        user_id
+    );
+
+    let status = if body.approve {
+        DEVICE_CODE_APPROVED
+    } else {
+        DEVICE_CODE_DENIED
+    };
+
+    Ok(HttpResponse::Ok().json(DeviceVerifyResponse {
+        status: status.to_string(),
+    }))
+}
+
+/// 查找未过期且尚未处理的设备授权
+async fn find_pending(
+    storage: &SeaOrmBackend,
+    user_code: &str,
+) -> Result<device_codes::Model, AppError> {
+    let user_code = normalize_user_code(user_code)
+        .ok_or(AppError::BadRequest("Invalid or expired user_code".into()))?;
+
+    storage
+        .find_device_code_by_user_code(&user_code)
+        .await?
+        .filter(|record| record.status == DEVICE_CODE_PENDING && record.expires_at > Utc::now())
+        .ok_or(AppError::BadRequest("Invalid or expired user_code".into()))
+}
+
+/// 设备轮询换取 token
+pub(crate) async fn device_code_grant(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    client: &o_auth_clients::Model,
+    device_code: &str,
+    dpop_jkt: Option<&str>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 查找设备授权并验证所属客户端
+    let record = storage
+        .find_device_code(device_code)
+        .await?
+        .ok_or(AppError::InvalidGrant("Invalid device_code".into()))?;
+
+    if record.client_id != client.client_id {
+        return Err(AppError::InvalidGrant(
+            "device_code was not issued to this client".into(),
+        ));
+    }
+
+    // 2. 检查是否过期
+    let now = Utc::now();
+    if record.expires_at < now {
+        storage.delete_device_code(record.id).await?;
+        return Err(AppError::ExpiredToken);
+    }
+
+    // 3. 轮询过于频繁时要求设备放慢速度
+    if let Some(last_polled_at) = record.last_polled_at
+        && now.timestamp() - last_polled_at.timestamp() < record.interval
+    {
+        storage
+            .record_device_poll(record.id, record.interval + SLOW_DOWN_INCREMENT)
+            .await?;
+        return Err(AppError::SlowDown);
+    }
+
+    storage
+        .record_device_poll(record.id, record.interval)
+        .await?;
+
+    // 4. 根据用户的决定处理
+    match record.status.as_str() {
+        DEVICE_CODE_APPROVED => {}
+        DEVICE_CODE_DENIED => {
+            storage.delete_device_code(record.id).await?;
+            return Err(AppError::AccessDenied(
+                "The user denied the authorization request".into(),
+            ));
+        }
+        _ => return Err(AppError::AuthorizationPending),
+    }
+
+    // 5. device_code 只能使用一次（并发请求中只有一个能成功）
+    if !storage.delete_device_code(record.id).await? {
+        return Err(AppError::InvalidGrant("Invalid device_code".into()));
+    }
+
+    // 6. 验证用户状态
+    let user_id = record
+        .user_id
+        .ok_or(AppError::InvalidGrant("Invalid device_code".into()))?;
+    let user = storage
+        .find_by_id(user_id)
+        .await?
+        .ok_or(AppError::InvalidGrant("User not found".into()))?;
+
+    if user.deleted_at.is_some() || !user.is_active {
+        return Err(AppError::InvalidGrant("User account is not active".into()));
+    }
+
+    // 7. 签发 token
+    let response = issue_tokens(
+        storage,
+        jwt_manager,
+        cache,
+        &client.client_id,
+        &user,
+        &record.scopes,
+        AuthContext {
+            dpop_jkt,
+            auth_time: record.auth_time.map(|at| at.timestamp()),
+            ..AuthContext::default()
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        "Device access token issued for client: {} user: {}",
+        client.name,
+        user.id
+    );
+
+    Ok(response)
+}
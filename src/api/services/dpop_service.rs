@@ -0,0 +1,71 @@
+use actix_web::HttpRequest;
+
+use crate::cache::CompositeCache;
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::security::dpop::{DPOP_HEADER, DPOP_PROOF_LIFETIME, verify_proof};
+use crate::security::generate_random_token;
+use crate::security::jwt::VALIDATION_LEEWAY;
+
+/// 服务器下发的 DPoP nonce 的有效期（秒，有效期内可以重复使用）
+const DPOP_NONCE_TTL: u64 = 300;
+
+/// 签发新的 DPoP nonce
+pub(crate) async fn issue_nonce(cache: &CompositeCache) -> String {
+    let nonce = generate_random_token(32);
+    cache
+        .set(
+            &format!("dpop_nonce:{}", nonce),
+            "valid".to_string(),
+            Some(DPOP_NONCE_TTL),
+        )
+        .await;
+    nonce
+}
+
+/// 验证请求中的 DPoP 证明，返回证明公钥的 JWK Thumbprint（未携带 DPoP 头时返回 None）
+///
+/// 证明必须针对当前请求（htu 为 issuer 加请求路径）并携带服务器签发的 nonce，
+/// 否则返回 use_dpop_nonce 及新的 nonce；jti 只能使用一次（RFC 9449 4.3、8、11.1）。
+/// `access_token` 为访问资源时提交的 token，用于验证 ath
+pub(crate) async fn check_proof(
+    req: &HttpRequest,
+    cache: &CompositeCache,
+    access_token: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    let mut headers = req.headers().get_all(DPOP_HEADER);
+    let Some(header) = headers.next() else {
+        return Ok(None);
+    };
+    if headers.next().is_some() {
+        return Err(AppError::InvalidDpopProof(
+            "Multiple DPoP proofs in request".into(),
+        ));
+    }
+
+    // 1. 验证签名及 htm、htu、iat、ath
+    let proof = header
+        .to_str()
+        .map_err(|_| AppError::InvalidDpopProof("Malformed DPoP proof".into()))?;
+    let htu = format!("{}{}", get_config().issuer(), req.path());
+    let proof = verify_proof(proof, req.method().as_str(), &htu, access_token)?;
+
+    // 2. nonce 必须是服务器签发且仍在有效期内的
+    match proof.claims.nonce.as_deref() {
+        Some(nonce) if cache.exists(&format!("dpop_nonce:{}", nonce)).await => {}
+        _ => return Err(AppError::UseDpopNonce(issue_nonce(cache).await)),
+    }
+
+    // 3. 防重放：在证明有效期内记录已使用的 jti
+    //    （原子写入，并发提交同一证明时只有一个请求成功）
+    let key = format!("dpop_jti:{}:{}", proof.jkt, proof.claims.jti);
+    let ttl = DPOP_PROOF_LIFETIME as u64 + VALIDATION_LEEWAY;
+    if !cache.set_if_absent(&key, "1".to_string(), Some(ttl)).await {
+        tracing::warn!("Replayed DPoP proof for key: {}", proof.jkt);
+        return Err(AppError::InvalidDpopProof(
+            "DPoP proof has already been used".into(),
+        ));
+    }
+
+    Ok(Some(proof.jkt))
+}
@@ -0,0 +1,317 @@
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::Algorithm;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::config::AppConfig;
+use crate::errors::AppError;
+use crate::security::jwt::VALIDATION_LEEWAY;
+use crate::security::key_encryption;
+use crate::security::keys::{algorithm_name, parse_algorithm};
+use crate::security::{Claims, JwtManager, SigningKey, VerificationKey};
+use crate::storage::entities::signing_keys;
+use crate::storage::{
+    NewSigningKey, SIGNING_KEY_ACTIVE, SIGNING_KEY_NEXT, SIGNING_KEY_RETIRED, SeaOrmBackend,
+};
+
+/// 定期从数据库重新加载签名密钥的间隔（秒）
+///
+/// 多实例部署时，其他实例轮换密钥后本实例在此间隔内切换到新密钥。新密钥在启用前已作为
+/// next 密钥加载，各实例之间签发的 Token 可以互相验证
+const KEY_RELOAD_INTERVAL: u64 = 60;
+
+#[derive(Debug, Serialize)]
+pub struct SigningKeyInfo {
+    pub kid: String,
+    pub algorithm: String,
+    pub status: String,
+    pub created_at: String,
+    pub activated_at: Option<String>,
+    pub retired_at: Option<String>,
+    /// 退役密钥签发的 Token 在此之前仍可验证
+    pub verify_until: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SigningKeyListResponse {
+    pub keys: Vec<SigningKeyInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateKeyResponse {
+    pub kid: String,
+    pub algorithm: String,
+    pub retired_kid: Option<String>,
+}
+
+/// GET /api/admin/keys
+/// 获取签名密钥列表（不包含私钥）
+pub async fn list_keys(storage: web::Data<Arc<SeaOrmBackend>>) -> Result<HttpResponse, AppError> {
+    let overlap = overlap_window(&storage).await?;
+    let keys = storage.list_signing_keys(Utc::now() - overlap).await?;
+
+    let keys = keys
+        .into_iter()
+        .map(|key| SigningKeyInfo {
+            verify_until: key.retired_at.map(|at| (at + overlap).to_rfc3339()),
+            kid: key.kid,
+            algorithm: key.algorithm,
+            status: key.status,
+            created_at: key.created_at.to_rfc3339(),
+            activated_at: key.activated_at.map(|at| at.to_rfc3339()),
+            retired_at: key.retired_at.map(|at| at.to_rfc3339()),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(SigningKeyListResponse { keys }))
+}
+
+/// POST /api/admin/keys/rotate
+/// 轮换签名密钥（旧密钥在重叠期内仍可验证已签发的 Token）
+pub async fn rotate_keys(
+    req: HttpRequest,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    // 从请求扩展中提取 Claims（由 AdminOnly 中间件注入）
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppError::Unauthorized)?;
+
+    let admin_id: i64 = claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+
+    // 1. HS256 共享密钥不支持轮换
+    let algorithm = parse_algorithm(&config.auth.signing_algorithm)?;
+    if algorithm == Algorithm::HS256 {
+        return Err(AppError::BadRequest(
+            "Key rotation requires an asymmetric signing algorithm".into(),
+        ));
+    }
+
+    // 2. 确保存在与配置算法一致的 next 密钥
+    let overlap = overlap_window(&storage).await?;
+    let keys = storage.list_signing_keys(Utc::now() - overlap).await?;
+    let encryption_key = &config.auth.signing_key_encryption_key;
+    ensure_next_key(&storage, &keys, algorithm, encryption_key).await?;
+
+    // 3. 轮换：active → retired，next → active，并生成新的 next
+    let new_next = SigningKey::generate(algorithm)?;
+    let private_key = encrypted_private_key(&new_next, encryption_key)?;
+    let (retired, activated) = storage
+        .rotate_signing_keys(new_signing_key(&new_next, &private_key))
+        .await?;
+
+    // 4. 清理已过重叠期的退役密钥
+    storage
+        .delete_retired_signing_keys(Utc::now() - overlap)
+        .await?;
+
+    // 5. 立即启用新密钥
+    reload_signing_keys(&storage, &jwt_manager, encryption_key).await?;
+
+    // 6. 记录审计日志
+    let retired_kid = retired.map(|key| key.kid);
+    storage
+        .log_config_change(
+            "signing_key",
+            retired_kid.clone(),
+            Some(activated.kid.clone()),
+            admin_id,
+            "rotate",
+        )
+        .await?;
+
+    tracing::info!(
+        "Signing key rotated by admin {}: {:?} -> {}",
+        admin_id,
+        retired_kid,
+        activated.kid
+    );
+
+    Ok(HttpResponse::Ok().json(RotateKeyResponse {
+        kid: activated.kid,
+        algorithm: activated.algorithm,
+        retired_kid,
+    }))
+}
+
+/// 初始化密钥库：没有 active 密钥时导入 initial_key，并预先生成 next 密钥
+///
+/// 私钥使用 `encryption_key` 加密保存，之前以明文保存的私钥在此时加密
+pub(crate) async fn init_signing_keys(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    encryption_key: &str,
+    initial_key: impl FnOnce() -> Result<SigningKey, AppError>,
+) -> Result<(), AppError> {
+    encrypt_plaintext_keys(storage, encryption_key).await?;
+
+    let overlap = overlap_window(storage).await?;
+    let keys = storage.list_signing_keys(Utc::now() - overlap).await?;
+
+    // 1. 首次启动：保存初始密钥
+    let algorithm = match keys.iter().find(|key| key.status == SIGNING_KEY_ACTIVE) {
+        Some(active) => parse_algorithm(&active.algorithm)?,
+        None => {
+            let key = initial_key()?;
+            let private_key = encrypted_private_key(&key, encryption_key)?;
+            storage
+                .create_signing_key(new_signing_key(&key, &private_key), SIGNING_KEY_ACTIVE)
+                .await?;
+            tracing::info!("Signing key {:?} stored as active", key.kid());
+
+            // 切换前用 HS256 共享密钥签发的 Token 只在其最长有效期内继续接受
+            storage
+                .set_legacy_token_deadline((Utc::now() + overlap).timestamp())
+                .await?;
+            key.algorithm()
+        }
+    };
+
+    // 2. 预先生成 next 密钥，使其在启用前就出现在 JWKS 中
+    if !keys.iter().any(|key| key.status == SIGNING_KEY_NEXT) {
+        let next = SigningKey::generate(algorithm)?;
+        let private_key = encrypted_private_key(&next, encryption_key)?;
+        storage
+            .create_signing_key(new_signing_key(&next, &private_key), SIGNING_KEY_NEXT)
+            .await?;
+    }
+
+    reload_signing_keys(storage, jwt_manager, encryption_key).await
+}
+
+/// 加密以明文保存的私钥
+async fn encrypt_plaintext_keys(
+    storage: &SeaOrmBackend,
+    encryption_key: &str,
+) -> Result<(), AppError> {
+    for model in storage.list_signing_keys(DateTime::<Utc>::MIN_UTC).await? {
+        if key_encryption::is_encrypted(&model.private_key) {
+            continue;
+        }
+
+        let private_key =
+            key_encryption::encrypt_private_key(&model.private_key, &model.kid, encryption_key)?;
+        storage
+            .update_signing_key_private_key(model.id, &private_key)
+            .await?;
+        tracing::info!("Private key of signing key {} encrypted", model.kid);
+    }
+
+    Ok(())
+}
+
+/// 从数据库加载签名密钥到 JwtManager
+pub(crate) async fn reload_signing_keys(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    encryption_key: &str,
+) -> Result<(), AppError> {
+    let overlap = overlap_window(storage).await?;
+    let keys = storage.list_signing_keys(Utc::now() - overlap).await?;
+
+    let mut active = None;
+    let mut others = Vec::new();
+
+    for model in keys {
+        let private_pem =
+            key_encryption::decrypt_private_key(&model.private_key, &model.kid, encryption_key)?;
+        let key = SigningKey::from_pem(parse_algorithm(&model.algorithm)?, &private_pem)?;
+
+        match model.status.as_str() {
+            SIGNING_KEY_ACTIVE => active = Some(key),
+            SIGNING_KEY_RETIRED => others.push(VerificationKey {
+                key,
+                not_after: model.retired_at.map(|at| (at + overlap).timestamp()),
+            }),
+            _ => others.push(VerificationKey {
+                key,
+                not_after: None,
+            }),
+        }
+    }
+
+    let active = active.ok_or(AppError::Internal("No active signing key".into()))?;
+    let legacy_not_after = storage.get_legacy_token_deadline().await?;
+    jwt_manager.set_signing_keys(active, others, legacy_not_after);
+    Ok(())
+}
+
+/// 在后台定期重新加载签名密钥（同步其他实例的轮换），加载失败时保留当前密钥
+pub(crate) fn spawn_signing_key_reload(
+    storage: SeaOrmBackend,
+    jwt_manager: Arc<JwtManager>,
+    encryption_key: String,
+) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(KEY_RELOAD_INTERVAL));
+        // 第一次 tick 立即完成，启动时已经加载过密钥
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload_signing_keys(&storage, &jwt_manager, &encryption_key).await {
+                tracing::warn!("Failed to reload signing keys: {}", e);
+            }
+        }
+    });
+}
+
+/// 退役密钥的重叠期：覆盖其签发的 Token 的最长有效期
+async fn overlap_window(storage: &SeaOrmBackend) -> Result<Duration, AppError> {
+    let auth_policy = storage.get_auth_policy_config().await?;
+    let lifetime = auth_policy
+        .access_token_expire
+        .max(auth_policy.refresh_token_expire);
+
+    Ok(Duration::seconds(lifetime + VALIDATION_LEEWAY as i64))
+}
+
+/// 算法变更后丢弃旧算法的 next 密钥并重新生成
+async fn ensure_next_key(
+    storage: &SeaOrmBackend,
+    keys: &[signing_keys::Model],
+    algorithm: Algorithm,
+    encryption_key: &str,
+) -> Result<(), AppError> {
+    let ready = keys
+        .iter()
+        .any(|key| key.status == SIGNING_KEY_NEXT && key.algorithm == algorithm_name(algorithm));
+    if ready {
+        return Ok(());
+    }
+
+    storage.delete_next_signing_keys().await?;
+    let next = SigningKey::generate(algorithm)?;
+    let private_key = encrypted_private_key(&next, encryption_key)?;
+    storage
+        .create_signing_key(new_signing_key(&next, &private_key), SIGNING_KEY_NEXT)
+        .await?;
+    Ok(())
+}
+
+/// 加密后的私钥
+fn encrypted_private_key(key: &SigningKey, encryption_key: &str) -> Result<String, AppError> {
+    key_encryption::encrypt_private_key(
+        key.private_pem().unwrap_or_default(),
+        key.kid().unwrap_or_default(),
+        encryption_key,
+    )
+}
+
+/// 待保存的非对称密钥（private_key 为加密后的私钥）
+fn new_signing_key<'a>(key: &'a SigningKey, private_key: &'a str) -> NewSigningKey<'a> {
+    NewSigningKey {
+        kid: key.kid().unwrap_or_default(),
+        algorithm: algorithm_name(key.algorithm()),
+        private_key,
+    }
+}
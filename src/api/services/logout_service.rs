@@ -0,0 +1,386 @@
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, web};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use url::Url;
+use url::form_urlencoded;
+
+use crate::api::middleware::auth::bearer_token;
+use crate::api::services::oauth_service::{UserSession, authenticated_session};
+use crate::api::services::subject_service;
+use crate::api::services::token_service::blacklist_token;
+use crate::cache::CompositeCache;
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::security::JwtManager;
+use crate::storage::entities::o_auth_clients;
+use crate::storage::repository::FIRST_PARTY_CLIENT_ID;
+use crate::storage::{ClientRepository, RevokedTokens, SeaOrmBackend, TokenRepository};
+use crate::utils::http_client;
+
+/// Back-Channel Logout 事件标识（OpenID Connect Back-Channel Logout 1.0 2.4）
+const BACKCHANNEL_LOGOUT_EVENT: &str = "http://schemas.openid.net/event/backchannel-logout";
+
+/// logout_token 有效期（秒，需要覆盖全部重试）
+const LOGOUT_TOKEN_EXPIRE: i64 = 300;
+
+/// 通知发送失败后的重试间隔（秒）
+const BACKCHANNEL_LOGOUT_RETRY_DELAYS: [u64; 3] = [5, 30, 120];
+
+/// RP 发起的登出请求（OpenID Connect RP-Initiated Logout 1.0）
+#[derive(Debug, Deserialize)]
+pub struct EndSessionRequest {
+    pub id_token_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub post_logout_redirect_uri: Option<String>,
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogoutResponse {
+    pub message: String,
+}
+
+/// GET /oauth/logout
+/// RP 发起的登出（参数通过查询字符串传递）
+pub async fn end_session(
+    req: HttpRequest,
+    query: web::Query<EndSessionRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    logout(&req, query.into_inner(), &storage, &jwt_manager, &cache).await
+}
+
+/// POST /oauth/logout
+/// RP 发起的登出（参数通过表单传递）
+pub async fn end_session_form(
+    req: HttpRequest,
+    form: web::Form<EndSessionRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    logout(&req, form.into_inner(), &storage, &jwt_manager, &cache).await
+}
+
+/// 结束当前会话及发起登出的客户端的 Token，并重定向到客户端登记的登出地址
+///
+/// id_token_hint 只用于确定用户和客户端；撤销 Token 需要请求携带用户的会话 Token
+/// （Authorization 头，跨站请求无法携带），没有会话时不撤销任何 Token
+async fn logout(
+    req: &HttpRequest,
+    params: EndSessionRequest,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证 id_token_hint（允许已过期），其 aud 即发起登出的客户端
+    let hint = params
+        .id_token_hint
+        .as_deref()
+        .map(|token| jwt_manager.verify_id_token_hint(token, &get_config().issuer()))
+        .transpose()
+        .map_err(|_| AppError::BadRequest("Invalid id_token_hint".into()))?;
+
+    if let (Some(hint), Some(client_id)) = (&hint, &params.client_id)
+        && hint.aud != *client_id
+    {
+        return Err(AppError::BadRequest(
+            "client_id does not match id_token_hint".into(),
+        ));
+    }
+    let client_id = params
+        .client_id
+        .clone()
+        .or_else(|| hint.as_ref().map(|hint| hint.aud.clone()));
+
+    // 2. post_logout_redirect_uri 必须是客户端登记的地址（在撤销 Token 前验证）
+    let redirect_url = match params.post_logout_redirect_uri.as_deref() {
+        Some(uri) => {
+            let client_id = client_id.as_deref().ok_or(AppError::BadRequest(
+                "id_token_hint or client_id is required with post_logout_redirect_uri".into(),
+            ))?;
+            let client = storage
+                .find_by_client_id(client_id)
+                .await?
+                .ok_or(AppError::InvalidClient)?;
+            if !is_registered_logout_uri(&client, uri) {
+                return Err(AppError::InvalidRedirectUri);
+            }
+            Some(logout_redirect_url(uri, params.state.as_deref())?)
+        }
+        None => None,
+    };
+
+    // 3. 当前会话的用户必须与 id_token_hint 标识的用户一致
+    if req.headers().contains_key(header::AUTHORIZATION) {
+        let session = authenticated_session(req, storage, jwt_manager, cache).await?;
+        if let Some(hint) = &hint {
+            let hint_user_id = subject_service::resolve_user_id(storage, &hint.sub).await?;
+            if hint_user_id != Some(session.user_id) {
+                return Err(AppError::BadRequest(
+                    "id_token_hint does not match the current session".into(),
+                ));
+            }
+        }
+
+        // 4. 结束当前会话，并撤销发起登出的客户端持有的 Token
+        let mut revoked = end_current_session(req, storage, jwt_manager, cache).await?;
+        if let Some(client_id) = client_id.as_deref() {
+            revoked += end_client_sessions(storage, jwt_manager, cache, session, client_id).await?;
+        }
+
+        tracing::info!(
+            "User {} logged out via client {:?} ({} tokens revoked)",
+            session.user_id,
+            client_id,
+            revoked
+        );
+    }
+
+    // 5. 重定向回客户端，未指定地址时直接返回结果
+    match redirect_url {
+        Some(url) => Ok(HttpResponse::Found()
+            .insert_header((header::LOCATION, url))
+            .finish()),
+        None => Ok(HttpResponse::Ok().json(LogoutResponse {
+            message: "Logged out successfully".to_string(),
+        })),
+    }
+}
+
+/// 撤销请求携带的会话 Token，返回被撤销的 access_token 数量
+async fn end_current_session(
+    req: &HttpRequest,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+) -> Result<usize, AppError> {
+    let token = bearer_token(req)?;
+    blacklist_token(jwt_manager, cache, &token).await;
+    cache.delete(&format!("token:{}", token)).await;
+
+    let Some(record) = storage.find_access_token(&token).await? else {
+        return Ok(0);
+    };
+    let Some(refresh_tokens) = storage.delete_access_token(record.id).await? else {
+        return Ok(0);
+    };
+    for refresh_token in &refresh_tokens {
+        blacklist_token(jwt_manager, cache, refresh_token).await;
+    }
+    Ok(1)
+}
+
+/// 地址是否在客户端登记的 post_logout_redirect_uris 中（完全匹配）
+fn is_registered_logout_uri(client: &o_auth_clients::Model, uri: &str) -> bool {
+    client
+        .post_logout_redirect_uris
+        .as_deref()
+        .and_then(|uris| serde_json::from_str::<Vec<String>>(uris).ok())
+        .is_some_and(|uris| uris.iter().any(|registered| registered == uri))
+}
+
+/// 构造登出后的重定向地址（原样返回 state）
+fn logout_redirect_url(uri: &str, state: Option<&str>) -> Result<String, AppError> {
+    let mut url = Url::parse(uri).map_err(|_| AppError::InvalidRedirectUri)?;
+    if let Some(state) = state {
+        url.query_pairs_mut().append_pair("state", state);
+    }
+    Ok(url.to_string())
+}
+
+/// 结束用户的所有会话（禁用、删除或重置密码时调用）
+///
+/// 删除用户的所有 Token 并加入黑名单，然后向持有该用户 Token 的客户端异步发送
+/// Back-Channel Logout 通知。返回被撤销的 access_token 数量
+pub(crate) async fn end_user_sessions(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    user_id: i64,
+) -> Result<usize, AppError> {
+    // 1. 删除 Token，已签发的 JWT 加入黑名单直到过期
+    let revoked = storage.revoke_user_tokens(user_id).await?;
+    revoke_cached_tokens(jwt_manager, cache, &revoked).await;
+
+    // 2. 持有该用户 Token 的客户端（第一方登录没有对应的客户端）
+    let mut client_ids: Vec<&str> = revoked
+        .access_tokens
+        .iter()
+        .map(|token| token.client_id.as_str())
+        .filter(|client_id| *client_id != FIRST_PARTY_CLIENT_ID)
+        .collect();
+    client_ids.sort_unstable();
+    client_ids.dedup();
+
+    // 3. 向登记了 backchannel_logout_uri 的客户端发送通知
+    for client_id in client_ids {
+        notify_client(storage, jwt_manager, user_id, client_id, None).await?;
+    }
+
+    Ok(revoked.access_tokens.len())
+}
+
+/// 结束用户在单个客户端的会话（RP 发起的登出），返回被撤销的 access_token 数量
+///
+/// 只撤销与当前登录会话相同认证时间的 Token，通知中携带该会话的 sid
+async fn end_client_sessions(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    session: UserSession,
+    client_id: &str,
+) -> Result<usize, AppError> {
+    let auth_time = session
+        .auth_time
+        .and_then(|at| DateTime::from_timestamp(at, 0));
+    let revoked = storage
+        .revoke_session_tokens(session.user_id, client_id, auth_time)
+        .await?;
+    revoke_cached_tokens(jwt_manager, cache, &revoked).await;
+
+    if !revoked.access_tokens.is_empty() && client_id != FIRST_PARTY_CLIENT_ID {
+        let sid = session.auth_time.map(|at| session_id(session.user_id, at));
+        notify_client(
+            storage,
+            jwt_manager,
+            session.user_id,
+            client_id,
+            sid.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(revoked.access_tokens.len())
+}
+
+/// 被撤销的 access token 和 refresh token 加入黑名单直到过期，并清除 Token 缓存
+async fn revoke_cached_tokens(
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    revoked: &RevokedTokens,
+) {
+    for token in revoked.jwts() {
+        blacklist_token(jwt_manager, cache, token).await;
+        cache.delete(&format!("token:{}", token)).await;
+    }
+}
+
+/// 客户端登记了 backchannel_logout_uri 时，在后台发送 logout_token
+///
+/// 未提供 sid 时客户端应结束该用户的所有会话
+async fn notify_client(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    user_id: i64,
+    client_id: &str,
+    sid: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(client) = storage.find_by_client_id(client_id).await? else {
+        return Ok(());
+    };
+    let Some(uri) = client.backchannel_logout_uri.clone() else {
+        return Ok(());
+    };
+
+    // logout_token 中的 sub 与客户端收到的 ID Token 一致
+    let subject = subject_service::subject_identifier(storage, Some(&client), user_id).await?;
+    match logout_token(jwt_manager, &client, &subject, sid) {
+        Ok(token) => {
+            let body = form_urlencoded::Serializer::new(String::new())
+                .append_pair("logout_token", &token)
+                .finish();
+            tokio::spawn(deliver_logout_token(client.client_id, uri, body));
+        }
+        Err(e) => tracing::error!(
+            "Failed to create logout token for client {}: {}",
+            client.client_id,
+            e
+        ),
+    }
+
+    Ok(())
+}
+
+/// 登录会话的标识（ID Token 和 logout_token 中的 sid）
+///
+/// 由用户 ID 和会话的认证时间计算：base64url(SHA-256("sid" || user_id || auth_time || salt))，
+/// 同一次登录授权给各客户端的 Token 具有相同的 sid
+pub(crate) fn session_id(user_id: i64, auth_time: i64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"sid");
+    hasher.update(user_id.to_string().as_bytes());
+    hasher.update(auth_time.to_string().as_bytes());
+    hasher.update(get_config().auth.pairwise_subject_salt.as_bytes());
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+/// 生成发送给客户端的 logout_token（OpenID Connect Back-Channel Logout 1.0 2.4）
+fn logout_token(
+    jwt_manager: &JwtManager,
+    client: &o_auth_clients::Model,
+    subject: &str,
+    sid: Option<&str>,
+) -> Result<String, AppError> {
+    use serde_json::json;
+
+    let now = Utc::now();
+    let mut claims = json!({
+        "iss": get_config().issuer(),
+        "sub": subject,
+        "aud": client.client_id,
+        "iat": now.timestamp(),
+        "exp": (now + Duration::seconds(LOGOUT_TOKEN_EXPIRE)).timestamp(),
+        "jti": uuid::Uuid::new_v4().to_string(),
+        "events": { BACKCHANNEL_LOGOUT_EVENT: {} },
+    });
+    if let Some(sid) = sid {
+        claims["sid"] = json!(sid);
+    }
+
+    jwt_manager.encode_typed(&claims, "logout+jwt")
+}
+
+/// 发送 logout_token，失败时按 BACKCHANNEL_LOGOUT_RETRY_DELAYS 重试
+///
+/// 重试队列只保存在内存中：服务在重试完成前重启时，未送达的通知会丢失
+async fn deliver_logout_token(client_id: String, uri: String, body: String) {
+    let delays = std::iter::once(0).chain(BACKCHANNEL_LOGOUT_RETRY_DELAYS);
+
+    for (attempt, delay) in delays.enumerate() {
+        if delay > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+        }
+
+        match http_client::post_form(&uri, &body).await {
+            Ok(response) if response.is_success() => {
+                tracing::info!("Back-channel logout delivered to client {}", client_id);
+                return;
+            }
+            Ok(response) => tracing::warn!(
+                "Back-channel logout to client {} failed (attempt {}): HTTP {}",
+                client_id,
+                attempt + 1,
+                response.status
+            ),
+            Err(e) => tracing::warn!(
+                "Back-channel logout to client {} failed (attempt {}): {}",
+                client_id,
+                attempt + 1,
+                e
+            ),
+        }
+    }
+
+    tracing::error!(
+        "Giving up back-channel logout to client {} after {} attempts",
+        client_id,
+        BACKCHANNEL_LOGOUT_RETRY_DELAYS.len() + 1
+    );
+}
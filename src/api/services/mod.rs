@@ -1,14 +1,26 @@
 pub mod admin_user_service;
 pub mod auth_service;
+pub mod client_service;
+pub mod consent_service;
+pub mod device_service;
+pub mod dpop_service;
 pub mod health;
 pub mod invite_service;
+pub mod key_service;
+pub mod logout_service;
 pub mod oauth_service;
 pub mod oidc_service;
+pub mod par_service;
+pub mod reauth_service;
+pub mod registration_service;
 pub mod settings_service;
+pub mod subject_service;
+pub mod token_exchange_service;
+pub mod token_service;
 pub mod user_service;
 
 // 认证服务
-pub use auth_service::{login, register};
+pub use auth_service::{login, refresh, register};
 
 // 健康检查
 pub use health::{health_check, liveness, readiness};
@@ -16,13 +28,33 @@ pub use health::{health_check, liveness, readiness};
 // OAuth2 服务
 pub use oauth_service::{authorize as oauth_authorize, token as oauth_token};
 
+// 推送授权请求服务
+pub use par_service::push_authorization_request as oauth_par;
+
+// 登出服务
+pub use logout_service::{end_session as oauth_logout, end_session_form as oauth_logout_form};
+
+// 授权同意服务
+pub use consent_service::consent as oauth_consent;
+
+// 重新认证服务
+pub use reauth_service::reauthenticate as oauth_reauthenticate;
+
+// Token 管理服务
+pub use token_service::{introspect as oauth_introspect, revoke as oauth_revoke};
+
+// 设备授权服务
+pub use device_service::{
+    device_authorization as device_authorize, lookup as device_lookup, verify as device_verify,
+};
+
 // OIDC 服务
 pub use oidc_service::{discovery as oidc_discovery, jwks as oidc_jwks, userinfo as oidc_userinfo};
 
 // 用户管理服务
 pub use user_service::{
     get_profile as user_get_profile, list_authorizations as user_list_authorizations,
-    revoke_authorization as user_revoke_authorization,
+    revoke_authorization as user_revoke_authorization, update_profile as user_update_profile,
 };
 
 // 设置管理服务
@@ -30,9 +62,12 @@ pub use settings_service::{
     get_audit_logs as settings_get_audit_logs,
     get_auth_policy_config as settings_get_auth_policy_config,
     get_cache_policy_config as settings_get_cache_policy_config,
+    get_client_registration_config as settings_get_client_registration_config,
     get_registration_config as settings_get_registration_config,
+    rotate_initial_access_token as settings_rotate_initial_access_token,
     update_auth_policy_config as settings_update_auth_policy_config,
     update_cache_policy_config as settings_update_cache_policy_config,
+    update_client_registration_config as settings_update_client_registration_config,
     update_registration_config as settings_update_registration_config,
 };
 
@@ -42,6 +77,22 @@ pub use invite_service::{
     revoke_invite as invite_revoke, verify_invite as invite_verify,
 };
 
+// 签名密钥管理服务
+pub use key_service::{list_keys as admin_list_keys, rotate_keys as admin_rotate_keys};
+
+// 客户端管理服务
+pub use client_service::{
+    create_client as admin_create_client, delete_client as admin_delete_client,
+    get_client as admin_get_client, list_clients as admin_list_clients,
+    rotate_secret as admin_rotate_client_secret, update_client as admin_update_client,
+};
+
+// 动态客户端注册服务
+pub use registration_service::{
+    delete_client as oauth_delete_registration, get_client as oauth_get_registration,
+    register as oauth_register, update_client as oauth_update_registration,
+};
+
 // 管理员用户管理服务
 pub use admin_user_service::{
     delete_user as admin_delete_user, get_user as admin_get_user,
@@ -1,20 +1,155 @@
+use actix_web::http::header;
 use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
-use chrono::{Duration, Utc};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::decode_header;
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
 use std::sync::Arc;
+use url::form_urlencoded;
 
+use crate::api::middleware::auth::{authenticate_token, bearer_token};
+use crate::api::services::device_service::{self, DEVICE_CODE_GRANT_TYPE};
+use crate::api::services::token_exchange_service::{self, TOKEN_EXCHANGE_GRANT_TYPE};
+use crate::api::services::{
+    consent_service, dpop_service, logout_service, oidc_service, par_service, reauth_service,
+    subject_service,
+};
 use crate::cache::CompositeCache;
+use crate::config::{AppConfig, get_config};
 use crate::errors::AppError;
-use crate::security::{Claims, JwtManager, generate_random_token};
+use crate::security::claims::{ClaimsRequest, released_claims};
+use crate::security::client_assertion::{
+    JWT_BEARER_ASSERTION_TYPE, assertion_subject, client_public_key, verify_assertion,
+};
+use crate::security::jwt::{REFRESH_SCOPE, VALIDATION_LEEWAY};
+use crate::security::keys::token_hash;
+use crate::security::request_object::{request_object_algorithm, verify_request_object};
+use crate::security::{Claims, ClientSecretManager, JwtManager, dpop, generate_random_token, pkce};
+use crate::storage::entities::{o_auth_clients, users};
+use crate::storage::repository::{NewAccessToken, NewAuthCode};
 use crate::storage::{ClientRepository, SeaOrmBackend, TokenRepository, UserRepository};
+use crate::utils::{html, http_client};
 
-#[derive(Debug, Deserialize)]
+/// /oauth/token 支持的 grant_type
+pub const SUPPORTED_GRANT_TYPES: [&str; 5] = [
+    "authorization_code",
+    "refresh_token",
+    "client_credentials",
+    DEVICE_CODE_GRANT_TYPE,
+    TOKEN_EXCHANGE_GRANT_TYPE,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorizeRequest {
+    /// 使用 request_uri 时可以省略
+    #[serde(default)]
     pub response_type: String,
     pub client_id: String,
+    /// 使用 request_uri 时可以省略
+    #[serde(default)]
     pub redirect_uri: String,
     pub scope: Option<String>,
     pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+    pub max_age: Option<u32>,
+    pub response_mode: Option<String>,
+    pub prompt: Option<String>,
+    pub login_hint: Option<String>,
+    pub ui_locales: Option<String>,
+    /// 请求返回的用户 claims（OpenID Connect Core 5.5，JSON 对象）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<String>,
+    /// PAR 端点返回的 request_uri（RFC 9126 4）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_uri: Option<String>,
+    /// 签名的请求对象（RFC 9101 2.1），解析后保留用于判断请求是否经过签名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<String>,
+}
+
+/// 可以由请求对象提供的授权请求参数（client_id 必须与请求参数一致）
+const REQUEST_OBJECT_PARAMETERS: [&str; 13] = [
+    "response_type",
+    "redirect_uri",
+    "scope",
+    "state",
+    "code_challenge",
+    "code_challenge_method",
+    "nonce",
+    "max_age",
+    "response_mode",
+    "prompt",
+    "login_hint",
+    "ui_locales",
+    "claims",
+];
+
+/// 授权端点支持的 response_mode（默认为 query）
+pub const SUPPORTED_RESPONSE_MODES: [&str; 3] = ["query", "fragment", "form_post"];
+
+/// 授权请求中的 prompt 参数（OpenID Connect Core 3.1.2.1）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Prompt {
+    /// 不显示任何页面，无法直接签发授权码时返回错误
+    pub none: bool,
+    /// 要求用户重新认证
+    pub login: bool,
+    /// 要求用户重新确认授权
+    pub consent: bool,
+}
+
+/// 支持的 prompt 取值（只有一个账号时 select_account 无需处理）
+pub const SUPPORTED_PROMPT_VALUES: [&str; 4] = ["none", "login", "consent", "select_account"];
+
+impl Prompt {
+    /// 解析以空格分隔的 prompt，包含不支持的值或 none 与其他值同时出现时返回 None
+    pub(crate) fn parse(prompt: Option<&str>) -> Option<Self> {
+        let values = parse_scopes(prompt.unwrap_or_default());
+        if values
+            .iter()
+            .any(|value| !SUPPORTED_PROMPT_VALUES.contains(&value.as_str()))
+        {
+            return None;
+        }
+
+        let prompt = Prompt {
+            none: values.iter().any(|value| value == "none"),
+            login: values.iter().any(|value| value == "login"),
+            consent: values.iter().any(|value| value == "consent"),
+        };
+        if prompt.none && values.len() > 1 {
+            return None;
+        }
+        Some(prompt)
+    }
+}
+
+/// 已通过验证、等待签发授权码的授权请求（等待用户同意时暂存于缓存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    pub client_id: String,
+    pub user_id: i64,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+    pub max_age: Option<i64>,
+    /// 用户完成认证的时间戳
+    pub auth_time: Option<i64>,
+    /// 用户界面的首选语言（原样交给前端）
+    pub ui_locales: Option<String>,
+    /// 返回授权结果的方式（为空时使用 query）
+    pub response_mode: Option<String>,
+    /// 请求返回的用户 claims（JSON）
+    #[serde(default)]
+    pub claims: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,23 +158,99 @@ pub struct AuthorizeResponse {
     pub state: Option<String>,
 }
 
+/// 请求体中的客户端凭证（client_secret_post / private_key_jwt）
+#[derive(Debug, Default, Deserialize)]
+pub struct ClientCredentials {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TokenRequest {
     pub grant_type: String,
     pub code: Option<String>,
-    pub client_id: String,
-    pub client_secret: String,
-    pub redirect_uri: String,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    #[serde(flatten)]
+    pub credentials: ClientCredentials,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub device_code: Option<String>,
+    /// Token Exchange 参数（RFC 8693 2.1）
+    pub subject_token: Option<String>,
+    pub subject_token_type: Option<String>,
+    pub actor_token: Option<String>,
+    pub actor_token_type: Option<String>,
+    pub requested_token_type: Option<String>,
+    pub audience: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TokenResponse {
     pub access_token: String,
-    pub refresh_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub token_type: String,
     pub expires_in: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id_token: Option<String>,
+    /// Token Exchange 签发的 token 类型
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued_token_type: Option<String>,
+}
+
+/// 已通过验证的授权请求参数
+#[derive(Debug)]
+pub(crate) struct ValidatedAuthorization {
+    pub client: o_auth_clients::Model,
+    pub scopes: Vec<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub response_mode: String,
+    pub prompt: Prompt,
+}
+
+/// 授权请求验证失败
+#[derive(Debug)]
+pub(crate) enum AuthorizeError {
+    /// client_id 或 redirect_uri 无效等无法重定向的错误，直接返回给用户代理
+    Invalid(AppError),
+    /// redirect_uri 已验证，通过重定向返回给客户端的错误码（RFC 6749 4.1.2.1）
+    Redirect {
+        error: &'static str,
+        response_mode: Option<String>,
+    },
+}
+
+impl From<AppError> for AuthorizeError {
+    fn from(error: AppError) -> Self {
+        AuthorizeError::Invalid(error)
+    }
+}
+
+impl From<AuthorizeError> for AppError {
+    fn from(error: AuthorizeError) -> Self {
+        match error {
+            AuthorizeError::Invalid(error) => error,
+            AuthorizeError::Redirect {
+                error: "invalid_scope",
+                ..
+            } => AppError::InvalidScope,
+            AuthorizeError::Redirect {
+                error: "unauthorized_client",
+                ..
+            } => AppError::UnauthorizedClient(
+                "Client is not allowed to use authorization_code".into(),
+            ),
+            AuthorizeError::Redirect { error, .. } => {
+                AppError::BadRequest(format!("Invalid authorization request: {}", error))
+            }
+        }
+    }
 }
 
 /// GET /oauth/authorize
@@ -50,10 +261,126 @@ pub async fn authorize(
     storage: web::Data<Arc<SeaOrmBackend>>,
     cache: web::Data<Arc<CompositeCache>>,
     jwt_manager: web::Data<Arc<JwtManager>>,
+    config: web::Data<AppConfig>,
 ) -> Result<HttpResponse, AppError> {
+    // 1. 使用 request_uri 时只采用推送的授权请求参数（RFC 9126 4），否则解析请求对象
+    let request_uri = query.request_uri.clone();
+    let query = match request_uri.as_deref() {
+        Some(_) if query.request.is_some() => {
+            return Err(AppError::BadRequest(
+                "request and request_uri cannot be used together".into(),
+            ));
+        }
+        Some(request_uri) => {
+            par_service::load_pushed_request(&cache, request_uri, &query.client_id).await?
+        }
+        None => resolve_request_object(&storage, &cache, &config, query.into_inner()).await?,
+    };
+
+    // 2. 验证授权请求
+    let ValidatedAuthorization {
+        client,
+        scopes,
+        code_challenge,
+        code_challenge_method,
+        response_mode,
+        prompt,
+    } = match validate_authorize_request(&storage, &config, &query, request_uri.is_some()).await {
+        Ok(validated) => validated,
+        Err(AuthorizeError::Invalid(e)) => return Err(e),
+        Err(AuthorizeError::Redirect {
+            error,
+            response_mode,
+        }) => {
+            return Ok(AuthorizationResponse::error(
+                &query.redirect_uri,
+                response_mode.as_deref(),
+                error,
+                query.state.as_deref(),
+            )
+            .into_response());
+        }
+    };
+
+    // 3. 从请求中提取用户身份（通过 JWT token 或 session），prompt=none 时不能要求登录
+    let session = match authenticated_session(&req, &storage, &jwt_manager, &cache).await {
+        Ok(session) => session,
+        Err(_) if prompt.none => return Ok(authorize_error(&query, "login_required")),
+        Err(e) => return Err(e),
+    };
+
+    // 验证用户是否存在
+    let user = storage
+        .find_by_id(session.user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // request_uri 只能使用一次（用户完成认证前允许重复请求，例如登录后重新发起授权）
+    if let Some(request_uri) = request_uri.as_deref() {
+        par_service::consume_pushed_request(&cache, request_uri).await;
+    }
+
+    let pending = PendingAuthorization {
+        client_id: query.client_id.clone(),
+        user_id: user.id,
+        redirect_uri: query.redirect_uri.clone(),
+        scope: scopes.join(" "),
+        state: query.state.clone(),
+        code_challenge,
+        code_challenge_method,
+        nonce: query.nonce.clone(),
+        max_age: query.max_age.map(i64::from),
+        auth_time: session.auth_time,
+        ui_locales: query.ui_locales.clone(),
+        response_mode: Some(response_mode),
+        claims: query.claims.clone(),
+    };
+
+    // 4. 要求重新认证（prompt=login、超过 max_age 或 login_hint 指向其他用户）
+    if prompt.login
+        || !within_max_age(pending.auth_time, pending.max_age)
+        || !matches_login_hint(&user, query.login_hint.as_deref())
+    {
+        if prompt.none {
+            return Ok(authorize_error(&query, "login_required"));
+        }
+        return reauth_service::request_login(
+            &cache,
+            &client,
+            pending,
+            prompt.consent,
+            query.login_hint.clone(),
+        )
+        .await;
+    }
+
+    // 5. 用户尚未同意请求的 scope（或 prompt=consent）时，返回同意页面所需信息
+    if prompt.consent || !consent_service::has_consent(&storage, &pending).await? {
+        if prompt.none {
+            return Ok(authorize_error(&query, "consent_required"));
+        }
+        return consent_service::request_consent(&cache, &client, pending).await;
+    }
+
+    // 6. 签发授权码，按 response_mode 返回
+    let response = issue_authorization_code(&storage, &cache, &client, &pending).await?;
+
+    Ok(response.into_response())
+}
+
+/// 验证授权请求参数（授权端点和 PAR 端点共用）
+///
+/// `pushed` 表示请求是否通过 PAR 端点提交。请求对象需要先通过
+/// `resolve_request_object` 合并到请求参数中
+pub(crate) async fn validate_authorize_request(
+    storage: &SeaOrmBackend,
+    config: &AppConfig,
+    query: &AuthorizeRequest,
+    pushed: bool,
+) -> Result<ValidatedAuthorization, AuthorizeError> {
     // 1. 验证 response_type
     if query.response_type != "code" {
-        return Err(AppError::BadRequest("Unsupported response_type".into()));
+        return Err(AppError::BadRequest("Unsupported response_type".into()).into());
     }
 
     // 2. 验证 client_id
@@ -61,80 +388,193 @@ pub async fn authorize(
         .find_by_client_id(&query.client_id)
         .await?
         .ok_or(AppError::InvalidClient)?;
+    if client.require_pushed_authorization_requests && !pushed {
+        return Err(AppError::BadRequest(
+            "Pushed authorization request is required for this client".into(),
+        )
+        .into());
+    }
+    if client.require_signed_request_object && query.request.is_none() {
+        return Err(AppError::BadRequest(
+            "Signed request object is required for this client".into(),
+        )
+        .into());
+    }
 
     // 3. 验证 redirect_uri
     if !storage
         .verify_redirect_uri(&query.client_id, &query.redirect_uri)
         .await?
     {
-        return Err(AppError::InvalidRedirectUri);
+        return Err(AppError::InvalidRedirectUri.into());
+    }
+
+    // 4. 验证 response_mode（之后的错误按该模式返回给客户端）
+    let response_mode = query.response_mode.as_deref().unwrap_or("query");
+    if !SUPPORTED_RESPONSE_MODES.contains(&response_mode)
+        || !is_response_mode_allowed(&client, response_mode)
+    {
+        return Err(AuthorizeError::Redirect {
+            error: "invalid_request",
+            response_mode: None,
+        });
+    }
+    let redirect_error = |error| AuthorizeError::Redirect {
+        error,
+        response_mode: Some(response_mode.to_string()),
+    };
+    if check_grant_type(&client, "authorization_code").is_err() {
+        return Err(redirect_error("unauthorized_client"));
     }
 
-    // 4. 从请求中提取用户身份（通过 JWT token 或 session）
-    // 优先从 Authorization header 获取 JWT
-    let user_id = if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                // 验证并解析 JWT
-                let claims = jwt_manager.verify_token(token)?;
-
-                // 检查黑名单
-                if cache.exists(&format!("blacklist:{}", token)).await {
-                    return Err(AppError::Unauthorized);
-                }
-
-                claims
-                    .sub
-                    .parse::<i64>()
-                    .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?
-            } else {
-                return Err(AppError::Unauthorized);
+    // 5. 验证 PKCE 参数（公共客户端必须使用 PKCE）
+    let (code_challenge, code_challenge_method) = match &query.code_challenge {
+        Some(challenge) => {
+            let method = query.code_challenge_method.as_deref().unwrap_or("plain");
+            if !pkce::SUPPORTED_CHALLENGE_METHODS.contains(&method) {
+                return Err(
+                    AppError::BadRequest("Unsupported code_challenge_method".into()).into(),
+                );
             }
-        } else {
-            return Err(AppError::Unauthorized);
+            if !pkce::is_valid_code_value(challenge) {
+                return Err(AppError::BadRequest("Invalid code_challenge".into()).into());
+            }
+            (Some(challenge.clone()), Some(method.to_string()))
         }
-    } else {
-        // 如果没有 Authorization header，尝试从请求扩展中获取（如果使用了认证中间件）
-        let claims = req
-            .extensions()
-            .get::<Claims>()
-            .cloned()
-            .ok_or_else(|| AppError::Unauthorized)?;
-
-        claims
-            .sub
-            .parse::<i64>()
-            .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?
+        None if client.require_pkce || is_public_client(&client) => {
+            return Err(
+                AppError::BadRequest("code_challenge is required for this client".into()).into(),
+            );
+        }
+        None => (None, None),
     };
 
-    // 验证用户是否存在
-    storage
-        .find_by_id(user_id)
+    // 6. 确定授予的 scope
+    let Ok(scopes) = grant_scopes(
+        &client,
+        query.scope.as_deref(),
+        &config.auth.supported_scopes,
+    ) else {
+        return Err(redirect_error("invalid_scope"));
+    };
+
+    // 7. 解析 prompt
+    let Some(prompt) = Prompt::parse(query.prompt.as_deref()) else {
+        return Err(redirect_error("invalid_request"));
+    };
+
+    // 8. 验证 claims 参数（请求的 claims 必须属于客户端允许的 scope）
+    if let Some(claims) = query.claims.as_deref() {
+        let Some(request) = ClaimsRequest::parse(claims) else {
+            return Err(redirect_error("invalid_request"));
+        };
+        let allowed = parse_scopes(&client.allowed_scopes);
+        if request.scopes().iter().any(|scope| {
+            !allowed.iter().any(|allowed| allowed == scope)
+                || !config.auth.supported_scopes.iter().any(|s| s == scope)
+        }) {
+            return Err(redirect_error("invalid_scope"));
+        }
+    }
+
+    Ok(ValidatedAuthorization {
+        client,
+        scopes,
+        code_challenge,
+        code_challenge_method,
+        response_mode: response_mode.to_string(),
+        prompt,
+    })
+}
+
+/// 验证请求中的 request 参数（RFC 9101），并以请求对象中的参数作为授权请求参数
+///
+/// 只使用请求对象中的参数和请求参数中的 client_id，请求参数中的其他参数被忽略（RFC 9101 5）。
+/// 请求对象使用客户端登记的 jwks 或 jwks_uri 验证
+pub(crate) async fn resolve_request_object(
+    storage: &SeaOrmBackend,
+    cache: &CompositeCache,
+    config: &AppConfig,
+    request: AuthorizeRequest,
+) -> Result<AuthorizeRequest, AppError> {
+    let Some(request_object) = request.request.as_deref() else {
+        return Ok(request);
+    };
+
+    // 1. 使用客户端公钥验证请求对象
+    request_object_algorithm(request_object)?;
+    let client = storage
+        .find_by_client_id(&request.client_id)
         .await?
-        .ok_or(AppError::NotFound)?;
+        .ok_or(AppError::InvalidClient)?;
+    let kid = decode_header(request_object)
+        .ok()
+        .and_then(|header| header.kid);
+    let jwks = client_jwks(cache, &client, kid.as_deref())
+        .await
+        .map_err(|_| AppError::InvalidRequestObject("Client has no usable JWKS".into()))?;
+    let claims = verify_request_object(request_object, &jwks, &client.client_id, &config.issuer())?;
 
-    // 5. 读取认证策略配置（从数据库）
+    // 2. 由请求对象中的参数构造授权请求（保留 request 用于判断请求是否经过签名）
+    let mut resolved = json!({
+        "client_id": request.client_id,
+        "request": request_object,
+    });
+    for name in REQUEST_OBJECT_PARAMETERS {
+        match claims.get(name) {
+            // 请求对象中的 claims 参数是 JSON 对象，请求参数中是其序列化后的字符串
+            Some(value) if name == "claims" && value.is_object() => {
+                resolved[name] = Value::String(value.to_string());
+            }
+            Some(value) => resolved[name] = value.clone(),
+            None => {}
+        }
+    }
+
+    serde_json::from_value(resolved).map_err(|e| {
+        AppError::InvalidRequestObject(format!("Invalid request object parameter: {}", e))
+    })
+}
+
+/// 生成授权码并返回包含 code 的授权响应
+pub(crate) async fn issue_authorization_code(
+    storage: &SeaOrmBackend,
+    cache: &CompositeCache,
+    client: &o_auth_clients::Model,
+    pending: &PendingAuthorization,
+) -> Result<AuthorizationResponse, AppError> {
+    // 1. 读取认证策略配置（从数据库）
     let auth_policy = storage.get_auth_policy_config().await?;
 
-    // 6. 生成授权码
+    // 2. 生成授权码
     let code = generate_random_token(32);
 
-    // 7. 计算过期时间
+    // 3. 计算过期时间
     let expires_at = Utc::now() + Duration::seconds(auth_policy.authorization_code_expire);
 
-    // 8. 保存授权码到数据库
+    // 4. 保存授权码到数据库
     storage
         .save_auth_code(
             &code,
-            &query.client_id,
-            user_id,
-            &query.redirect_uri,
-            &query.scope.clone().unwrap_or_default(),
-            expires_at,
+            NewAuthCode {
+                client_id: pending.client_id.clone(),
+                user_id: pending.user_id,
+                redirect_uri: pending.redirect_uri.clone(),
+                scopes: pending.scope.clone(),
+                expires_at,
+                code_challenge: pending.code_challenge.clone(),
+                code_challenge_method: pending.code_challenge_method.clone(),
+                nonce: pending.nonce.clone(),
+                max_age: pending.max_age,
+                auth_time: pending
+                    .auth_time
+                    .and_then(|at| DateTime::from_timestamp(at, 0)),
+                claims: pending.claims.clone(),
+            },
         )
         .await?;
 
-    // 9. 缓存授权码（用于快速验证）
+    // 5. 缓存授权码（用于快速验证）
     cache
         .set(
             &format!("authcode:{}", code),
@@ -146,35 +586,556 @@ pub async fn authorize(
     tracing::info!(
         "Authorization code generated for client: {} user: {}",
         client.name,
-        user_id
+        pending.user_id
     );
 
-    // 10. 构造重定向 URL
-    let mut redirect_url = format!("{}?code={}", query.redirect_uri, code);
-    if let Some(ref state) = query.state {
-        redirect_url.push_str(&format!("&state={}", state));
+    // 6. 构造授权响应
+    let mut params = vec![("code", code)];
+    if let Some(state) = &pending.state {
+        params.push(("state", state.clone()));
     }
 
-    // 返回 307 重定向
-    Ok(HttpResponse::TemporaryRedirect()
-        .insert_header(("Location", redirect_url))
-        .finish())
+    Ok(AuthorizationResponse {
+        redirect_uri: pending.redirect_uri.clone(),
+        response_mode: pending.response_mode.clone(),
+        params,
+    })
+}
+
+/// 按 response_mode 返回给客户端的授权响应
+#[derive(Debug)]
+pub(crate) struct AuthorizationResponse {
+    pub redirect_uri: String,
+    pub response_mode: Option<String>,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl AuthorizationResponse {
+    /// 授权错误响应（RFC 6749 4.1.2.1）
+    pub(crate) fn error(
+        redirect_uri: &str,
+        response_mode: Option<&str>,
+        error: &str,
+        state: Option<&str>,
+    ) -> Self {
+        let mut params = vec![("error", error.to_string())];
+        if let Some(state) = state {
+            params.push(("state", state.to_string()));
+        }
+
+        Self {
+            redirect_uri: redirect_uri.to_string(),
+            response_mode: response_mode.map(str::to_string),
+            params,
+        }
+    }
+
+    pub(crate) fn is_form_post(&self) -> bool {
+        self.response_mode.as_deref() == Some("form_post")
+    }
+
+    /// 重定向地址（参数编码到 query 或 fragment 中，form_post 时为 redirect_uri 本身）
+    pub(crate) fn redirect_url(&self) -> String {
+        let encoded = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.params)
+            .finish();
+
+        match self.response_mode.as_deref() {
+            Some("form_post") => self.redirect_uri.clone(),
+            Some("fragment") => format!("{}#{}", self.redirect_uri, encoded),
+            _ => {
+                // 保留 redirect_uri 中已有的查询参数
+                let separator = if self.redirect_uri.contains('?') {
+                    '&'
+                } else {
+                    '?'
+                };
+                format!("{}{}{}", self.redirect_uri, separator, encoded)
+            }
+        }
+    }
+
+    /// form_post 模式下需要提交的参数
+    pub(crate) fn form_params(&self) -> Vec<(&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect()
+    }
+
+    /// query / fragment 返回 307 重定向，form_post 返回自动提交的表单页面
+    pub(crate) fn into_response(self) -> HttpResponse {
+        if self.is_form_post() {
+            return HttpResponse::Ok()
+                .content_type("text/html; charset=utf-8")
+                .insert_header((header::CACHE_CONTROL, "no-store"))
+                .body(html::auto_submit_form(
+                    &self.redirect_uri,
+                    &self.form_params(),
+                ));
+        }
+
+        HttpResponse::TemporaryRedirect()
+            .insert_header((header::LOCATION, self.redirect_url()))
+            .finish()
+    }
+}
+
+/// redirect_uri 和 response_mode 验证通过后，按 response_mode 返回授权错误
+fn authorize_error(query: &AuthorizeRequest, error: &str) -> HttpResponse {
+    AuthorizationResponse::error(
+        &query.redirect_uri,
+        query.response_mode.as_deref(),
+        error,
+        query.state.as_deref(),
+    )
+    .into_response()
+}
+
+/// 用户最近一次认证是否在 max_age 秒以内（未指定 max_age 时不限制）
+pub(crate) fn within_max_age(auth_time: Option<i64>, max_age: Option<i64>) -> bool {
+    match max_age {
+        Some(max_age) => auth_time.is_some_and(|at| Utc::now().timestamp() - at <= max_age),
+        None => true,
+    }
+}
+
+/// login_hint 是否指向当前用户（用户名或邮箱，未提供时视为匹配）
+fn matches_login_hint(user: &users::Model, login_hint: Option<&str>) -> bool {
+    login_hint.is_none_or(|hint| hint == user.username || hint.eq_ignore_ascii_case(&user.email))
+}
+
+/// 当前登录用户的会话
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UserSession {
+    pub user_id: i64,
+    /// 用户完成本次会话认证的时间（记录在会话 Token 中，而不是用户最近一次登录时间）
+    pub auth_time: Option<i64>,
+}
+
+/// 从请求中提取当前登录用户及其会话的认证时间（授权端点、重新认证端点共用）
+pub(crate) async fn authenticated_session(
+    req: &HttpRequest,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+) -> Result<UserSession, AppError> {
+    let user_id = authenticated_user_id(req, jwt_manager, cache).await?;
+
+    let auth_time = match bearer_token(req) {
+        Ok(token) => storage
+            .find_access_token(&token)
+            .await?
+            .filter(|record| record.user_id == Some(user_id))
+            .and_then(|record| record.auth_time)
+            .map(|at| at.timestamp()),
+        Err(_) => None,
+    };
+
+    Ok(UserSession { user_id, auth_time })
+}
+
+/// 从请求中提取当前登录用户（授权端点、设备验证端点共用）
+pub(crate) async fn authenticated_user_id(
+    req: &HttpRequest,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+) -> Result<i64, AppError> {
+    // 优先从 Authorization header 获取 JWT（Bearer 或 DPoP）
+    if req.headers().contains_key(header::AUTHORIZATION) {
+        let claims = authenticate_token(req, jwt_manager, cache).await?;
+
+        // 客户端 Token 不代表任何用户
+        if claims.is_client() {
+            return Err(AppError::Unauthorized);
+        }
+
+        // 用户会话 Token 的 sub 是用户 ID；pairwise 标识只出现在第三方客户端的 Token 中
+        return claims
+            .sub
+            .parse::<i64>()
+            .map_err(|_| AppError::Unauthorized);
+    }
+
+    // 如果没有 Authorization header，尝试从请求扩展中获取（如果使用了认证中间件）
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| AppError::Unauthorized)?;
+
+    claims
+        .sub
+        .parse::<i64>()
+        .map_err(|_| AppError::Unauthorized)
 }
 
 /// POST /oauth/token
-/// 授权码换取 access token
+/// 按 grant_type 签发 access token
 pub async fn token(
+    http_req: HttpRequest,
     req: web::Json<TokenRequest>,
     storage: web::Data<Arc<SeaOrmBackend>>,
     jwt_manager: web::Data<Arc<JwtManager>>,
     cache: web::Data<Arc<CompositeCache>>,
+    config: web::Data<AppConfig>,
 ) -> Result<HttpResponse, AppError> {
     // 1. 验证 grant_type
-    if req.grant_type != "authorization_code" {
+    if !SUPPORTED_GRANT_TYPES.contains(&req.grant_type.as_str()) {
         return Err(AppError::InvalidGrantType);
     }
 
-    // 2. 验证授权码
+    // 2. 验证客户端身份及其是否允许使用该 grant_type
+    let client = authenticate_client(&storage, &cache, &http_req, &req.credentials).await?;
+    check_grant_type(&client, &req.grant_type)?;
+
+    // 3. 携带 DPoP 证明时，签发的 token 绑定证明的公钥（RFC 9449 5）
+    let dpop_jkt = dpop_service::check_proof(&http_req, &cache, None).await?;
+    let dpop_jkt = dpop_jkt.as_deref();
+
+    // 4. 按授权类型处理
+    let response = match req.grant_type.as_str() {
+        "refresh_token" => {
+            let refresh_token = req
+                .refresh_token
+                .as_ref()
+                .ok_or(AppError::BadRequest("Missing refresh_token".into()))?;
+
+            refresh_token_grant(
+                &storage,
+                &jwt_manager,
+                &cache,
+                &client.client_id,
+                refresh_token,
+                req.scope.as_deref(),
+                dpop_jkt,
+            )
+            .await?
+        }
+        "client_credentials" => {
+            let scopes =
+                grant_scopes(&client, req.scope.as_deref(), &config.auth.supported_scopes)?;
+            client_credentials_grant(&client, scopes, &storage, &jwt_manager, &cache, dpop_jkt)
+                .await?
+        }
+        DEVICE_CODE_GRANT_TYPE => {
+            let device_code = req
+                .device_code
+                .as_ref()
+                .ok_or(AppError::BadRequest("Missing device_code".into()))?;
+
+            device_service::device_code_grant(
+                &storage,
+                &jwt_manager,
+                &cache,
+                &client,
+                device_code,
+                dpop_jkt,
+            )
+            .await?
+        }
+        TOKEN_EXCHANGE_GRANT_TYPE => {
+            token_exchange_service::token_exchange_grant(
+                &req,
+                &client,
+                &storage,
+                &jwt_manager,
+                &cache,
+                dpop_jkt,
+            )
+            .await?
+        }
+        _ => {
+            authorization_code_grant(&req, &client, &storage, &jwt_manager, &cache, dpop_jkt)
+                .await?
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// 验证客户端身份（client_secret_basic / client_secret_post / private_key_jwt，公共客户端无需 secret）
+pub(crate) async fn authenticate_client(
+    storage: &SeaOrmBackend,
+    cache: &CompositeCache,
+    req: &HttpRequest,
+    credentials: &ClientCredentials,
+) -> Result<o_auth_clients::Model, AppError> {
+    // 通过 Authorization 头认证失败时返回 401 并携带 WWW-Authenticate（RFC 6749 5.2）
+    match verify_client_credentials(storage, cache, req, credentials).await {
+        Err(AppError::InvalidClient) if basic_authorization(req).is_some() => {
+            Err(AppError::InvalidClientBasic)
+        }
+        result => result,
+    }
+}
+
+/// 按客户端提供的认证方式校验凭证
+async fn verify_client_credentials(
+    storage: &SeaOrmBackend,
+    cache: &CompositeCache,
+    req: &HttpRequest,
+    credentials: &ClientCredentials,
+) -> Result<o_auth_clients::Model, AppError> {
+    let basic = basic_credentials(req)?;
+
+    // 1. 客户端断言（RFC 7523，不能同时使用多种认证方式）
+    if let Some(assertion) = credentials.client_assertion.as_deref() {
+        if credentials.client_assertion_type.as_deref() != Some(JWT_BEARER_ASSERTION_TYPE) {
+            return Err(AppError::BadRequest(
+                "Unsupported client_assertion_type".into(),
+            ));
+        }
+        if basic.is_some() || credentials.client_secret.is_some() {
+            return Err(AppError::BadRequest(
+                "Multiple client authentication methods".into(),
+            ));
+        }
+        return authenticate_client_assertion(
+            storage,
+            cache,
+            req,
+            credentials.client_id.as_deref(),
+            assertion,
+        )
+        .await;
+    }
+
+    // 2. 优先使用 HTTP Basic 凭证（RFC 6749 2.3.1）
+    let (client_id, client_secret) = match basic {
+        Some((basic_id, basic_secret)) => {
+            if credentials.client_secret.is_some() {
+                return Err(AppError::BadRequest(
+                    "Multiple client authentication methods".into(),
+                ));
+            }
+            if credentials
+                .client_id
+                .as_deref()
+                .is_some_and(|id| id != basic_id)
+            {
+                return Err(AppError::InvalidClient);
+            }
+            (basic_id, Some(basic_secret))
+        }
+        None => (
+            credentials
+                .client_id
+                .clone()
+                .ok_or(AppError::InvalidClient)?,
+            credentials.client_secret.clone(),
+        ),
+    };
+
+    let client = storage
+        .find_by_client_id(&client_id)
+        .await?
+        .ok_or(AppError::InvalidClient)?;
+
+    if is_public_client(&client) {
+        return Ok(client);
+    }
+
+    // 3. 校验密钥哈希（private_key_jwt 客户端不能使用 secret）
+    match client_secret {
+        Some(secret)
+            if client.token_endpoint_auth_method != PRIVATE_KEY_JWT
+                && ClientSecretManager::verify_secret(&secret, &client.client_secret) =>
+        {
+            Ok(client)
+        }
+        _ => Err(AppError::InvalidClient),
+    }
+}
+
+/// 验证 private_key_jwt 客户端断言，jti 只能使用一次
+async fn authenticate_client_assertion(
+    storage: &SeaOrmBackend,
+    cache: &CompositeCache,
+    req: &HttpRequest,
+    client_id: Option<&str>,
+    assertion: &str,
+) -> Result<o_auth_clients::Model, AppError> {
+    // 1. 断言的 sub 即 client_id
+    let subject = assertion_subject(assertion)?;
+    if client_id.is_some_and(|id| id != subject) {
+        return Err(AppError::InvalidClient);
+    }
+
+    let client = storage
+        .find_by_client_id(&subject)
+        .await?
+        .filter(|client| client.token_endpoint_auth_method == PRIVATE_KEY_JWT)
+        .ok_or(AppError::InvalidClient)?;
+
+    // 2. 验证签名及 iss、sub、aud、exp（aud 可以是 issuer、Token 端点或当前端点）
+    let kid = decode_header(assertion).ok().and_then(|header| header.kid);
+    let jwks = client_jwks(cache, &client, kid.as_deref()).await?;
+    let issuer = get_config().issuer();
+    let audiences = vec![
+        format!("{}/oauth/token", issuer),
+        format!("{}{}", issuer, req.path()),
+        issuer,
+    ];
+    let claims = verify_assertion(assertion, &jwks, &client.client_id, &audiences)?;
+
+    // 3. 防重放：在断言过期前记录已使用的 jti（并发提交同一断言时只有一个请求成功）
+    let key = format!("client_assertion:{}:{}", client.client_id, claims.jti);
+    let ttl = (claims.exp - Utc::now().timestamp()).max(0) as u64 + VALIDATION_LEEWAY;
+    if !cache.set_if_absent(&key, "1".to_string(), Some(ttl)).await {
+        tracing::warn!("Replayed client assertion for client: {}", client.client_id);
+        return Err(AppError::InvalidClient);
+    }
+
+    Ok(client)
+}
+
+/// 客户端公钥集：优先使用登记的 jwks，否则从 jwks_uri 获取并缓存
+///
+/// 缓存的公钥集中找不到 `kid` 对应的公钥时（客户端轮换了密钥）重新获取，
+/// 每个客户端在 `CLIENT_JWKS_REFETCH_INTERVAL` 内最多重新获取一次
+async fn client_jwks(
+    cache: &CompositeCache,
+    client: &o_auth_clients::Model,
+    kid: Option<&str>,
+) -> Result<String, AppError> {
+    if let Some(jwks) = &client.jwks {
+        return Ok(jwks.clone());
+    }
+
+    let jwks_uri = client.jwks_uri.as_deref().ok_or(AppError::InvalidClient)?;
+    let key = format!("client_jwks:{}", client.client_id);
+    if let Some(jwks) = cache.get(&key).await {
+        let refetch = client_public_key(&jwks, kid).is_err()
+            && cache
+                .set_if_absent(
+                    &format!("client_jwks_refetch:{}", client.client_id),
+                    "1".to_string(),
+                    Some(CLIENT_JWKS_REFETCH_INTERVAL),
+                )
+                .await;
+        if !refetch {
+            return Ok(jwks);
+        }
+    }
+
+    let response = http_client::get(jwks_uri).await.map_err(|e| {
+        tracing::warn!("Failed to fetch JWKS of {}: {}", client.client_id, e);
+        AppError::InvalidClient
+    })?;
+    if !response.is_success() {
+        tracing::warn!(
+            "Failed to fetch JWKS of {}: HTTP {}",
+            client.client_id,
+            response.status
+        );
+        return Err(AppError::InvalidClient);
+    }
+
+    let jwks = String::from_utf8(response.body).map_err(|_| AppError::InvalidClient)?;
+    cache
+        .set(&key, jwks.clone(), Some(CLIENT_JWKS_CACHE_TTL))
+        .await;
+    Ok(jwks)
+}
+
+/// Authorization: Basic 头中编码后的凭证
+fn basic_authorization(req: &HttpRequest) -> Option<&str> {
+    let header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())?;
+
+    let (scheme, encoded) = header.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("Basic").then_some(encoded)
+}
+
+/// 解析 Authorization: Basic 头中的 client_id 和 client_secret
+fn basic_credentials(req: &HttpRequest) -> Result<Option<(String, String)>, AppError> {
+    let Some(encoded) = basic_authorization(req) else {
+        return Ok(None);
+    };
+
+    let decoded = STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(AppError::InvalidClient)?;
+    let (client_id, client_secret) = decoded.split_once(':').ok_or(AppError::InvalidClient)?;
+
+    // client_id 和 client_secret 在编码前经过 application/x-www-form-urlencoded 编码
+    let form_decode = |value: &str| {
+        percent_decode_str(&value.replace('+', " "))
+            .decode_utf8()
+            .map(|value| value.into_owned())
+            .map_err(|_| AppError::InvalidClient)
+    };
+
+    Ok(Some((form_decode(client_id)?, form_decode(client_secret)?)))
+}
+
+/// 使用客户端断言认证的 token_endpoint_auth_method
+pub(crate) const PRIVATE_KEY_JWT: &str = "private_key_jwt";
+
+/// 支持的客户端认证方式
+pub const TOKEN_ENDPOINT_AUTH_METHODS: &[&str] = &[
+    "client_secret_basic",
+    "client_secret_post",
+    PRIVATE_KEY_JWT,
+    "none",
+];
+
+/// 从 jwks_uri 获取的客户端公钥集缓存时间（秒）
+const CLIENT_JWKS_CACHE_TTL: u64 = 300;
+
+/// 因 kid 未知重新获取客户端公钥集的最短间隔（秒）
+const CLIENT_JWKS_REFETCH_INTERVAL: u64 = 10;
+
+/// 验证客户端是否允许使用指定的 grant_type（未登记 grant_types 的客户端不受限制）
+pub(crate) fn check_grant_type(
+    client: &o_auth_clients::Model,
+    grant_type: &str,
+) -> Result<(), AppError> {
+    let Some(grant_types) = &client.grant_types else {
+        return Ok(());
+    };
+
+    let allowed: Vec<String> = serde_json::from_str(grant_types).unwrap_or_default();
+    if allowed.iter().any(|allowed| allowed == grant_type) {
+        Ok(())
+    } else {
+        Err(AppError::UnauthorizedClient(format!(
+            "Client is not allowed to use {}",
+            grant_type
+        )))
+    }
+}
+
+/// 客户端是否可以使用该 response_mode（未登记 response_modes 时不限制）
+fn is_response_mode_allowed(client: &o_auth_clients::Model, response_mode: &str) -> bool {
+    client.response_modes.as_deref().is_none_or(|modes| {
+        serde_json::from_str::<Vec<String>>(modes)
+            .unwrap_or_default()
+            .iter()
+            .any(|mode| mode == response_mode)
+    })
+}
+
+/// 是否为公共客户端（SPA、移动应用等无法保存 secret 的客户端）
+pub(crate) fn is_public_client(client: &o_auth_clients::Model) -> bool {
+    client.token_endpoint_auth_method == "none"
+}
+
+/// 授权码换取 token
+async fn authorization_code_grant(
+    req: &TokenRequest,
+    client: &o_auth_clients::Model,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    dpop_jkt: Option<&str>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 验证授权码
     let code = req
         .code
         .as_ref()
@@ -185,58 +1146,308 @@ pub async fn token(
         .await?
         .ok_or(AppError::InvalidAuthCode)?;
 
-    // 3. 验证授权码是否过期
-    if auth_data.expires_at < Utc::now() {
+    // 2. 验证授权码是否过期、是否属于当前客户端
+    if auth_data.expires_at < Utc::now() || auth_data.client_id != client.client_id {
         return Err(AppError::InvalidAuthCode);
     }
 
-    // 4. 验证 client_id 和 client_secret
-    let client = storage
-        .find_by_client_id(&req.client_id)
-        .await?
-        .ok_or(AppError::InvalidClient)?;
-
-    if client.client_secret != req.client_secret {
-        return Err(AppError::InvalidClient);
+    // 3. 验证 redirect_uri 与授权码中的一致
+    if req.redirect_uri.as_deref() != Some(auth_data.redirect_uri.as_str()) {
+        return Err(AppError::InvalidRedirectUri);
     }
 
-    // 5. 验证 redirect_uri 与授权码中的一致
-    if auth_data.redirect_uri != req.redirect_uri {
-        return Err(AppError::InvalidRedirectUri);
+    // 4. 验证 PKCE code_verifier
+    match (&auth_data.code_challenge, &req.code_verifier) {
+        (Some(challenge), Some(verifier)) => {
+            let method = auth_data
+                .code_challenge_method
+                .as_deref()
+                .unwrap_or("plain");
+            if !pkce::verify_code_verifier(verifier, challenge, method) {
+                return Err(AppError::InvalidGrant("Invalid code_verifier".into()));
+            }
+        }
+        (Some(_), None) => {
+            return Err(AppError::InvalidGrant("Missing code_verifier".into()));
+        }
+        (None, Some(_)) => {
+            return Err(AppError::InvalidGrant(
+                "code_verifier provided but no code_challenge was used".into(),
+            ));
+        }
+        (None, None) => {}
     }
 
-    // 6. 查询用户获取 role
+    // 5. 查询用户获取 role
     let user = storage
         .find_by_id(auth_data.user_id)
         .await?
         .ok_or(AppError::NotFound)?;
 
-    // 7. 读取认证策略配置（从数据库）
+    // 6. 签发 token
+    let response = issue_tokens(
+        storage,
+        jwt_manager,
+        cache,
+        &auth_data.client_id,
+        &user,
+        &auth_data.scopes,
+        AuthContext {
+            nonce: auth_data.nonce.as_deref(),
+            auth_time: auth_data.auth_time.map(|at| at.timestamp()),
+            code: Some(code),
+            dpop_jkt,
+            claims: auth_data.claims.as_deref(),
+        },
+    )
+    .await?;
+
+    // 7. 删除授权码缓存
+    cache.delete(&format!("authcode:{}", code)).await;
+
+    tracing::info!(
+        "Access token issued for client: {} user: {}",
+        client.name,
+        auth_data.user_id
+    );
+
+    Ok(response)
+}
+
+/// 客户端凭证模式（机器对机器，token 的主体是客户端本身）
+async fn client_credentials_grant(
+    client: &o_auth_clients::Model,
+    scopes: Vec<String>,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    dpop_jkt: Option<&str>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 公共客户端无法证明自身身份
+    if is_public_client(client) {
+        return Err(AppError::UnauthorizedClient(
+            "Public clients cannot use client_credentials".into(),
+        ));
+    }
+
+    // 2. 读取认证策略配置（从数据库）
     let auth_policy = storage.get_auth_policy_config().await?;
 
-    // 8. 生成 access_token 和 refresh_token
+    // 3. 生成 access_token（不签发 refresh_token）
+    let access_token = jwt_manager.generate_client_token(
+        &client.client_id,
+        auth_policy.access_token_expire,
+        Some(scopes.clone()),
+        dpop_jkt,
+    )?;
+
+    // 4. 保存 token 到数据库（不关联用户）
+    storage
+        .save_access_token(
+            &access_token,
+            NewAccessToken {
+                client_id: client.client_id.clone(),
+                user_id: None,
+                scopes: scopes.join(" "),
+                expires_at: Utc::now() + Duration::seconds(auth_policy.access_token_expire),
+                claims: None,
+                auth_time: None,
+            },
+        )
+        .await?;
+
+    // 5. 缓存 token
+    cache
+        .set(
+            &format!("token:{}", access_token),
+            client.client_id.clone(),
+            Some(auth_policy.access_token_expire as u64),
+        )
+        .await;
+
+    tracing::info!(
+        "Client credentials token issued for client: {}",
+        client.name
+    );
+
+    Ok(TokenResponse {
+        access_token,
+        refresh_token: None,
+        token_type: dpop::token_type(dpop_jkt).to_string(),
+        expires_in: auth_policy.access_token_expire,
+        scope: Some(scopes.join(" ")),
+        id_token: None,
+        issued_token_type: None,
+    })
+}
+
+/// 使用 refresh token 换取新的 token（同时轮换 refresh token）
+///
+/// 绑定了 DPoP 密钥的 refresh token 只能由持有同一密钥的客户端使用
+pub(crate) async fn refresh_token_grant(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    client_id: &str,
+    refresh_token: &str,
+    scope: Option<&str>,
+    dpop_jkt: Option<&str>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 验证签名及用途
+    let claims = jwt_manager
+        .verify_token(refresh_token)
+        .map_err(|_| AppError::InvalidGrant("Invalid refresh token".into()))?;
+
+    if !claims.is_refresh() {
+        return Err(AppError::InvalidGrant("Invalid refresh token".into()));
+    }
+
+    if let Some(cnf) = &claims.cnf
+        && dpop_jkt != Some(cnf.jkt.as_str())
+    {
+        return Err(AppError::InvalidDpopProof(
+            "DPoP key does not match the refresh token".into(),
+        ));
+    }
+
+    // 2. 查找数据库记录（已轮换或已撤销的 token 不存在）
+    let (stored, access) = storage
+        .find_refresh_token(refresh_token)
+        .await?
+        .ok_or(AppError::InvalidGrant("Invalid refresh token".into()))?;
+
+    if stored.expires_at < Utc::now() {
+        return Err(AppError::InvalidGrant("Refresh token expired".into()));
+    }
+
+    // 3. 验证 refresh token 属于当前客户端
+    if access.client_id != client_id {
+        return Err(AppError::InvalidGrant(
+            "Refresh token was not issued to this client".into(),
+        ));
+    }
+
+    // 4. 验证用户状态
+    let user_id = access
+        .user_id
+        .ok_or(AppError::InvalidGrant("Invalid refresh token".into()))?;
+    let user = storage
+        .find_by_id(user_id)
+        .await?
+        .ok_or(AppError::InvalidGrant("User not found".into()))?;
+
+    if user.deleted_at.is_some() || !user.is_active {
+        return Err(AppError::InvalidGrant("User account is not active".into()));
+    }
+
+    // 5. 请求的 scope 只能是原授权 scope 的子集
+    let scopes = match scope {
+        Some(requested) => {
+            let granted = parse_scopes(&access.scopes);
+            let requested = parse_scopes(requested);
+            if !requested.iter().all(|s| granted.contains(s)) {
+                return Err(AppError::InvalidScope);
+            }
+            requested.join(" ")
+        }
+        None => access.scopes.clone(),
+    };
+
+    // 6. 删除旧 refresh token（并发请求中只有一个能成功）
+    if !storage.delete_refresh_token(stored.id).await? {
+        return Err(AppError::InvalidGrant("Invalid refresh token".into()));
+    }
+
+    // 7. 签发新 token
+    let response = issue_tokens(
+        storage,
+        jwt_manager,
+        cache,
+        client_id,
+        &user,
+        &scopes,
+        AuthContext {
+            dpop_jkt,
+            // 刷新后的 token 保留授权请求中的 claims 参数
+            claims: access.claims.as_deref(),
+            // 沿用原始认证时间，而不是用户最近一次登录时间
+            auth_time: access.auth_time.map(|at| at.timestamp()),
+            ..AuthContext::default()
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        "Refresh token rotated for client: {} user: {}",
+        client_id,
+        user.id
+    );
+
+    Ok(response)
+}
+
+/// 签发 token 时与本次认证相关的信息
+#[derive(Debug, Default)]
+pub(crate) struct AuthContext<'a> {
+    /// 授权请求中的 nonce
+    pub nonce: Option<&'a str>,
+    /// 用户完成认证的时间（随 token 记录保存；未知时 ID Token 使用签发时间）
+    pub auth_time: Option<i64>,
+    /// 换取 token 的授权码（用于 c_hash）
+    pub code: Option<&'a str>,
+    /// 绑定的 DPoP 公钥的 JWK Thumbprint
+    pub dpop_jkt: Option<&'a str>,
+    /// 授权请求中的 claims 参数（JSON）
+    pub claims: Option<&'a str>,
+}
+
+/// 签发 access_token 和 refresh_token 并保存到数据库
+pub(crate) async fn issue_tokens(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    client_id: &str,
+    user: &users::Model,
+    scopes: &str,
+    auth: AuthContext<'_>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 读取认证策略配置（从数据库）
+    let auth_policy = storage.get_auth_policy_config().await?;
+
+    // 2. 确定客户端看到的用户标识（pairwise 客户端按扇区计算）
+    let client = storage.find_by_client_id(client_id).await?;
+    let subject = subject_service::subject_identifier(storage, client.as_ref(), user.id).await?;
+
+    // 3. 生成 access_token 和 refresh_token
     let access_token = jwt_manager.generate_token(
-        auth_data.user_id as i64,
+        &subject,
         auth_policy.access_token_expire,
-        Some(parse_scopes(&auth_data.scopes)),
+        Some(parse_scopes(scopes)),
         &user.role,
+        auth.dpop_jkt,
     )?;
 
     let refresh_token = jwt_manager.generate_token(
-        auth_data.user_id as i64,
+        &subject,
         auth_policy.refresh_token_expire,
-        Some(vec!["refresh".to_string()]),
+        Some(vec![REFRESH_SCOPE.to_string()]),
         &user.role,
+        auth.dpop_jkt,
     )?;
 
-    // 9. 保存 token 到数据库
+    // 4. 保存 token 到数据库（记录认证时间，刷新时沿用）
+    let auth_time = auth.auth_time;
     let access_token_id = storage
         .save_access_token(
             &access_token,
-            &auth_data.client_id,
-            auth_data.user_id,
-            &auth_data.scopes,
-            Utc::now() + Duration::seconds(auth_policy.access_token_expire),
+            NewAccessToken {
+                client_id: client_id.to_string(),
+                user_id: Some(user.id),
+                scopes: scopes.to_string(),
+                expires_at: Utc::now() + Duration::seconds(auth_policy.access_token_expire),
+                claims: auth.claims.map(str::to_string),
+                auth_time: auth_time.and_then(|at| DateTime::from_timestamp(at, 0)),
+            },
         )
         .await?;
 
@@ -248,84 +1459,147 @@ pub async fn token(
         )
         .await?;
 
-    // 10. 缓存 token
+    // 5. 缓存 token
     cache
         .set(
             &format!("token:{}", access_token),
-            auth_data.user_id.to_string(),
+            user.id.to_string(),
             Some(auth_policy.access_token_expire as u64),
         )
         .await;
 
-    // 11. 删除授权码缓存
-    cache.delete(&format!("authcode:{}", code)).await;
-
-    // 12. 生成 OIDC ID Token（如果 scope 包含 openid）
-    let id_token = if auth_data.scopes.contains("openid") {
+    // 6. 生成 OIDC ID Token（如果 scope 包含 openid）
+    let token_type = dpop::token_type(auth.dpop_jkt).to_string();
+    let id_token = if parse_scopes(scopes).iter().any(|s| s == "openid") {
+        // 用户 claims 由授予的 scope 和 claims 参数中的 id_token 成员决定
+        let requested = auth
+            .claims
+            .and_then(ClaimsRequest::parse)
+            .unwrap_or_default();
+        let released = released_claims(&parse_scopes(scopes), requested.id_token.as_ref());
+        let mut id_claims = oidc_service::user_claims(user, &released);
+        // 登录会话标识，与 Back-Channel Logout 的 logout_token 一致
+        if let Some(auth_time) = auth_time {
+            id_claims.insert(
+                "sid".to_string(),
+                json!(logout_service::session_id(user.id, auth_time)),
+            );
+        }
         Some(generate_id_token(
-            &user,
-            &auth_data.client_id,
-            &jwt_manager,
+            id_claims,
+            &subject,
+            client_id,
+            jwt_manager,
             auth_policy.access_token_expire,
+            &access_token,
+            AuthContext { auth_time, ..auth },
         )?)
     } else {
         None
     };
 
-    tracing::info!(
-        "Access token issued for client: {} user: {}",
-        client.name,
-        auth_data.user_id
-    );
-
-    Ok(HttpResponse::Ok().json(TokenResponse {
+    Ok(TokenResponse {
         access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
+        refresh_token: Some(refresh_token),
+        token_type,
         expires_in: auth_policy.access_token_expire,
+        scope: Some(parse_scopes(scopes).join(" ")),
         id_token,
-    }))
+        issued_token_type: None,
+    })
 }
 
+/// ID Token 的认证上下文类引用（仅支持密码登录）
+const ID_TOKEN_ACR: &str = "1";
+const ID_TOKEN_AMR: [&str; 1] = ["pwd"];
+
 /// 生成 OIDC ID Token
 fn generate_id_token(
-    user: &crate::storage::entities::users::Model,
+    user_claims: Map<String, Value>,
+    subject: &str,
     client_id: &str,
     jwt_manager: &JwtManager,
     expires_in: i64,
+    access_token: &str,
+    auth: AuthContext<'_>,
 ) -> Result<String, AppError> {
-    use jsonwebtoken::{EncodingKey, Header, encode};
-    use serde_json::json;
-
     let now = Utc::now();
     let exp = (now + Duration::seconds(expires_in)).timestamp();
     let iat = now.timestamp();
+    let auth_time = auth.auth_time.unwrap_or(iat);
+    let algorithm = jwt_manager.signing_algorithm();
 
     // 构造 ID Token claims
-    let claims = json!({
-        "iss": "ferrusgate",  // Issuer
-        "sub": user.id.to_string(),  // Subject (user_id)
+    let mut claims = json!({
+        "iss": get_config().issuer(),  // Issuer
+        "sub": subject,  // Subject（客户端看到的用户标识）
         "aud": client_id,  // Audience (client_id)
+        "azp": client_id,  // Authorized party
         "exp": exp,  // Expiration time
         "iat": iat,  // Issued at
-        "name": user.username,  // User name
-        "email": user.email,  // User email
-        "email_verified": true,  // Email verification status
+        "auth_time": auth_time,  // 用户完成认证的时间
+        "acr": ID_TOKEN_ACR,
+        "amr": ID_TOKEN_AMR,
+        "at_hash": token_hash(algorithm, access_token),  // Access token hash
     });
 
-    // 使用 JWT manager 的密钥生成 token
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(jwt_manager.secret().as_bytes()),
-    )
-    .map_err(|e| AppError::Internal(format!("Failed to generate ID token: {}", e)))?;
+    for (name, value) in user_claims {
+        claims[name] = value;
+    }
+
+    // nonce 原样返回，供客户端防重放
+    if let Some(nonce) = auth.nonce {
+        claims["nonce"] = json!(nonce);
+    }
+    if let Some(code) = auth.code {
+        claims["c_hash"] = json!(token_hash(algorithm, code));
+    }
+
+    // 使用 JWT manager 的签名密钥生成 token（header 中携带 kid）
+    jwt_manager.encode(&claims)
+}
+
+/// 按服务器支持的 scope 和客户端的 allowed_scopes 确定授予的 scope
+///
+/// 请求未注册的 scope 返回 invalid_scope；客户端无权使用的 scope 被忽略（RFC 6749 3.3）；
+/// 未指定 scope 时授予客户端的全部 allowed_scopes
+pub(crate) fn grant_scopes(
+    client: &o_auth_clients::Model,
+    requested: Option<&str>,
+    supported: &[String],
+) -> Result<Vec<String>, AppError> {
+    let allowed: Vec<String> = parse_scopes(&client.allowed_scopes)
+        .into_iter()
+        .filter(|scope| supported.contains(scope))
+        .collect();
+
+    let requested = requested.map(parse_scopes).unwrap_or_default();
+    if requested.iter().any(|scope| !supported.contains(scope)) {
+        return Err(AppError::InvalidScope);
+    }
+
+    let granted = if requested.is_empty() {
+        allowed
+    } else {
+        let mut granted: Vec<String> = Vec::new();
+        for scope in requested {
+            if allowed.contains(&scope) && !granted.contains(&scope) {
+                granted.push(scope);
+            }
+        }
+        granted
+    };
+
+    // 没有任何可授予的 scope
+    if granted.is_empty() {
+        return Err(AppError::InvalidScope);
+    }
 
-    Ok(token)
+    Ok(granted)
 }
 
 /// 解析 scope 字符串为数组
-fn parse_scopes(scopes: &str) -> Vec<String> {
+pub(crate) fn parse_scopes(scopes: &str) -> Vec<String> {
     if scopes.is_empty() {
         return vec![];
     }
@@ -1,75 +1,159 @@
 use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
 use serde::Serialize;
+use serde_json::{Map, Value, json};
 use std::sync::Arc;
 
+use crate::api::middleware::auth::bearer_token;
+
+use crate::api::services::oauth_service::{
+    SUPPORTED_GRANT_TYPES, SUPPORTED_PROMPT_VALUES, SUPPORTED_RESPONSE_MODES,
+    TOKEN_ENDPOINT_AUTH_METHODS, parse_scopes,
+};
+use crate::api::services::subject_service;
 use crate::errors::AppError;
-use crate::security::Claims;
-use crate::storage::{SeaOrmBackend, UserRepository};
+use crate::security::claims::{ClaimsRequest, released_claims, supported_user_claims};
+use crate::security::client_assertion::SUPPORTED_ASSERTION_ALGORITHMS;
+use crate::security::dpop::SUPPORTED_DPOP_ALGORITHMS;
+use crate::security::keys::{Jwk, algorithm_name};
+use crate::security::pairwise::SUPPORTED_SUBJECT_TYPES;
+use crate::security::request_object::supported_request_object_algorithms;
+use crate::security::{Claims, JwtManager, pkce};
+use crate::storage::entities::users;
+use crate::storage::{SeaOrmBackend, TokenRepository, UserRepository};
 
 #[derive(Debug, Serialize)]
 pub struct OpenIDConfiguration {
     pub issuer: String,
     pub authorization_endpoint: String,
+    pub pushed_authorization_request_endpoint: String,
+    pub require_pushed_authorization_requests: bool,
     pub token_endpoint: String,
+    pub device_authorization_endpoint: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub registration_endpoint: String,
     pub userinfo_endpoint: String,
+    pub end_session_endpoint: String,
     pub jwks_uri: String,
     pub response_types_supported: Vec<String>,
+    pub response_modes_supported: Vec<String>,
     pub subject_types_supported: Vec<String>,
     pub id_token_signing_alg_values_supported: Vec<String>,
     pub scopes_supported: Vec<String>,
     pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub token_endpoint_auth_signing_alg_values_supported: Vec<String>,
     pub claims_supported: Vec<String>,
+    pub claims_parameter_supported: bool,
+    pub code_challenge_methods_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub prompt_values_supported: Vec<String>,
+    pub request_parameter_supported: bool,
+    pub request_uri_parameter_supported: bool,
+    pub request_object_signing_alg_values_supported: Vec<String>,
+    pub backchannel_logout_supported: bool,
+    pub backchannel_logout_session_supported: bool,
+    pub dpop_signing_alg_values_supported: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct JWKSResponse {
-    pub keys: Vec<JWK>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct JWK {
-    pub kty: String,
-    pub kid: String,
-    pub r#use: String,
-    pub alg: String,
-    pub n: String,
-    pub e: String,
+    pub keys: Vec<Jwk>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct UserInfoResponse {
     pub sub: String,
-    pub name: String,
-    pub email: String,
-    pub email_verified: bool,
+    /// 按授予的 scope 和 claims 参数返回的用户 claims
+    #[serde(flatten)]
+    pub claims: Map<String, Value>,
 }
 
 /// GET /.well-known/openid-configuration
 /// OpenID Connect Discovery 文档
-pub async fn discovery(config: web::Data<crate::config::AppConfig>) -> HttpResponse {
-    let base_url = format!("http://{}:{}", config.server.host, config.server.port);
+pub async fn discovery(
+    config: web::Data<crate::config::AppConfig>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+) -> HttpResponse {
+    let base_url = config.issuer();
 
     let discovery = OpenIDConfiguration {
         issuer: base_url.clone(),
         authorization_endpoint: format!("{}/oauth/authorize", base_url),
+        pushed_authorization_request_endpoint: format!("{}/oauth/par", base_url),
+        // 是否必须使用 PAR 由客户端配置决定
+        require_pushed_authorization_requests: false,
         token_endpoint: format!("{}/oauth/token", base_url),
+        device_authorization_endpoint: format!("{}/oauth/device_authorization", base_url),
+        introspection_endpoint: format!("{}/oauth/introspect", base_url),
+        revocation_endpoint: format!("{}/oauth/revoke", base_url),
+        registration_endpoint: format!("{}/oauth/register", base_url),
         userinfo_endpoint: format!("{}/oauth/userinfo", base_url),
+        end_session_endpoint: format!("{}/oauth/logout", base_url),
         jwks_uri: format!("{}/.well-known/jwks.json", base_url),
         response_types_supported: vec!["code".to_string()],
-        subject_types_supported: vec!["public".to_string()],
-        id_token_signing_alg_values_supported: vec!["HS256".to_string()],
-        scopes_supported: vec![
-            "openid".to_string(),
-            "profile".to_string(),
-            "email".to_string(),
-        ],
-        token_endpoint_auth_methods_supported: vec!["client_secret_post".to_string()],
-        claims_supported: vec![
-            "sub".to_string(),
-            "name".to_string(),
-            "email".to_string(),
-            "email_verified".to_string(),
-        ],
+        response_modes_supported: SUPPORTED_RESPONSE_MODES
+            .iter()
+            .map(|mode| mode.to_string())
+            .collect(),
+        subject_types_supported: SUPPORTED_SUBJECT_TYPES
+            .iter()
+            .map(|subject_type| subject_type.to_string())
+            .collect(),
+        id_token_signing_alg_values_supported: vec![jwt_manager.algorithm().to_string()],
+        scopes_supported: config.auth.supported_scopes.clone(),
+        token_endpoint_auth_methods_supported: TOKEN_ENDPOINT_AUTH_METHODS
+            .iter()
+            .map(|method| method.to_string())
+            .collect(),
+        token_endpoint_auth_signing_alg_values_supported: SUPPORTED_ASSERTION_ALGORITHMS
+            .iter()
+            .map(|alg| algorithm_name(*alg).to_string())
+            .collect(),
+        claims_supported: [
+            "iss",
+            "sub",
+            "aud",
+            "azp",
+            "exp",
+            "iat",
+            "auth_time",
+            "nonce",
+            "acr",
+            "amr",
+            "at_hash",
+            "c_hash",
+        ]
+        .into_iter()
+        .chain(supported_user_claims())
+        .map(|claim| claim.to_string())
+        .collect(),
+        claims_parameter_supported: true,
+        code_challenge_methods_supported: pkce::SUPPORTED_CHALLENGE_METHODS
+            .iter()
+            .map(|m| m.to_string())
+            .collect(),
+        grant_types_supported: SUPPORTED_GRANT_TYPES
+            .iter()
+            .map(|g| g.to_string())
+            .collect(),
+        prompt_values_supported: SUPPORTED_PROMPT_VALUES
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        request_parameter_supported: true,
+        // request_uri 只接受 PAR 端点返回的地址，不获取客户端托管的请求对象
+        request_uri_parameter_supported: false,
+        request_object_signing_alg_values_supported: supported_request_object_algorithms()
+            .into_iter()
+            .map(|alg| algorithm_name(alg).to_string())
+            .collect(),
+        backchannel_logout_supported: true,
+        // ID Token 和 RP 发起登出的 logout_token 中包含 sid
+        backchannel_logout_session_supported: true,
+        dpop_signing_alg_values_supported: SUPPORTED_DPOP_ALGORITHMS
+            .iter()
+            .map(|alg| algorithm_name(*alg).to_string())
+            .collect(),
     };
 
     HttpResponse::Ok().json(discovery)
@@ -77,24 +161,10 @@ pub async fn discovery(config: web::Data<crate::config::AppConfig>) -> HttpRespo
 
 /// GET /.well-known/jwks.json
 /// JSON Web Key Set (公钥端点)
-pub async fn jwks() -> HttpResponse {
-    // 当前使用对称密钥 (HS256)，不应该公开密钥
-    // 对于对称密钥签名的 ID Token，客户端应该使用 client_secret 来验证
-    //
-    // 如果需要支持公开密钥验证，可以升级到非对称密钥算法：
-    // 1. 切换到 RS256/RS512（RSA）或 ES256（ECDSA）
-    // 2. 生成公钥/私钥对
-    // 3. 在这里返回公钥的 JWK 表示
-    //
-    // 参考实现：
-    // - 使用 ring 或 openssl 生成密钥对
-    // - 将公钥转换为 JWK 格式（包含 n, e 参数）
-    // - 添加 kid (Key ID) 支持密钥轮换
+pub async fn jwks(jwt_manager: web::Data<Arc<JwtManager>>) -> HttpResponse {
+    // 对称密钥 (HS256) 不公开，此时返回空数组
     let jwks = JWKSResponse {
-        keys: vec![
-            // 对称密钥场景返回空数组是符合规范的
-            // 客户端应使用 client_secret 验证 ID Token
-        ],
+        keys: jwt_manager.public_jwks(),
     };
 
     HttpResponse::Ok().json(jwks)
@@ -113,11 +183,15 @@ pub async fn userinfo(
         .cloned()
         .ok_or(AppError::Unauthorized)?;
 
-    // 解析 user_id
-    let user_id: i64 = claims
-        .sub
-        .parse()
-        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+    // 客户端 Token 不代表任何用户
+    if claims.is_client() {
+        return Err(AppError::InvalidToken);
+    }
+
+    // 将 sub 映射回用户（pairwise 客户端的 sub 需要查表）
+    let user_id = subject_service::resolve_user_id(&storage, &claims.sub)
+        .await?
+        .ok_or(AppError::InvalidToken)?;
 
     // 查询用户信息
     let user = storage
@@ -125,10 +199,78 @@ pub async fn userinfo(
         .await?
         .ok_or(AppError::NotFound)?;
 
+    // 按 token 记录中的 scope 和 claims 参数确定返回的 claims
+    // （没有记录的 token 使用其自身的 scope）
+    let record = storage.find_access_token(&bearer_token(&req)?).await?;
+    let (scopes, requested) = match &record {
+        Some(record) => (
+            parse_scopes(&record.scopes),
+            record
+                .claims
+                .as_deref()
+                .and_then(ClaimsRequest::parse)
+                .unwrap_or_default(),
+        ),
+        None => (
+            claims.scope.clone().unwrap_or_default(),
+            ClaimsRequest::default(),
+        ),
+    };
+
+    // 只有获得 openid scope 的 Token 可以访问 UserInfo（OpenID Connect Core 5.3）
+    if !scopes.iter().any(|scope| scope == "openid") {
+        return Err(AppError::InsufficientScope(
+            "The access token was not granted the openid scope".into(),
+        ));
+    }
+    let released = released_claims(&scopes, requested.userinfo.as_ref());
+
+    // 返回的 sub 与客户端收到的 ID Token 一致
     Ok(HttpResponse::Ok().json(UserInfoResponse {
-        sub: user.id.to_string(),
-        name: user.username.clone(),
-        email: user.email.clone(),
-        email_verified: true, // 简化版，实际应该有验证字段
+        sub: claims.sub,
+        claims: user_claims(&user, &released),
     }))
 }
+
+/// 按名称取出用户的 claims（值为空的 claim 不返回）
+pub(crate) fn user_claims(user: &users::Model, names: &[&str]) -> Map<String, Value> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let value = match *name {
+                "name" => json!(full_name(user).unwrap_or_else(|| user.username.clone())),
+                "given_name" => json!(user.given_name),
+                "family_name" => json!(user.family_name),
+                "preferred_username" => json!(user.username),
+                "picture" => json!(user.picture),
+                "locale" => json!(user.locale),
+                "zoneinfo" => json!(user.zoneinfo),
+                "updated_at" => json!(user.updated_at.timestamp()),
+                "email" => json!(user.email),
+                "email_verified" => json!(user.email_verified),
+                "phone_number" => json!(user.phone_number),
+                "phone_number_verified" => match user.phone_number {
+                    Some(_) => json!(user.phone_number_verified),
+                    None => Value::Null,
+                },
+                // 地址以 JSON 对象保存（OpenID Connect Core 5.1.1）
+                "address" => user
+                    .address
+                    .as_deref()
+                    .and_then(|address| serde_json::from_str(address).ok())
+                    .unwrap_or(Value::Null),
+                _ => Value::Null,
+            };
+            (!value.is_null()).then(|| (name.to_string(), value))
+        })
+        .collect()
+}
+
+/// 由名和姓组成的全名
+fn full_name(user: &users::Model) -> Option<String> {
+    let parts: Vec<&str> = [user.given_name.as_deref(), user.family_name.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
@@ -0,0 +1,117 @@
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::services::oauth_service::{
+    AuthorizeRequest, ClientCredentials, authenticate_client, resolve_request_object,
+    validate_authorize_request,
+};
+use crate::cache::CompositeCache;
+use crate::config::AppConfig;
+use crate::errors::AppError;
+use crate::security::generate_random_token;
+use crate::storage::SeaOrmBackend;
+
+/// request_uri 前缀（RFC 9126 2.2）
+const REQUEST_URI_PREFIX: &str = "urn:ietf:params:oauth:request_uri:";
+
+/// request_uri 有效期（秒）
+const PAR_EXPIRE: u64 = 60;
+
+/// 推送的授权请求：授权请求参数和客户端认证参数
+#[derive(Debug, Deserialize)]
+pub struct PushedAuthorizationRequest {
+    #[serde(flatten)]
+    pub authorization: AuthorizeRequest,
+    pub client_secret: Option<String>,
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PushedAuthorizationResponse {
+    pub request_uri: String,
+    pub expires_in: u64,
+}
+
+/// POST /oauth/par
+/// 推送授权请求（RFC 9126），返回在授权端点使用的 request_uri
+pub async fn push_authorization_request(
+    http_req: HttpRequest,
+    req: web::Json<PushedAuthorizationRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    let req = req.into_inner();
+    let authorization = req.authorization;
+
+    // 1. 认证客户端（授权请求中的 client_id 必须是认证的客户端）
+    let credentials = ClientCredentials {
+        client_id: Some(authorization.client_id.clone()),
+        client_secret: req.client_secret,
+        client_assertion_type: req.client_assertion_type,
+        client_assertion: req.client_assertion,
+    };
+    let client = authenticate_client(&storage, &cache, &http_req, &credentials).await?;
+    if client.client_id != authorization.client_id {
+        return Err(AppError::InvalidClient);
+    }
+
+    // 2. 推送的请求中不能再包含 request_uri（RFC 9126 2.1），请求对象在此解析
+    if authorization.request_uri.is_some() {
+        return Err(AppError::BadRequest(
+            "request_uri is not allowed in a pushed authorization request".into(),
+        ));
+    }
+    let authorization = resolve_request_object(&storage, &cache, &config, authorization).await?;
+
+    // 3. 与授权端点相同的参数验证
+    validate_authorize_request(&storage, &config, &authorization, true).await?;
+
+    // 4. 保存授权请求
+    let request_uri = format!("{}{}", REQUEST_URI_PREFIX, generate_random_token(32));
+    let value = serde_json::to_string(&authorization)
+        .map_err(|e| AppError::Internal(format!("Failed to store authorization request: {}", e)))?;
+    cache
+        .set(&pushed_request_key(&request_uri), value, Some(PAR_EXPIRE))
+        .await;
+
+    tracing::info!(
+        "Pushed authorization request stored for client: {}",
+        client.client_id
+    );
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(PushedAuthorizationResponse {
+            request_uri,
+            expires_in: PAR_EXPIRE,
+        }))
+}
+
+/// 读取推送的授权请求（client_id 必须与推送请求的客户端一致）
+pub(crate) async fn load_pushed_request(
+    cache: &CompositeCache,
+    request_uri: &str,
+    client_id: &str,
+) -> Result<AuthorizeRequest, AppError> {
+    cache
+        .get(&pushed_request_key(request_uri))
+        .await
+        .and_then(|value| serde_json::from_str::<AuthorizeRequest>(&value).ok())
+        .filter(|request| request.client_id == client_id)
+        .ok_or(AppError::BadRequest(
+            "Invalid or expired request_uri".into(),
+        ))
+}
+
+/// 使 request_uri 失效
+pub(crate) async fn consume_pushed_request(cache: &CompositeCache, request_uri: &str) {
+    cache.delete(&pushed_request_key(request_uri)).await;
+}
+
+fn pushed_request_key(request_uri: &str) -> String {
+    format!("par:{}", request_uri)
+}
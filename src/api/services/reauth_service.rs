@@ -0,0 +1,146 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::services::consent_service::{self, ConsentResponse};
+use crate::api::services::oauth_service::{
+    PendingAuthorization, authenticated_session, issue_authorization_code,
+};
+use crate::cache::CompositeCache;
+use crate::errors::AppError;
+use crate::security::{JwtManager, generate_random_token};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::{ClientRepository, SeaOrmBackend, UserRepository};
+
+/// login_ticket 有效期（秒）
+const LOGIN_TICKET_EXPIRE: u64 = 600;
+
+/// 等待用户重新认证的授权请求
+#[derive(Debug, Serialize, Deserialize)]
+struct LoginTicket {
+    pending: PendingAuthorization,
+    /// 是否要求用户重新确认授权（prompt=consent）
+    force_consent: bool,
+    /// 要求重新认证的时间，用户必须在此之后登录
+    requested_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginRequiredResponse {
+    pub login_required: bool,
+    pub login_ticket: String,
+    pub client_id: String,
+    pub client_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ui_locales: Option<String>,
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReauthenticateRequest {
+    pub login_ticket: String,
+}
+
+/// POST /oauth/authorize/login
+/// 用户重新登录后继续授权请求
+pub async fn reauthenticate(
+    req: HttpRequest,
+    body: web::Json<ReauthenticateRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证用户身份
+    let session = authenticated_session(&req, &storage, &jwt_manager, &cache).await?;
+    let user = storage
+        .find_by_id(session.user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // 2. 取出暂存的授权请求
+    let key = format!("login:{}", body.login_ticket);
+    let ticket = cache
+        .get(&key)
+        .await
+        .and_then(|value| serde_json::from_str::<LoginTicket>(&value).ok())
+        .ok_or(AppError::BadRequest(
+            "Invalid or expired login_ticket".into(),
+        ))?;
+
+    // 3. 当前会话必须在要求重新认证之后登录（login_hint 只是提示，允许切换账号）
+    let auth_time = session.auth_time;
+    if auth_time.is_none_or(|at| at < ticket.requested_at) {
+        return Err(AppError::Unauthorized);
+    }
+
+    // 票据只能使用一次（并发请求中只有一个能取出）
+    if cache.take(&key).await.is_none() {
+        return Err(AppError::BadRequest(
+            "Invalid or expired login_ticket".into(),
+        ));
+    }
+
+    let mut pending = ticket.pending;
+    pending.user_id = user.id;
+    pending.auth_time = auth_time;
+
+    let client = storage
+        .find_by_client_id(&pending.client_id)
+        .await?
+        .ok_or(AppError::InvalidClient)?;
+
+    tracing::info!(
+        "User {} re-authenticated for client: {}",
+        user.id,
+        client.name
+    );
+
+    // 4. 用户尚未同意请求的 scope（或 prompt=consent）时，返回同意页面所需信息
+    if ticket.force_consent || !consent_service::has_consent(&storage, &pending).await? {
+        return consent_service::request_consent(&cache, &client, pending).await;
+    }
+
+    // 5. 签发授权码
+    let response = issue_authorization_code(&storage, &cache, &client, &pending).await?;
+
+    Ok(HttpResponse::Ok().json(ConsentResponse::from(response)))
+}
+
+/// 暂存授权请求，返回登录页面所需的客户端名称和 login_hint
+pub(crate) async fn request_login(
+    cache: &CompositeCache,
+    client: &o_auth_clients::Model,
+    pending: PendingAuthorization,
+    force_consent: bool,
+    login_hint: Option<String>,
+) -> Result<HttpResponse, AppError> {
+    let login_ticket = generate_random_token(32);
+    let ui_locales = pending.ui_locales.clone();
+    let value = serde_json::to_string(&LoginTicket {
+        pending,
+        force_consent,
+        requested_at: Utc::now().timestamp(),
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to store login request: {}", e)))?;
+
+    cache
+        .set(
+            &format!("login:{}", login_ticket),
+            value,
+            Some(LOGIN_TICKET_EXPIRE),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok().json(LoginRequiredResponse {
+        login_required: true,
+        login_ticket,
+        client_id: client.client_id.clone(),
+        client_name: client.name.clone(),
+        login_hint,
+        ui_locales,
+        expires_in: LOGIN_TICKET_EXPIRE,
+    }))
+}
@@ -0,0 +1,487 @@
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::services::client_service::{
+    uses_client_secret, validate_allowed_scopes, validate_auth_method,
+    validate_backchannel_logout_uri, validate_grant_types, validate_key_set, validate_name,
+    validate_post_logout_redirect_uris, validate_redirect_uris, validate_subject_type,
+};
+use crate::api::services::oauth_service::parse_scopes;
+use crate::api::services::token_service::blacklist_token;
+use crate::cache::CompositeCache;
+use crate::config::AppConfig;
+use crate::errors::AppError;
+use crate::security::pairwise::SUBJECT_TYPE_PUBLIC;
+use crate::security::{
+    ClientSecretManager, JwtManager, generate_client_id, generate_client_secret,
+    generate_random_token,
+};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::{ClientRepository, ClientUpdateFields, NewClient, SeaOrmBackend};
+
+// ============= 请求/响应结构体 =============
+
+/// 客户端元数据（RFC 7591 2）
+#[derive(Debug, Deserialize)]
+pub struct ClientMetadata {
+    /// 更新请求中必须与路径中的 client_id 一致（RFC 7592 2.2）
+    pub client_id: Option<String>,
+    pub redirect_uris: Option<Vec<String>>,
+    pub post_logout_redirect_uris: Option<Vec<String>>,
+    pub backchannel_logout_uri: Option<String>,
+    pub token_endpoint_auth_method: Option<String>,
+    pub grant_types: Option<Vec<String>>,
+    pub response_types: Option<Vec<String>>,
+    pub require_pushed_authorization_requests: Option<bool>,
+    pub require_signed_request_object: Option<bool>,
+    pub client_name: Option<String>,
+    pub scope: Option<String>,
+    pub subject_type: Option<String>,
+    pub sector_identifier_uri: Option<String>,
+    pub jwks: Option<serde_json::Value>,
+    pub jwks_uri: Option<String>,
+}
+
+/// 客户端信息响应（RFC 7591 3.2.1 / RFC 7592 3）
+#[derive(Debug, Serialize)]
+pub struct ClientRegistrationResponse {
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub client_id_issued_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret_expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_access_token: Option<String>,
+    pub registration_client_uri: String,
+    pub client_name: String,
+    pub redirect_uris: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_logout_redirect_uris: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backchannel_logout_uri: Option<String>,
+    pub grant_types: Vec<String>,
+    pub response_types: Vec<String>,
+    pub require_pushed_authorization_requests: bool,
+    pub require_signed_request_object: bool,
+    pub token_endpoint_auth_method: String,
+    pub scope: String,
+    pub subject_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sector_identifier_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks_uri: Option<String>,
+}
+
+/// 验证后的客户端元数据（JSON 字段已序列化为存储格式）
+struct ValidatedMetadata {
+    name: Option<String>,
+    redirect_uris: String,
+    post_logout_redirect_uris: Option<String>,
+    backchannel_logout_uri: Option<String>,
+    grant_types: String,
+    require_pushed_authorization_requests: bool,
+    require_signed_request_object: bool,
+    allowed_scopes: String,
+    token_endpoint_auth_method: String,
+    subject_type: String,
+    sector_identifier_uri: Option<String>,
+    jwks: Option<String>,
+    jwks_uri: Option<String>,
+}
+
+// ============= 处理函数 =============
+
+/// POST /oauth/register
+/// 动态注册客户端（需要初始访问令牌，除非开放注册）
+pub async fn register(
+    req: HttpRequest,
+    body: web::Json<ClientMetadata>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证初始访问令牌（RFC 7591 3）
+    match bearer_token(&req) {
+        Some(token) => {
+            let valid = storage
+                .get_initial_access_token_hash()
+                .await?
+                .is_some_and(|hash| {
+                    ClientSecretManager::is_hashed(&hash)
+                        && ClientSecretManager::verify_secret(token, &hash)
+                });
+            if !valid {
+                return Err(AppError::InvalidToken);
+            }
+        }
+        None if storage
+            .get_client_registration_config()
+            .await?
+            .allow_open_registration => {}
+        None => return Err(AppError::InvalidToken),
+    }
+
+    // 2. 验证客户端元数据
+    let metadata = validate_metadata(body.into_inner(), &config.auth.supported_scopes).await?;
+
+    // 3. 生成凭证（公共客户端和 private_key_jwt 客户端不使用 secret）
+    let client_id = generate_client_id();
+    let client_secret =
+        uses_client_secret(&metadata.token_endpoint_auth_method).then(generate_client_secret);
+    let secret_hash = match &client_secret {
+        Some(secret) => ClientSecretManager::hash_secret(secret)?,
+        None => String::new(),
+    };
+    let registration_access_token = generate_random_token(32);
+
+    // 4. 保存客户端（公共客户端必须使用 PKCE）
+    let client = storage
+        .create_client(NewClient {
+            name: metadata.name.unwrap_or_else(|| client_id.clone()),
+            client_id,
+            client_secret: secret_hash,
+            redirect_uris: metadata.redirect_uris,
+            allowed_scopes: metadata.allowed_scopes,
+            require_pkce: metadata.token_endpoint_auth_method == "none",
+            token_endpoint_auth_method: metadata.token_endpoint_auth_method,
+            jwks: metadata.jwks,
+            jwks_uri: metadata.jwks_uri,
+            grant_types: Some(metadata.grant_types),
+            post_logout_redirect_uris: metadata.post_logout_redirect_uris,
+            backchannel_logout_uri: metadata.backchannel_logout_uri,
+            response_modes: None,
+            require_pushed_authorization_requests: metadata.require_pushed_authorization_requests,
+            require_signed_request_object: metadata.require_signed_request_object,
+            token_exchange_policy: None,
+            subject_type: metadata.subject_type,
+            sector_identifier_uri: metadata.sector_identifier_uri,
+            registration_access_token: Some(ClientSecretManager::hash_secret(
+                &registration_access_token,
+            )?),
+        })
+        .await?;
+
+    tracing::info!("Client {} registered dynamically", client.client_id);
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(registration_response(
+            &client,
+            &config,
+            client_secret,
+            Some(registration_access_token),
+        )))
+}
+
+/// GET /oauth/register/{client_id}
+/// 读取客户端配置（RFC 7592 2.1）
+pub async fn get_client(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    let client = authenticate_registration(&storage, &req, &client_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(registration_response(&client, &config, None, None)))
+}
+
+/// PUT /oauth/register/{client_id}
+/// 更新客户端配置（RFC 7592 2.2，请求中的元数据整体替换原有配置）
+pub async fn update_client(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    body: web::Json<ClientMetadata>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    cache: web::Data<Arc<CompositeCache>>,
+    config: web::Data<AppConfig>,
+) -> Result<HttpResponse, AppError> {
+    let existing = authenticate_registration(&storage, &req, &client_id).await?;
+    let body = body.into_inner();
+
+    // 1. 请求体中的 client_id 必须与路径一致
+    if body
+        .client_id
+        .as_deref()
+        .is_some_and(|id| id != existing.client_id)
+    {
+        return Err(AppError::InvalidClientMetadata(
+            "client_id does not match".into(),
+        ));
+    }
+
+    // 2. 验证客户端元数据
+    let metadata = validate_metadata(body, &config.auth.supported_scopes).await?;
+    let method = metadata.token_endpoint_auth_method;
+
+    // 3. 改为使用 secret 的认证方式且尚无 secret 时生成新的 secret
+    let client_secret = (uses_client_secret(&method) && existing.client_secret.is_empty())
+        .then(generate_client_secret);
+    if let Some(secret) = &client_secret {
+        storage
+            .update_client_secret(
+                &existing.client_id,
+                &ClientSecretManager::hash_secret(secret)?,
+            )
+            .await?;
+    }
+
+    // 4. 保存并清除缓存的客户端公钥集
+    let fields = ClientUpdateFields {
+        name: metadata.name.or_else(|| Some(existing.client_id.clone())),
+        redirect_uris: Some(metadata.redirect_uris),
+        allowed_scopes: Some(metadata.allowed_scopes),
+        require_pkce: Some(existing.require_pkce || method == "none"),
+        token_endpoint_auth_method: Some(method),
+        jwks: Some(metadata.jwks),
+        jwks_uri: Some(metadata.jwks_uri),
+        grant_types: Some(Some(metadata.grant_types)),
+        post_logout_redirect_uris: Some(metadata.post_logout_redirect_uris),
+        backchannel_logout_uri: Some(metadata.backchannel_logout_uri),
+        require_pushed_authorization_requests: Some(metadata.require_pushed_authorization_requests),
+        require_signed_request_object: Some(metadata.require_signed_request_object),
+        // response_modes 和 token_exchange_policy 不属于注册元数据，保持管理员的配置
+        response_modes: None,
+        token_exchange_policy: None,
+        subject_type: Some(metadata.subject_type),
+        sector_identifier_uri: Some(metadata.sector_identifier_uri),
+    };
+    let client = storage.update_client(existing, fields).await?;
+    cache
+        .delete(&format!("client_jwks:{}", client.client_id))
+        .await;
+
+    tracing::info!(
+        "Client {} updated via registration endpoint",
+        client.client_id
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(registration_response(&client, &config, client_secret, None)))
+}
+
+/// DELETE /oauth/register/{client_id}
+/// 注销客户端，并撤销其签发的所有 Token（RFC 7592 2.3）
+pub async fn delete_client(
+    req: HttpRequest,
+    client_id: web::Path<String>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    let client = authenticate_registration(&storage, &req, &client_id).await?;
+
+    // 1. 删除客户端及相关数据
+    let tokens = storage.delete_client(&client.client_id).await?;
+
+    // 2. 已签发的 JWT 加入黑名单直到过期
+    for token in &tokens {
+        blacklist_token(&jwt_manager, &cache, token).await;
+    }
+    cache
+        .delete(&format!("client_jwks:{}", client.client_id))
+        .await;
+
+    tracing::info!(
+        "Client {} deleted via registration endpoint ({} tokens revoked)",
+        client.client_id,
+        tokens.len()
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// ============= 辅助函数 =============
+
+/// 验证客户端元数据，校验失败返回 invalid_client_metadata
+async fn validate_metadata(
+    metadata: ClientMetadata,
+    supported_scopes: &[String],
+) -> Result<ValidatedMetadata, AppError> {
+    let invalid = |e: AppError| match e {
+        AppError::BadRequest(message) => AppError::InvalidClientMetadata(message),
+        e => e,
+    };
+
+    // 1. 授权类型（默认 authorization_code），只支持 code 响应类型
+    let grant_types = metadata
+        .grant_types
+        .unwrap_or_else(|| vec!["authorization_code".to_string()]);
+    let uses_authorization_code = grant_types.iter().any(|g| g == "authorization_code");
+
+    let response_types = metadata.response_types.unwrap_or_default();
+    if response_types.iter().any(|r| r != "code") {
+        return Err(AppError::InvalidClientMetadata(
+            "Only the code response_type is supported".into(),
+        ));
+    }
+    if !response_types.is_empty() && !uses_authorization_code {
+        return Err(AppError::InvalidClientMetadata(
+            "response_type code requires the authorization_code grant_type".into(),
+        ));
+    }
+
+    // 2. 使用授权码模式时必须登记 redirect_uris
+    let redirect_uri_list = metadata.redirect_uris.unwrap_or_default();
+    let redirect_uris = if redirect_uri_list.is_empty() && !uses_authorization_code {
+        "[]".to_string()
+    } else {
+        validate_redirect_uris(&redirect_uri_list).map_err(invalid)?
+    };
+    let post_logout_redirect_uris = metadata
+        .post_logout_redirect_uris
+        .as_deref()
+        .map(validate_post_logout_redirect_uris)
+        .transpose()
+        .map_err(invalid)?
+        .flatten();
+    let backchannel_logout_uri = metadata
+        .backchannel_logout_uri
+        .map(validate_backchannel_logout_uri)
+        .transpose()
+        .map_err(invalid)?;
+
+    // 3. 认证方式（默认 client_secret_basic），公共客户端不能使用客户端凭证模式
+    let method = metadata
+        .token_endpoint_auth_method
+        .unwrap_or_else(|| "client_secret_basic".to_string());
+    if method == "none" && grant_types.iter().any(|g| g == "client_credentials") {
+        return Err(AppError::InvalidClientMetadata(
+            "Public clients cannot use client_credentials".into(),
+        ));
+    }
+    let (jwks, jwks_uri) = validate_key_set(metadata.jwks, metadata.jwks_uri).map_err(invalid)?;
+    validate_auth_method(&method, jwks.as_deref(), jwks_uri.as_deref()).map_err(invalid)?;
+
+    // 4. subject_type（默认 public），pairwise 客户端需要能确定扇区
+    let subject_type = metadata
+        .subject_type
+        .unwrap_or_else(|| SUBJECT_TYPE_PUBLIC.to_string());
+    validate_subject_type(
+        &subject_type,
+        metadata.sector_identifier_uri.as_deref(),
+        &redirect_uri_list,
+    )
+    .await
+    .map_err(invalid)?;
+
+    // 5. scope 以空格分隔，未指定时允许服务器支持的全部 scope
+    let allowed_scopes = match metadata.scope.as_deref() {
+        Some(scope) => {
+            validate_allowed_scopes(&parse_scopes(scope), supported_scopes).map_err(invalid)?
+        }
+        None => serde_json::to_string(supported_scopes).unwrap_or_default(),
+    };
+
+    Ok(ValidatedMetadata {
+        name: metadata
+            .client_name
+            .as_deref()
+            .map(validate_name)
+            .transpose()
+            .map_err(invalid)?,
+        redirect_uris,
+        post_logout_redirect_uris,
+        backchannel_logout_uri,
+        grant_types: validate_grant_types(&grant_types).map_err(invalid)?,
+        require_pushed_authorization_requests: metadata
+            .require_pushed_authorization_requests
+            .unwrap_or(false),
+        require_signed_request_object: metadata.require_signed_request_object.unwrap_or(false),
+        allowed_scopes,
+        token_endpoint_auth_method: method,
+        subject_type,
+        sector_identifier_uri: metadata.sector_identifier_uri,
+        jwks,
+        jwks_uri,
+    })
+}
+
+/// 使用 registration_access_token 认证客户端配置端点的请求
+///
+/// 客户端不存在或令牌不匹配时均返回 invalid_token，避免泄露客户端是否存在
+async fn authenticate_registration(
+    storage: &SeaOrmBackend,
+    req: &HttpRequest,
+    client_id: &str,
+) -> Result<o_auth_clients::Model, AppError> {
+    let token = bearer_token(req).ok_or(AppError::InvalidToken)?;
+
+    storage
+        .find_by_client_id(client_id)
+        .await?
+        .filter(|client| {
+            client
+                .registration_access_token
+                .as_deref()
+                .is_some_and(|hash| {
+                    ClientSecretManager::is_hashed(hash)
+                        && ClientSecretManager::verify_secret(token, hash)
+                })
+        })
+        .ok_or(AppError::InvalidToken)
+}
+
+/// 从 Authorization 头中提取 Bearer 令牌
+fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// 构造客户端信息响应
+fn registration_response(
+    client: &o_auth_clients::Model,
+    config: &AppConfig,
+    client_secret: Option<String>,
+    registration_access_token: Option<String>,
+) -> ClientRegistrationResponse {
+    let grant_types: Vec<String> = client
+        .grant_types
+        .as_deref()
+        .and_then(|grant_types| serde_json::from_str(grant_types).ok())
+        .unwrap_or_else(|| vec!["authorization_code".to_string()]);
+    let response_types = if grant_types.iter().any(|g| g == "authorization_code") {
+        vec!["code".to_string()]
+    } else {
+        vec![]
+    };
+
+    ClientRegistrationResponse {
+        client_id: client.client_id.clone(),
+        client_secret_expires_at: client_secret.as_ref().map(|_| 0),
+        client_secret,
+        client_id_issued_at: client.created_at.timestamp(),
+        registration_access_token,
+        registration_client_uri: format!("{}/oauth/register/{}", config.issuer(), client.client_id),
+        client_name: client.name.clone(),
+        redirect_uris: serde_json::from_str(&client.redirect_uris).unwrap_or_default(),
+        post_logout_redirect_uris: client
+            .post_logout_redirect_uris
+            .as_deref()
+            .and_then(|uris| serde_json::from_str(uris).ok())
+            .unwrap_or_default(),
+        backchannel_logout_uri: client.backchannel_logout_uri.clone(),
+        grant_types,
+        response_types,
+        require_pushed_authorization_requests: client.require_pushed_authorization_requests,
+        require_signed_request_object: client.require_signed_request_object,
+        token_endpoint_auth_method: client.token_endpoint_auth_method.clone(),
+        scope: parse_scopes(&client.allowed_scopes).join(" "),
+        subject_type: client.subject_type.clone(),
+        sector_identifier_uri: client.sector_identifier_uri.clone(),
+        jwks: client
+            .jwks
+            .as_deref()
+            .and_then(|jwks| serde_json::from_str(jwks).ok()),
+        jwks_uri: client.jwks_uri.clone(),
+    }
+}
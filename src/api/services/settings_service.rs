@@ -2,9 +2,11 @@ use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::config::{AuthPolicyConfig, CachePolicyConfig, RegistrationConfig};
+use crate::config::{
+    AuthPolicyConfig, CachePolicyConfig, ClientRegistrationConfig, RegistrationConfig,
+};
 use crate::errors::AppError;
-use crate::security::Claims;
+use crate::security::{Claims, ClientSecretManager, generate_random_token};
 use crate::storage::{SeaOrmBackend, entities::config_audit_logs};
 
 #[derive(Debug, Serialize)]
@@ -12,6 +14,12 @@ pub struct SettingsUpdateResponse {
     pub message: String,
 }
 
+/// 新生成的初始访问令牌（只返回一次）
+#[derive(Debug, Serialize)]
+pub struct InitialAccessTokenResponse {
+    pub initial_access_token: String,
+}
+
 /// GET /api/admin/settings/registration
 /// 获取注册配置
 pub async fn get_registration_config(
@@ -156,3 +164,77 @@ pub async fn update_cache_policy_config(
         message: "Cache policy configuration updated successfully".to_string(),
     }))
 }
+
+/// GET /api/admin/settings/client-registration
+/// 获取动态客户端注册配置
+pub async fn get_client_registration_config(
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    let config = storage.get_client_registration_config().await?;
+    Ok(HttpResponse::Ok().json(config))
+}
+
+/// PUT /api/admin/settings/client-registration
+/// 更新动态客户端注册配置
+pub async fn update_client_registration_config(
+    req: HttpRequest,
+    config: web::Json<ClientRegistrationConfig>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    // 从请求扩展中提取 Claims（由 AdminOnly 中间件注入）
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppError::Unauthorized)?;
+
+    // 解析 user_id
+    let user_id: i64 = claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+
+    // 更新配置
+    storage
+        .update_client_registration_config(&config.into_inner(), user_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(SettingsUpdateResponse {
+        message: "Client registration configuration updated successfully".to_string(),
+    }))
+}
+
+/// POST /api/admin/settings/client-registration/token
+/// 生成新的初始访问令牌（旧令牌立即失效）
+pub async fn rotate_initial_access_token(
+    req: HttpRequest,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    // 从请求扩展中提取 Claims（由 AdminOnly 中间件注入）
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppError::Unauthorized)?;
+
+    // 解析 user_id
+    let user_id: i64 = claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+
+    // 生成令牌，数据库中只保存哈希
+    let token = generate_random_token(32);
+    storage
+        .set_initial_access_token_hash(&ClientSecretManager::hash_secret(&token)?, user_id)
+        .await?;
+
+    tracing::info!(
+        "Client registration initial access token rotated by admin {}",
+        user_id
+    );
+
+    Ok(HttpResponse::Ok().json(InitialAccessTokenResponse {
+        initial_access_token: token,
+    }))
+}
@@ -0,0 +1,81 @@
+use actix_web::{HttpMessage, HttpRequest};
+
+use crate::api::middleware::auth::bearer_token;
+use crate::config::get_config;
+use crate::errors::AppError;
+use crate::security::Claims;
+use crate::security::pairwise::{SUBJECT_TYPE_PAIRWISE, pairwise_subject, sector_identifier};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::repository::FIRST_PARTY_CLIENT_ID;
+use crate::storage::{SeaOrmBackend, TokenRepository};
+
+/// 客户端看到的用户标识（sub）
+///
+/// public 客户端（及第一方登录）使用用户 ID；pairwise 客户端使用按扇区计算的标识，
+/// 首次计算后保存到数据库，之后即使 salt 改变也保持不变
+pub(crate) async fn subject_identifier(
+    storage: &SeaOrmBackend,
+    client: Option<&o_auth_clients::Model>,
+    user_id: i64,
+) -> Result<String, AppError> {
+    let Some(client) = client.filter(|client| client.subject_type == SUBJECT_TYPE_PAIRWISE) else {
+        return Ok(user_id.to_string());
+    };
+
+    // 无法确定扇区时（登记后修改了 redirect_uris）以 client_id 作为扇区
+    let redirect_uris: Vec<String> =
+        serde_json::from_str(&client.redirect_uris).unwrap_or_default();
+    let sector = sector_identifier(client.sector_identifier_uri.as_deref(), &redirect_uris)
+        .unwrap_or_else(|| client.client_id.clone());
+
+    let subject = pairwise_subject(&sector, user_id, &get_config().auth.pairwise_subject_salt);
+    let record = storage
+        .save_pairwise_subject(&sector, user_id, &subject)
+        .await?;
+    Ok(record.subject)
+}
+
+/// 将 token 中的 sub 映射回用户 ID（public 标识直接解析，pairwise 标识查表）
+pub(crate) async fn resolve_user_id(
+    storage: &SeaOrmBackend,
+    subject: &str,
+) -> Result<Option<i64>, AppError> {
+    if let Ok(user_id) = subject.parse::<i64>() {
+        return Ok(Some(user_id));
+    }
+
+    let record = storage.find_pairwise_subject_by_sub(subject).await?;
+    Ok(record.map(|record| record.user_id))
+}
+
+/// 当前请求的用户 ID（用户自助接口 /api/user 使用）
+///
+/// 只接受第一方登录签发的 Token：第三方客户端获得的用户 Token 只能访问被授权的资源，
+/// 不能修改用户资料或管理授权，返回 403；客户端 Token 或已撤销的 Token 返回 401
+pub(crate) async fn current_user_id(
+    req: &HttpRequest,
+    storage: &SeaOrmBackend,
+) -> Result<i64, AppError> {
+    // 1. JwtAuth 中间件已验证 Token 并注入 Claims
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppError::Unauthorized)?;
+    if claims.is_client() {
+        return Err(AppError::Unauthorized);
+    }
+
+    // 2. Token 必须由第一方登录签发
+    let record = storage
+        .find_access_token(&bearer_token(req)?)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    if record.client_id != FIRST_PARTY_CLIENT_ID {
+        return Err(AppError::Forbidden(
+            "Token was not issued by first-party login".into(),
+        ));
+    }
+
+    record.user_id.ok_or(AppError::Unauthorized)
+}
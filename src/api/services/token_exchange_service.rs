@@ -0,0 +1,291 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::services::oauth_service::{
+    TokenRequest, TokenResponse, is_public_client, parse_scopes,
+};
+use crate::api::services::subject_service;
+use crate::cache::CompositeCache;
+use crate::errors::AppError;
+use crate::security::{Actor, Claims, JwtManager, dpop};
+use crate::storage::entities::o_auth_clients;
+use crate::storage::repository::NewAccessToken;
+use crate::storage::{SeaOrmBackend, TokenRepository, UserRepository};
+
+/// Token Exchange grant_type（RFC 8693）
+pub const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+
+/// 本服务签发的 access_token 的 token 类型标识（RFC 8693 3）
+pub const ACCESS_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:access_token";
+
+/// 可以作为 subject_token / actor_token 提交的 token 类型（access_token 本身就是 JWT）
+const SUPPORTED_TOKEN_TYPES: [&str; 2] =
+    [ACCESS_TOKEN_TYPE, "urn:ietf:params:oauth:token-type:jwt"];
+
+/// 客户端的 Token Exchange 策略（未配置时客户端不能换取 Token）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenExchangePolicy {
+    /// 允许请求的 audience，为空时只能换取不指定 audience 的 Token
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// 允许不携带 actor_token，直接以用户身份换取 Token（模拟）
+    #[serde(default)]
+    pub allow_impersonation: bool,
+    /// 允许携带 actor_token 换取带 act claim 的 Token（委托）
+    #[serde(default)]
+    pub allow_delegation: bool,
+}
+
+impl TokenExchangePolicy {
+    /// 读取客户端配置的策略
+    pub fn of(client: &o_auth_clients::Model) -> Option<Self> {
+        client
+            .token_exchange_policy
+            .as_deref()
+            .and_then(|policy| serde_json::from_str(policy).ok())
+    }
+}
+
+/// 使用 subject_token（以及可选的 actor_token）换取新的 access_token
+///
+/// 新 Token 的主体与 subject_token 相同，scope 只能缩小，有效期不超过提交的 Token；
+/// 携带 actor_token 时在 act claim 中记录行事的一方（RFC 8693 2.1、4.1）
+pub(crate) async fn token_exchange_grant(
+    req: &TokenRequest,
+    client: &o_auth_clients::Model,
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    dpop_jkt: Option<&str>,
+) -> Result<TokenResponse, AppError> {
+    // 1. 只有配置了策略的机密客户端可以换取 Token
+    let policy = TokenExchangePolicy::of(client).ok_or(AppError::UnauthorizedClient(
+        "Client is not allowed to use token exchange".into(),
+    ))?;
+    if is_public_client(client) {
+        return Err(AppError::UnauthorizedClient(
+            "Public clients cannot use token exchange".into(),
+        ));
+    }
+
+    if req
+        .requested_token_type
+        .as_deref()
+        .is_some_and(|token_type| token_type != ACCESS_TOKEN_TYPE)
+    {
+        return Err(AppError::BadRequest(
+            "Unsupported requested_token_type".into(),
+        ));
+    }
+
+    // 2. 验证 subject_token 和 actor_token
+    let subject_token = req
+        .subject_token
+        .as_deref()
+        .ok_or(AppError::BadRequest("Missing subject_token".into()))?;
+    let subject = verify_exchange_token(
+        storage,
+        jwt_manager,
+        cache,
+        subject_token,
+        req.subject_token_type.as_deref(),
+        "subject_token",
+        dpop_jkt,
+    )
+    .await?;
+
+    let actor = match req.actor_token.as_deref() {
+        Some(actor_token) => Some(
+            verify_exchange_token(
+                storage,
+                jwt_manager,
+                cache,
+                actor_token,
+                req.actor_token_type.as_deref(),
+                "actor_token",
+                dpop_jkt,
+            )
+            .await?,
+        ),
+        None if req.actor_token_type.is_some() => {
+            return Err(AppError::BadRequest(
+                "actor_token_type requires actor_token".into(),
+            ));
+        }
+        None => None,
+    };
+
+    // 3. 按策略检查模拟 / 委托和 audience
+    match &actor {
+        Some(_) if !policy.allow_delegation => {
+            return Err(AppError::UnauthorizedClient(
+                "Delegation is not allowed for this client".into(),
+            ));
+        }
+        None if !policy.allow_impersonation => {
+            return Err(AppError::UnauthorizedClient(
+                "Impersonation is not allowed for this client".into(),
+            ));
+        }
+        _ => {}
+    }
+
+    if let Some(audience) = &req.audience
+        && !policy.audiences.contains(audience)
+    {
+        return Err(AppError::InvalidTarget(format!(
+            "Audience {} is not allowed for this client",
+            audience
+        )));
+    }
+
+    // 4. 请求的 scope 只能是 subject_token scope 的子集
+    let scopes = match req.scope.as_deref() {
+        Some(requested) => {
+            let granted = subject.scope.clone().unwrap_or_default();
+            let requested = parse_scopes(requested);
+            if !requested.iter().all(|s| granted.contains(s)) {
+                return Err(AppError::InvalidScope);
+            }
+            Some(requested)
+        }
+        None => subject.scope.clone(),
+    };
+
+    // 5. 当前行事的一方在最外层，subject_token 中已有的委托链嵌套在内
+    let act = match &actor {
+        Some(actor) => Some(Actor {
+            sub: actor.sub.clone(),
+            act: subject.act.clone().map(Box::new),
+        }),
+        None => subject.act.clone(),
+    };
+
+    // 6. 签发 Token（有效期不超过 subject_token 和 actor_token）
+    let auth_policy = storage.get_auth_policy_config().await?;
+    let now = Utc::now().timestamp();
+    let expires_in = [Some(subject.exp), actor.as_ref().map(|actor| actor.exp)]
+        .into_iter()
+        .flatten()
+        .map(|exp| exp - now)
+        .fold(auth_policy.access_token_expire, i64::min);
+    if expires_in <= 0 {
+        return Err(AppError::InvalidGrant("subject_token has expired".into()));
+    }
+
+    let access_token = jwt_manager.generate_exchanged_token(
+        &subject,
+        expires_in,
+        scopes.clone(),
+        req.audience.clone(),
+        act,
+        dpop_jkt,
+    )?;
+
+    let user_id = if subject.is_client() {
+        None
+    } else {
+        Some(
+            subject_service::resolve_user_id(storage, &subject.sub)
+                .await?
+                .ok_or(AppError::InvalidGrant("Invalid subject_token".into()))?,
+        )
+    };
+    let scope = scopes.unwrap_or_default().join(" ");
+    storage
+        .save_access_token(
+            &access_token,
+            NewAccessToken {
+                client_id: client.client_id.clone(),
+                user_id,
+                scopes: scope.clone(),
+                expires_at: Utc::now() + Duration::seconds(expires_in),
+                claims: None,
+                auth_time: None,
+            },
+        )
+        .await?;
+
+    // 与其他授权方式一致，缓存用户 ID（客户端主体缓存 client_id），而不是客户端看到的 sub
+    cache
+        .set(
+            &format!("token:{}", access_token),
+            user_id.map_or_else(|| subject.sub.clone(), |user_id| user_id.to_string()),
+            Some(expires_in as u64),
+        )
+        .await;
+
+    tracing::info!(
+        "Token exchanged by client: {} subject: {} actor: {:?}",
+        client.client_id,
+        subject.sub,
+        actor.as_ref().map(|actor| &actor.sub)
+    );
+
+    Ok(TokenResponse {
+        access_token,
+        refresh_token: None,
+        token_type: dpop::token_type(dpop_jkt).to_string(),
+        expires_in,
+        scope: (!scope.is_empty()).then_some(scope),
+        id_token: None,
+        issued_token_type: Some(ACCESS_TOKEN_TYPE.to_string()),
+    })
+}
+
+/// 验证提交的 access_token：类型、签名、黑名单、DPoP 绑定、数据库记录及用户状态
+///
+/// `name` 为参数名（subject_token / actor_token），用于错误信息；`dpop_jkt` 为本次请求
+/// DPoP 证明的公钥指纹，绑定了 DPoP 密钥的 token 必须由持有同一密钥的客户端提交
+async fn verify_exchange_token(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    token: &str,
+    token_type: Option<&str>,
+    name: &str,
+    dpop_jkt: Option<&str>,
+) -> Result<Claims, AppError> {
+    // 1. 验证 token 类型
+    match token_type {
+        Some(token_type) if SUPPORTED_TOKEN_TYPES.contains(&token_type) => {}
+        Some(_) => {
+            return Err(AppError::BadRequest(format!("Unsupported {}_type", name)));
+        }
+        None => return Err(AppError::BadRequest(format!("Missing {}_type", name))),
+    }
+
+    // 2. 验证签名、过期时间和黑名单
+    let invalid = || AppError::InvalidGrant(format!("Invalid {}", name));
+    let claims = jwt_manager.verify_token(token).map_err(|_| invalid())?;
+    if cache.exists(&format!("blacklist:{}", token)).await {
+        return Err(invalid());
+    }
+
+    // 3. 绑定了 DPoP 密钥的 token 需要同一密钥签名的 DPoP 证明（RFC 9449 7）
+    if let Some(cnf) = &claims.cnf
+        && dpop_jkt != Some(cnf.jkt.as_str())
+    {
+        return Err(AppError::InvalidDpopProof(format!(
+            "{} is bound to a different DPoP key",
+            name
+        )));
+    }
+
+    // 4. 必须是未撤销的 access_token（refresh_token 不在 access_tokens 中）
+    let record = storage
+        .find_access_token(token)
+        .await?
+        .filter(|record| record.expires_at > Utc::now())
+        .ok_or_else(invalid)?;
+
+    // 5. 用户被禁用或删除后 token 不再有效
+    if let Some(user_id) = record.user_id {
+        match storage.find_by_id(user_id).await? {
+            Some(user) if user.deleted_at.is_none() && user.is_active => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(claims)
+}
@@ -0,0 +1,268 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::services::oauth_service::{
+    ClientCredentials, authenticate_client, is_public_client, parse_scopes,
+};
+use crate::cache::CompositeCache;
+use crate::errors::AppError;
+use crate::security::jwt::VALIDATION_LEEWAY;
+use crate::security::{Actor, Confirmation, JwtManager, dpop};
+use crate::storage::entities::access_tokens;
+use crate::storage::{SeaOrmBackend, TokenRepository, UserRepository};
+
+#[derive(Debug, Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    #[serde(flatten)]
+    pub credentials: ClientCredentials,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevokeRequest {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    #[serde(flatten)]
+    pub credentials: ClientCredentials,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    /// Token Exchange 委托链（RFC 8693 4.1）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+    /// 绑定的 DPoP 公钥（RFC 9449 6.2）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>,
+}
+
+/// POST /oauth/introspect
+/// 资源服务器查询 token 当前是否有效（RFC 7662）
+pub async fn introspect(
+    http_req: HttpRequest,
+    req: web::Json<IntrospectRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证客户端身份（公共客户端不能调用）
+    let client = authenticate_client(&storage, &cache, &http_req, &req.credentials).await?;
+
+    if is_public_client(&client) {
+        return Err(AppError::UnauthorizedClient(
+            "Public clients cannot introspect tokens".into(),
+        ));
+    }
+
+    // 2. 查询 token 状态（任何无效情况都只返回 active: false）
+    let response = inspect_token(
+        &storage,
+        &jwt_manager,
+        &cache,
+        &req.token,
+        req.token_type_hint.as_deref(),
+    )
+    .await?
+    .unwrap_or_default();
+
+    tracing::debug!(
+        "Token introspected by client: {} active: {}",
+        client.client_id,
+        response.active
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /oauth/revoke
+/// 客户端撤销自己持有的 access token 或 refresh token（RFC 7009）
+pub async fn revoke(
+    http_req: HttpRequest,
+    req: web::Json<RevokeRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
+    cache: web::Data<Arc<CompositeCache>>,
+) -> Result<HttpResponse, AppError> {
+    // 1. 验证客户端身份
+    let client = authenticate_client(&storage, &cache, &http_req, &req.credentials).await?;
+
+    // 2. 查找 token（无效或已撤销的 token 同样返回 200）
+    let Some(stored) =
+        find_stored_token(&storage, &req.token, req.token_type_hint.as_deref()).await?
+    else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+
+    let record = match stored.kind {
+        StoredTokenKind::Access(record) | StoredTokenKind::Refresh(record) => record,
+    };
+
+    // 3. 只能撤销签发给自己的 token
+    if record.client_id != client.client_id {
+        return Err(AppError::UnauthorizedClient(
+            "Token was not issued to this client".into(),
+        ));
+    }
+
+    // 4. 删除 access token 及其 refresh token（撤销整个授权）
+    let refresh_tokens = storage
+        .delete_access_token(record.id)
+        .await?
+        .unwrap_or_default();
+
+    // 5. 将 access token 和 refresh token 加入黑名单，使其在剩余有效期内立即失效
+    blacklist_token(&jwt_manager, &cache, &req.token).await;
+    for token in std::iter::once(&record.token).chain(&refresh_tokens) {
+        if *token != req.token {
+            blacklist_token(&jwt_manager, &cache, token).await;
+        }
+    }
+    cache.delete(&format!("token:{}", record.token)).await;
+
+    tracing::info!(
+        "Token revoked by client: {} user: {:?}",
+        client.client_id,
+        record.user_id
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// 将 JWT 加入黑名单直到其过期（已过期的 token 无需处理）
+pub(crate) async fn blacklist_token(jwt_manager: &JwtManager, cache: &CompositeCache, token: &str) {
+    let Ok(claims) = jwt_manager.verify_token(token) else {
+        return;
+    };
+
+    // 验证时允许时钟偏差，黑名单需要多保留相应时间
+    let remaining = (claims.exp - Utc::now().timestamp()).max(0) as u64 + VALIDATION_LEEWAY;
+    cache
+        .set(
+            &format!("blacklist:{}", token),
+            "revoked".to_string(),
+            Some(remaining),
+        )
+        .await;
+}
+
+/// 数据库中的 token 记录
+struct StoredToken {
+    kind: StoredTokenKind,
+    expires_at: DateTime<Utc>,
+}
+
+enum StoredTokenKind {
+    Access(access_tokens::Model),
+    /// refresh token 关联的 access token 记录
+    Refresh(access_tokens::Model),
+}
+
+/// 按 token_type_hint 决定查找顺序，在 access_tokens / refresh_tokens 中查找 token
+async fn find_stored_token(
+    storage: &SeaOrmBackend,
+    token: &str,
+    token_type_hint: Option<&str>,
+) -> Result<Option<StoredToken>, AppError> {
+    let refresh_first = token_type_hint == Some("refresh_token");
+
+    for refresh in [refresh_first, !refresh_first] {
+        let stored = if refresh {
+            storage
+                .find_refresh_token(token)
+                .await?
+                .map(|(refresh, access)| StoredToken {
+                    expires_at: refresh.expires_at.with_timezone(&Utc),
+                    kind: StoredTokenKind::Refresh(access),
+                })
+        } else {
+            storage
+                .find_access_token(token)
+                .await?
+                .map(|access| StoredToken {
+                    expires_at: access.expires_at.with_timezone(&Utc),
+                    kind: StoredTokenKind::Access(access),
+                })
+        };
+
+        if stored.is_some() {
+            return Ok(stored);
+        }
+    }
+
+    Ok(None)
+}
+
+/// 检查 token 的签名、黑名单和数据库记录，有效时返回其元数据
+async fn inspect_token(
+    storage: &SeaOrmBackend,
+    jwt_manager: &JwtManager,
+    cache: &CompositeCache,
+    token: &str,
+    token_type_hint: Option<&str>,
+) -> Result<Option<IntrospectResponse>, AppError> {
+    // 1. 验证签名和过期时间
+    let Ok(claims) = jwt_manager.verify_token(token) else {
+        return Ok(None);
+    };
+
+    // 2. 检查黑名单
+    if cache.exists(&format!("blacklist:{}", token)).await {
+        return Ok(None);
+    }
+
+    // 3. 查找数据库记录（撤销后记录被删除）
+    let Some(stored) = find_stored_token(storage, token, token_type_hint).await? else {
+        return Ok(None);
+    };
+
+    if stored.expires_at < Utc::now() {
+        return Ok(None);
+    }
+
+    let (record, token_type) = match stored.kind {
+        StoredTokenKind::Access(record) => (
+            record,
+            dpop::token_type(claims.cnf.as_ref().map(|cnf| cnf.jkt.as_str())),
+        ),
+        StoredTokenKind::Refresh(record) => (record, "refresh_token"),
+    };
+
+    // 4. 用户被禁用或删除后 token 不再有效
+    if let Some(user_id) = record.user_id {
+        match storage.find_by_id(user_id).await? {
+            Some(user) if user.deleted_at.is_none() && user.is_active => {}
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some(IntrospectResponse {
+        active: true,
+        scope: Some(parse_scopes(&record.scopes).join(" ")),
+        client_id: Some(record.client_id),
+        sub: Some(claims.sub),
+        exp: Some(claims.exp),
+        iat: Some(claims.iat),
+        token_type: Some(token_type.to_string()),
+        aud: claims.aud,
+        act: claims.act,
+        cnf: claims.cnf,
+    }))
+}
@@ -1,10 +1,15 @@
-use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
-use serde::Serialize;
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use url::Url;
 
+use crate::api::services::subject_service::current_user_id;
+use crate::api::services::token_service::blacklist_token;
 use crate::cache::CompositeCache;
 use crate::errors::AppError;
-use crate::security::Claims;
+use crate::security::JwtManager;
+use crate::storage::entities::users;
+use crate::storage::repository::UserUpdateFields;
 use crate::storage::{SeaOrmBackend, UserRepository};
 
 #[derive(Debug, Serialize)]
@@ -12,7 +17,72 @@ pub struct UserProfileResponse {
     pub id: i64,
     pub username: String,
     pub email: String,
+    pub email_verified: bool,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub picture: Option<String>,
+    pub locale: Option<String>,
+    pub zoneinfo: Option<String>,
+    pub phone_number: Option<String>,
+    pub phone_number_verified: bool,
+    pub address: Option<Address>,
     pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 用户地址（OpenID Connect Core 5.1.1）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Address {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+}
+
+/// 更新用户资料请求（未提供的字段保持不变，空字符串表示清空）
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub picture: Option<String>,
+    pub locale: Option<String>,
+    pub zoneinfo: Option<String>,
+    pub phone_number: Option<String>,
+    /// 所有成员为空的地址表示清空
+    pub address: Option<Address>,
+}
+
+impl From<users::Model> for UserProfileResponse {
+    fn from(user: users::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            email_verified: user.email_verified,
+            given_name: user.given_name,
+            family_name: user.family_name,
+            picture: user.picture,
+            locale: user.locale,
+            zoneinfo: user.zoneinfo,
+            phone_number: user.phone_number,
+            phone_number_verified: user.phone_number_verified,
+            address: user
+                .address
+                .as_deref()
+                .and_then(|address| serde_json::from_str(address).ok()),
+            created_at: user.created_at.to_rfc3339(),
+            updated_at: user.updated_at.to_rfc3339(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -29,18 +99,8 @@ pub async fn get_profile(
     req: HttpRequest,
     storage: web::Data<Arc<SeaOrmBackend>>,
 ) -> Result<HttpResponse, AppError> {
-    // 从请求扩展中提取 Claims（由 JwtAuth 中间件注入）
-    let claims = req
-        .extensions()
-        .get::<Claims>()
-        .cloned()
-        .ok_or(AppError::Unauthorized)?;
-
-    // 解析 user_id
-    let user_id: i64 = claims
-        .sub
-        .parse()
-        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+    // 当前用户（只接受第一方登录签发的 Token）
+    let user_id = current_user_id(&req, &storage).await?;
 
     // 查询用户信息
     let user = storage
@@ -48,12 +108,155 @@ pub async fn get_profile(
         .await?
         .ok_or(AppError::NotFound)?;
 
-    Ok(HttpResponse::Ok().json(UserProfileResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        created_at: user.created_at.to_rfc3339(),
-    }))
+    Ok(HttpResponse::Ok().json(UserProfileResponse::from(user)))
+}
+
+/// PUT /api/user/me
+/// 更新当前用户的资料（OIDC 标准 claims）
+pub async fn update_profile(
+    req: HttpRequest,
+    body: web::Json<UpdateProfileRequest>,
+    storage: web::Data<Arc<SeaOrmBackend>>,
+) -> Result<HttpResponse, AppError> {
+    // 当前用户（只接受第一方登录签发的 Token）
+    let user_id = current_user_id(&req, &storage).await?;
+
+    let user = storage
+        .find_by_id(user_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // 1. 验证各字段
+    let body = body.into_inner();
+    let given_name = body
+        .given_name
+        .map(|name| clearable(name, 100))
+        .transpose()?;
+    let family_name = body
+        .family_name
+        .map(|name| clearable(name, 100))
+        .transpose()?;
+    let picture = body
+        .picture
+        .map(|picture| clearable(picture, 2048))
+        .transpose()?;
+    if let Some(Some(picture)) = &picture
+        && !Url::parse(picture).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        return Err(AppError::BadRequest(
+            "picture must be an http(s) URL".into(),
+        ));
+    }
+    let locale = body
+        .locale
+        .map(|locale| clearable(locale, 35))
+        .transpose()?;
+    if let Some(Some(locale)) = &locale
+        && !is_language_tag(locale)
+    {
+        return Err(AppError::BadRequest("Invalid locale".into()));
+    }
+    let zoneinfo = body
+        .zoneinfo
+        .map(|zoneinfo| clearable(zoneinfo, 64))
+        .transpose()?;
+    if let Some(Some(zoneinfo)) = &zoneinfo
+        && zoneinfo.contains(char::is_whitespace)
+    {
+        return Err(AppError::BadRequest("Invalid zoneinfo".into()));
+    }
+    let phone_number = body
+        .phone_number
+        .map(|phone| clearable(phone, 16))
+        .transpose()?;
+    if let Some(Some(phone)) = &phone_number
+        && !is_e164(phone)
+    {
+        return Err(AppError::BadRequest(
+            "phone_number must be in E.164 format".into(),
+        ));
+    }
+    let address = body.address.map(address_json).transpose()?;
+
+    // 2. 更换手机号后需要重新验证
+    let phone_number_verified = phone_number
+        .as_ref()
+        .filter(|phone| **phone != user.phone_number)
+        .map(|_| false);
+
+    // 3. 保存
+    let user = storage
+        .update_user(
+            user_id,
+            UserUpdateFields {
+                given_name,
+                family_name,
+                picture,
+                locale,
+                zoneinfo,
+                phone_number,
+                address,
+                phone_number_verified,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    tracing::info!("Profile updated for user {}", user_id);
+
+    Ok(HttpResponse::Ok().json(UserProfileResponse::from(user)))
+}
+
+/// 去除首尾空白，空字符串表示清空
+fn clearable(value: String, max_len: usize) -> Result<Option<String>, AppError> {
+    let value = value.trim();
+    if value.chars().count() > max_len {
+        return Err(AppError::BadRequest(format!(
+            "Profile fields cannot exceed {} characters",
+            max_len
+        )));
+    }
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+/// 简单校验 BCP 47 语言标签（如 zh-CN、en）
+fn is_language_tag(locale: &str) -> bool {
+    locale.split(['-', '_']).all(|part| {
+        (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// E.164 格式的手机号（+ 加最多 15 位数字）
+fn is_e164(phone: &str) -> bool {
+    phone.strip_prefix('+').is_some_and(|digits| {
+        (2..=15).contains(&digits.len())
+            && !digits.starts_with('0')
+            && digits.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// 序列化地址（所有成员为空时表示清空）
+fn address_json(address: Address) -> Result<Option<String>, AppError> {
+    let clean = |member: Option<String>| -> Result<Option<String>, AppError> {
+        member
+            .map(|member| clearable(member, 255))
+            .transpose()
+            .map(Option::flatten)
+    };
+    let address = Address {
+        formatted: clean(address.formatted)?,
+        street_address: clean(address.street_address)?,
+        locality: clean(address.locality)?,
+        region: clean(address.region)?,
+        postal_code: clean(address.postal_code)?,
+        country: clean(address.country)?,
+    };
+    let value = serde_json::to_value(&address)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize address: {}", e)))?;
+    if value.as_object().is_some_and(|members| members.is_empty()) {
+        return Ok(None);
+    }
+    Ok(Some(value.to_string()))
 }
 
 /// GET /api/user/authorizations
@@ -62,17 +265,8 @@ pub async fn list_authorizations(
     req: HttpRequest,
     storage: web::Data<Arc<SeaOrmBackend>>,
 ) -> Result<HttpResponse, AppError> {
-    // 从请求扩展中提取 Claims
-    let claims = req
-        .extensions()
-        .get::<Claims>()
-        .cloned()
-        .ok_or(AppError::Unauthorized)?;
-
-    let user_id: i64 = claims
-        .sub
-        .parse()
-        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+    // 当前用户（只接受第一方登录签发的 Token）
+    let user_id = current_user_id(&req, &storage).await?;
 
     // 从数据库查询用户的授权记录
     let authorizations = storage.get_user_authorizations(user_id).await?;
@@ -97,31 +291,24 @@ pub async fn revoke_authorization(
     req: HttpRequest,
     client_id: web::Path<String>,
     storage: web::Data<Arc<SeaOrmBackend>>,
+    jwt_manager: web::Data<Arc<JwtManager>>,
     cache: web::Data<Arc<CompositeCache>>,
 ) -> Result<HttpResponse, AppError> {
-    // 从请求扩展中提取 Claims
-    let claims = req
-        .extensions()
-        .get::<Claims>()
-        .cloned()
-        .ok_or(AppError::Unauthorized)?;
-
-    let user_id: i64 = claims
-        .sub
-        .parse()
-        .map_err(|_| AppError::Internal("Invalid user_id in token".into()))?;
+    // 当前用户（只接受第一方登录签发的 Token）
+    let user_id = current_user_id(&req, &storage).await?;
 
     // 从数据库删除授权记录（包括 access_tokens 和 refresh_tokens）
-    storage
+    let tokens = storage
         .revoke_user_authorization(user_id, &client_id)
         .await?;
 
-    // 将用户的相关 token 加入黑名单（可选，增加安全性）
-    // 使用较短的 TTL，因为 token 本身有过期时间
-    let blacklist_key = format!("blacklist:user:{}:client:{}", user_id, client_id.as_str());
-    cache
-        .set(&blacklist_key, "revoked".to_string(), Some(3600))
-        .await;
+    // 删除同意记录，下次授权时需要重新确认
+    storage.delete_consent(user_id, &client_id).await?;
+
+    // 将已签发的 JWT 加入黑名单，使其在剩余有效期内立即失效
+    for token in &tokens {
+        blacklist_token(&jwt_manager, &cache, token).await;
+    }
 
     tracing::info!(
         "Authorization revoked for user {} client {}",
@@ -40,12 +40,28 @@ impl CompositeCache {
         self.l2.set(key, value, ttl).await;
     }
 
+    /// 键不存在时设置缓存（以 L2 为准，成功后写入 L1），返回是否设置成功
+    pub async fn set_if_absent(&self, key: &str, value: String, ttl: Option<u64>) -> bool {
+        let inserted = self.l2.set_if_absent(key, value.clone(), ttl).await;
+        if inserted {
+            self.l1.set(key, value, ttl).await;
+        }
+        inserted
+    }
+
     /// 删除缓存（同时删除 L1 和 L2）
     pub async fn delete(&self, key: &str) {
         self.l1.delete(key).await;
         self.l2.delete(key).await;
     }
 
+    /// 取出并删除缓存（以 L2 为准，多实例共享 Redis 时同样只有一个请求能取到）
+    pub async fn take(&self, key: &str) -> Option<String> {
+        let value = self.l2.take(key).await;
+        self.l1.delete(key).await;
+        value
+    }
+
     /// 检查键是否存在（先查 L1，再查 L2）
     pub async fn exists(&self, key: &str) -> bool {
         self.l1.exists(key).await || self.l2.exists(key).await
@@ -68,10 +84,18 @@ impl Cache for CompositeCache {
         self.set(key, value, ttl).await;
     }
 
+    async fn set_if_absent(&self, key: &str, value: String, ttl: Option<u64>) -> bool {
+        self.set_if_absent(key, value, ttl).await
+    }
+
     async fn delete(&self, key: &str) {
         self.delete(key).await;
     }
 
+    async fn take(&self, key: &str) -> Option<String> {
+        self.take(key).await
+    }
+
     async fn exists(&self, key: &str) -> bool {
         self.exists(key).await
     }
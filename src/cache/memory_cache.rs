@@ -1,20 +1,50 @@
 use async_trait::async_trait;
+use moka::Expiry;
 use moka::future::Cache as MokaCache;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::cache::traits::Cache;
 
+/// 未指定 TTL 时的默认过期时间（5 分钟）
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// 缓存值及其 TTL
+#[derive(Clone)]
+struct Entry {
+    value: String,
+    ttl: Duration,
+}
+
+/// 按写入时指定的 TTL 过期（黑名单等需要与 token 剩余有效期一致）
+struct PerKeyExpiry;
+
+impl Expiry<String, Entry> for PerKeyExpiry {
+    fn expire_after_create(&self, _key: &String, entry: &Entry, _now: Instant) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &Entry,
+        _now: Instant,
+        _current: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+}
+
 /// 内存缓存实现（基于 Moka）
 pub struct MemoryCache {
-    cache: Arc<MokaCache<String, String>>,
+    cache: Arc<MokaCache<String, Entry>>,
 }
 
 impl MemoryCache {
     pub fn new(max_capacity: u64) -> Self {
         let cache = MokaCache::builder()
             .max_capacity(max_capacity)
-            .time_to_live(Duration::from_secs(300)) // 默认 5 分钟过期
+            .expire_after(PerKeyExpiry)
             .build();
 
         Self {
@@ -26,19 +56,33 @@ impl MemoryCache {
 #[async_trait]
 impl Cache for MemoryCache {
     async fn get(&self, key: &str) -> Option<String> {
-        self.cache.get(key).await
+        self.cache.get(key).await.map(|entry| entry.value)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Option<u64>) {
+        let ttl = ttl.map(Duration::from_secs).unwrap_or(DEFAULT_TTL);
+        self.cache
+            .insert(key.to_string(), Entry { value, ttl })
+            .await;
     }
 
-    async fn set(&self, key: &str, value: String, _ttl: Option<u64>) {
-        // Moka 的 TTL 在创建 Cache 时统一设置，这里简化为直接 insert
-        // 如果需要per-key TTL，需要使用不同的方案
-        self.cache.insert(key.to_string(), value).await;
+    async fn set_if_absent(&self, key: &str, value: String, ttl: Option<u64>) -> bool {
+        let ttl = ttl.map(Duration::from_secs).unwrap_or(DEFAULT_TTL);
+        self.cache
+            .entry(key.to_string())
+            .or_insert(Entry { value, ttl })
+            .await
+            .is_fresh()
     }
 
     async fn delete(&self, key: &str) {
         self.cache.invalidate(key).await;
     }
 
+    async fn take(&self, key: &str) -> Option<String> {
+        self.cache.remove(key).await.map(|entry| entry.value)
+    }
+
     async fn exists(&self, key: &str) -> bool {
         self.cache.contains_key(key)
     }
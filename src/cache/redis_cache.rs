@@ -40,11 +40,30 @@ impl Cache for RedisCache {
         }
     }
 
+    async fn set_if_absent(&self, key: &str, value: String, ttl: Option<u64>) -> bool {
+        let mut conn = self.conn.lock().await;
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(key).arg(value).arg("NX");
+        if let Some(ttl_secs) = ttl {
+            cmd.arg("EX").arg(ttl_secs);
+        }
+        // 键已存在时 SET NX 返回 nil
+        matches!(
+            cmd.query_async::<Option<String>>(&mut *conn).await,
+            Ok(Some(_))
+        )
+    }
+
     async fn delete(&self, key: &str) {
         let mut conn = self.conn.lock().await;
         let _: Result<(), redis::RedisError> = conn.del(key).await;
     }
 
+    async fn take(&self, key: &str) -> Option<String> {
+        let mut conn = self.conn.lock().await;
+        conn.get_del(key).await.ok().flatten()
+    }
+
     async fn exists(&self, key: &str) -> bool {
         let mut conn = self.conn.lock().await;
         conn.exists(key).await.unwrap_or(false)
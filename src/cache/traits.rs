@@ -9,9 +9,15 @@ pub trait Cache: Send + Sync {
     /// 设置缓存值
     async fn set(&self, key: &str, value: String, ttl: Option<u64>);
 
+    /// 键不存在时设置缓存值（原子操作），返回是否设置成功
+    async fn set_if_absent(&self, key: &str, value: String, ttl: Option<u64>) -> bool;
+
     /// 删除缓存
     async fn delete(&self, key: &str);
 
+    /// 取出并删除缓存值（原子操作，并发请求中只有一个能取到）
+    async fn take(&self, key: &str) -> Option<String>;
+
     /// 检查键是否存在
     async fn exists(&self, key: &str) -> bool;
 
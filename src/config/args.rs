@@ -17,6 +17,7 @@
 ///
 /// # 示例
 /// ```
+/// # use ferrusgate_lite::config::args::parse_config_path;
 /// let args = vec!["program".to_string(), "-c".to_string(), "custom.toml".to_string()];
 /// assert_eq!(parse_config_path(&args), Some("custom.toml".to_string()));
 /// ```
@@ -56,9 +57,13 @@ pub fn parse_config_path(args: &[String]) -> Option<String> {
 ///
 /// # 示例
 /// ```
-/// let args = vec!["program", "-c", "custom.toml", "serve"];
+/// # use ferrusgate_lite::config::args::filter_config_args;
+/// let args: Vec<String> = ["program", "-c", "custom.toml", "serve"]
+///     .iter()
+///     .map(|s| s.to_string())
+///     .collect();
 /// let filtered = filter_config_args(&args);
-/// // 结果: ["program", "serve"]
+/// assert_eq!(filtered, vec!["program".to_string(), "serve".to_string()]);
 /// ```
 pub fn filter_config_args(args: &[String]) -> Vec<String> {
     let mut filtered = Vec::new();
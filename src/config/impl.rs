@@ -4,6 +4,7 @@ use std::path::Path;
 use std::sync::OnceLock;
 
 use super::AppConfig;
+use crate::security::keys::SUPPORTED_SIGNING_ALGORITHMS;
 
 static CONFIG: OnceLock<AppConfig> = OnceLock::new();
 static CONFIG_PATH: OnceLock<String> = OnceLock::new();
@@ -102,6 +103,9 @@ impl AppConfig {
                 eprintln!("[ERROR] 无效的 SERVER_PORT: {}", port);
             }
         }
+        if let Ok(issuer) = env::var("OIDC_ISSUER") {
+            self.server.issuer = Some(issuer);
+        }
 
         // 数据库配置
         if let Ok(database_url) = env::var("DATABASE_URL") {
@@ -159,6 +163,18 @@ impl AppConfig {
                 eprintln!("[ERROR] 无效的 AUTHORIZATION_CODE_EXPIRE: {}", expire);
             }
         }
+        if let Ok(algorithm) = env::var("JWT_SIGNING_ALGORITHM") {
+            self.auth.signing_algorithm = algorithm;
+        }
+        if let Ok(path) = env::var("JWT_SIGNING_KEY_PATH") {
+            self.auth.signing_key_path = path;
+        }
+        if let Ok(salt) = env::var("PAIRWISE_SUBJECT_SALT") {
+            self.auth.pairwise_subject_salt = salt;
+        }
+        if let Ok(key) = env::var("SIGNING_KEY_ENCRYPTION_KEY") {
+            self.auth.signing_key_encryption_key = key;
+        }
 
         // 缓存配置
         if let Ok(enable) = env::var("ENABLE_MEMORY_CACHE") {
@@ -227,12 +243,52 @@ impl AppConfig {
         Ok(())
     }
 
+    /// 对外公开的 Issuer（不带末尾的 /）
+    pub fn issuer(&self) -> String {
+        match &self.server.issuer {
+            Some(issuer) => issuer.trim_end_matches('/').to_string(),
+            None => format!("http://{}:{}", self.server.host, self.server.port),
+        }
+    }
+
     /// 验证配置有效性
     pub fn validate(&self) -> Result<(), String> {
         if self.auth.jwt_secret.len() < 32 {
             return Err("JWT secret 必须至少 32 个字符".to_string());
         }
 
+        if !SUPPORTED_SIGNING_ALGORITHMS.contains(&self.auth.signing_algorithm.as_str()) {
+            return Err(format!(
+                "不支持的签名算法: {}（可选: {}）",
+                self.auth.signing_algorithm,
+                SUPPORTED_SIGNING_ALGORITHMS.join(", ")
+            ));
+        }
+
+        if let Some(issuer) = &self.server.issuer
+            && !(issuer.starts_with("https://") || issuer.starts_with("http://"))
+        {
+            return Err(format!("Issuer 必须是 http(s) URL: {}", issuer));
+        }
+
+        // scope-token 只能包含可见 ASCII 字符（不含空格、双引号和反斜杠，RFC 6749 3.3）
+        if let Some(scope) = self.auth.supported_scopes.iter().find(|scope| {
+            scope.is_empty()
+                || !scope
+                    .bytes()
+                    .all(|b| (0x21..=0x7e).contains(&b) && b != b'"' && b != b'\\')
+        }) {
+            return Err(format!("无效的 scope: {:?}", scope));
+        }
+
+        if self.auth.pairwise_subject_salt.is_empty() {
+            return Err("Pairwise subject salt 不能为空".to_string());
+        }
+
+        if self.auth.signing_key_encryption_key.len() < 32 {
+            return Err("签名私钥加密密钥必须至少 32 个字符".to_string());
+        }
+
         if self.auth.access_token_expire <= 0 {
             return Err("Access token 过期时间必须为正数".to_string());
         }
@@ -264,7 +320,8 @@ pub fn get_config() -> &'static AppConfig {
 ///   - `None`: 从默认 "config.toml" 加载（不存在则警告）
 ///
 /// # 示例
-/// ```
+/// ```no_run
+/// # use ferrusgate_lite::config::init_config;
 /// // 使用默认 config.toml
 /// init_config(None);
 ///
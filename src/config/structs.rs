@@ -85,6 +85,13 @@ impl Default for CachePolicyConfig {
     }
 }
 
+/// 动态客户端注册配置（从数据库读取）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClientRegistrationConfig {
+    /// 是否允许不携带初始访问令牌注册客户端
+    pub allow_open_registration: bool,
+}
+
 /// 服务器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -92,6 +99,9 @@ pub struct ServerConfig {
     pub host: String,
     #[serde(default = "default_server_port")]
     pub port: u16,
+    /// 对外公开的 Issuer（ID Token 的 iss 及各端点的基础地址），未配置时使用 http://host:port
+    #[serde(default)]
+    pub issuer: Option<String>,
 }
 
 /// 数据库配置
@@ -125,6 +135,19 @@ pub struct AuthConfig {
     pub refresh_token_expire: i64,
     #[serde(default = "default_authorization_code_expire")]
     pub authorization_code_expire: i64,
+    #[serde(default = "default_signing_algorithm")]
+    pub signing_algorithm: String,
+    #[serde(default = "default_signing_key_path")]
+    pub signing_key_path: String,
+    /// 服务器支持的 scope（客户端只能请求其中的 scope）
+    #[serde(default = "default_supported_scopes")]
+    pub supported_scopes: Vec<String>,
+    /// 计算 pairwise subject 使用的 salt（修改后新用户的标识随之改变，已签发的保持不变）
+    #[serde(default = "default_pairwise_subject_salt")]
+    pub pairwise_subject_salt: String,
+    /// 加密数据库中签名私钥的密钥（修改后已保存的私钥无法解密）
+    #[serde(default = "default_signing_key_encryption_key")]
+    pub signing_key_encryption_key: String,
 }
 
 /// 缓存配置
@@ -201,6 +224,32 @@ fn default_authorization_code_expire() -> i64 {
     300 // 5 minutes
 }
 
+fn default_signing_algorithm() -> String {
+    "RS256".to_string()
+}
+
+fn default_signing_key_path() -> String {
+    "ferrusgate_signing_key.pem".to_string()
+}
+
+fn default_supported_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+        "phone".to_string(),
+        "address".to_string(),
+    ]
+}
+
+fn default_signing_key_encryption_key() -> String {
+    "CHANGE-THIS-ENCRYPTION-KEY-IN-PRODUCTION-MIN-32-CHARS".to_string()
+}
+
+fn default_pairwise_subject_salt() -> String {
+    "CHANGE-THIS-SALT-IN-PRODUCTION".to_string()
+}
+
 fn default_enable_memory_cache() -> bool {
     true
 }
@@ -240,6 +289,7 @@ impl Default for ServerConfig {
         Self {
             host: default_server_host(),
             port: default_server_port(),
+            issuer: None,
         }
     }
 }
@@ -270,6 +320,11 @@ impl Default for AuthConfig {
             access_token_expire: default_access_token_expire(),
             refresh_token_expire: default_refresh_token_expire(),
             authorization_code_expire: default_authorization_code_expire(),
+            signing_algorithm: default_signing_algorithm(),
+            signing_key_path: default_signing_key_path(),
+            supported_scopes: default_supported_scopes(),
+            pairwise_subject_salt: default_pairwise_subject_salt(),
+            signing_key_encryption_key: default_signing_key_encryption_key(),
         }
     }
 }
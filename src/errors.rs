@@ -1,7 +1,10 @@
-use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use actix_web::http::{StatusCode, header};
+use actix_web::{HttpResponse, ResponseError};
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::security::dpop::DPOP_NONCE_HEADER;
+
 #[derive(Debug, Error)]
 pub enum AppError {
     // 数据库错误
@@ -28,10 +31,18 @@ pub enum AppError {
     #[error("Forbidden: {0}")]
     Forbidden(String),
 
+    // Token 有效但未获授权访问该资源所需的 scope（RFC 6750 3.1）
+    #[error("Insufficient scope: {0}")]
+    InsufficientScope(String),
+
     // OAuth2 错误
     #[error("Invalid OAuth2 client")]
     InvalidClient,
 
+    // 通过 Authorization 头认证失败的客户端（401）
+    #[error("Invalid OAuth2 client")]
+    InvalidClientBasic,
+
     #[error("Invalid authorization code")]
     InvalidAuthCode,
 
@@ -44,6 +55,39 @@ pub enum AppError {
     #[error("Invalid scope")]
     InvalidScope,
 
+    #[error("Invalid grant: {0}")]
+    InvalidGrant(String),
+
+    #[error("Unauthorized client: {0}")]
+    UnauthorizedClient(String),
+
+    #[error("Authorization pending")]
+    AuthorizationPending,
+
+    #[error("Polling too frequently, slow down")]
+    SlowDown,
+
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+
+    #[error("Device code expired")]
+    ExpiredToken,
+
+    #[error("Invalid client metadata: {0}")]
+    InvalidClientMetadata(String),
+
+    #[error("Invalid request object: {0}")]
+    InvalidRequestObject(String),
+
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+
+    #[error("Invalid DPoP proof: {0}")]
+    InvalidDpopProof(String),
+
+    #[error("DPoP nonce required")]
+    UseDpopNonce(String),
+
     // 通用错误
     #[error("Not found")]
     NotFound,
@@ -69,11 +113,24 @@ impl AppError {
             AppError::InvalidToken => "E005",
             AppError::Unauthorized => "E006",
             AppError::Forbidden(_) => "E016",
+            AppError::InsufficientScope(_) => "E029",
             AppError::InvalidClient => "E007",
+            AppError::InvalidClientBasic => "E028",
             AppError::InvalidAuthCode => "E008",
             AppError::InvalidRedirectUri => "E009",
             AppError::InvalidGrantType => "E010",
             AppError::InvalidScope => "E011",
+            AppError::InvalidGrant(_) => "E017",
+            AppError::UnauthorizedClient(_) => "E018",
+            AppError::AuthorizationPending => "E019",
+            AppError::SlowDown => "E020",
+            AppError::AccessDenied(_) => "E021",
+            AppError::ExpiredToken => "E022",
+            AppError::InvalidClientMetadata(_) => "E023",
+            AppError::InvalidRequestObject(_) => "E024",
+            AppError::InvalidTarget(_) => "E025",
+            AppError::InvalidDpopProof(_) => "E026",
+            AppError::UseDpopNonce(_) => "E027",
             AppError::NotFound => "E012",
             AppError::BadRequest(_) => "E013",
             AppError::Internal(_) => "E014",
@@ -91,11 +148,23 @@ impl AppError {
             AppError::InvalidToken => "Invalid Token",
             AppError::Unauthorized => "Unauthorized",
             AppError::Forbidden(_) => "Forbidden",
-            AppError::InvalidClient => "Invalid Client",
+            AppError::InsufficientScope(_) => "Insufficient Scope",
+            AppError::InvalidClient | AppError::InvalidClientBasic => "Invalid Client",
             AppError::InvalidAuthCode => "Invalid Authorization Code",
             AppError::InvalidRedirectUri => "Invalid Redirect URI",
             AppError::InvalidGrantType => "Invalid Grant Type",
             AppError::InvalidScope => "Invalid Scope",
+            AppError::InvalidGrant(_) => "Invalid Grant",
+            AppError::UnauthorizedClient(_) => "Unauthorized Client",
+            AppError::AuthorizationPending => "Authorization Pending",
+            AppError::SlowDown => "Slow Down",
+            AppError::AccessDenied(_) => "Access Denied",
+            AppError::ExpiredToken => "Expired Token",
+            AppError::InvalidClientMetadata(_) => "Invalid Client Metadata",
+            AppError::InvalidRequestObject(_) => "Invalid Request Object",
+            AppError::InvalidTarget(_) => "Invalid Target",
+            AppError::InvalidDpopProof(_) => "Invalid DPoP Proof",
+            AppError::UseDpopNonce(_) => "Use DPoP Nonce",
             AppError::NotFound => "Not Found",
             AppError::BadRequest(_) => "Bad Request",
             AppError::Internal(_) => "Internal Server Error",
@@ -143,9 +212,10 @@ impl ResponseError for AppError {
             AppError::InvalidCredentials
             | AppError::TokenExpired
             | AppError::InvalidToken
-            | AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            | AppError::Unauthorized
+            | AppError::InvalidClientBasic => StatusCode::UNAUTHORIZED,
 
-            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Forbidden(_) | AppError::InsufficientScope(_) => StatusCode::FORBIDDEN,
 
             AppError::NotFound => StatusCode::NOT_FOUND,
 
@@ -154,7 +224,18 @@ impl ResponseError for AppError {
             | AppError::InvalidAuthCode
             | AppError::InvalidRedirectUri
             | AppError::InvalidGrantType
-            | AppError::InvalidScope => StatusCode::BAD_REQUEST,
+            | AppError::InvalidScope
+            | AppError::InvalidGrant(_)
+            | AppError::UnauthorizedClient(_)
+            | AppError::AuthorizationPending
+            | AppError::SlowDown
+            | AppError::AccessDenied(_)
+            | AppError::ExpiredToken
+            | AppError::InvalidClientMetadata(_)
+            | AppError::InvalidRequestObject(_)
+            | AppError::InvalidTarget(_)
+            | AppError::InvalidDpopProof(_)
+            | AppError::UseDpopNonce(_) => StatusCode::BAD_REQUEST,
 
             AppError::Database(_)
             | AppError::Redis(_)
@@ -171,17 +252,45 @@ impl ResponseError for AppError {
             AppError::InvalidToken => "invalid_token",
             AppError::Unauthorized => "unauthorized",
             AppError::Forbidden(_) => "forbidden",
-            AppError::InvalidClient => "invalid_client",
+            AppError::InsufficientScope(_) => "insufficient_scope",
+            AppError::InvalidClient | AppError::InvalidClientBasic => "invalid_client",
             AppError::InvalidAuthCode => "invalid_grant",
             AppError::InvalidRedirectUri => "invalid_request",
             AppError::InvalidGrantType => "unsupported_grant_type",
             AppError::InvalidScope => "invalid_scope",
+            AppError::InvalidGrant(_) => "invalid_grant",
+            AppError::UnauthorizedClient(_) => "unauthorized_client",
+            AppError::AuthorizationPending => "authorization_pending",
+            AppError::SlowDown => "slow_down",
+            AppError::AccessDenied(_) => "access_denied",
+            AppError::ExpiredToken => "expired_token",
+            AppError::InvalidClientMetadata(_) => "invalid_client_metadata",
+            AppError::InvalidRequestObject(_) => "invalid_request_object",
+            AppError::InvalidTarget(_) => "invalid_target",
+            AppError::InvalidDpopProof(_) => "invalid_dpop_proof",
+            AppError::UseDpopNonce(_) => "use_dpop_nonce",
             AppError::NotFound => "not_found",
             AppError::BadRequest(_) => "bad_request",
             _ => "internal_error",
         };
 
-        HttpResponse::build(status).json(ErrorResponse {
+        let mut response = HttpResponse::build(status);
+        // 下发客户端需要在 DPoP 证明中使用的 nonce（RFC 9449 8）
+        if let AppError::UseDpopNonce(nonce) = self {
+            response.insert_header((DPOP_NONCE_HEADER, nonce.as_str()));
+        }
+        // 客户端使用 HTTP Basic 认证失败时提示认证方案（RFC 6749 5.2）
+        if let AppError::InvalidClientBasic = self {
+            response.insert_header((header::WWW_AUTHENTICATE, "Basic"));
+        }
+        if let AppError::InsufficientScope(_) = self {
+            response.insert_header((
+                header::WWW_AUTHENTICATE,
+                r#"Bearer error="insufficient_scope""#,
+            ));
+        }
+
+        response.json(ErrorResponse {
             error: error_type.to_string(),
             message: self.to_string(),
         })
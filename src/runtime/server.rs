@@ -2,8 +2,10 @@ use actix_web::{App, HttpServer, middleware, web};
 use std::sync::Arc;
 
 use crate::api::{middleware as app_middleware, services};
+use crate::cache::CompositeCache;
 use crate::config::get_config;
 use crate::runtime::startup::StartupContext;
+use crate::security::JwtManager;
 use crate::storage::SeaOrmBackend;
 
 pub async fn run_server(ctx: StartupContext) -> std::io::Result<()> {
@@ -18,6 +20,7 @@ pub async fn run_server(ctx: StartupContext) -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             // 共享状态
+            .app_data(web::Data::new(config.clone()))
             .app_data(web::Data::new(ctx.db.clone()))
             .app_data(web::Data::new(storage.clone()))
             .app_data(web::Data::new(ctx.cache.clone()))
@@ -26,128 +29,217 @@ pub async fn run_server(ctx: StartupContext) -> std::io::Result<()> {
             .wrap(middleware::Logger::default())
             .wrap(middleware::Compress::default())
             .wrap(middleware::DefaultHeaders::new().add(("X-Version", env!("CARGO_PKG_VERSION"))))
-            // 健康检查端点（无需认证）
-            .service(
-                web::scope("/health")
-                    .route("", web::get().to(services::health_check))
-                    .route("/ready", web::get().to(services::readiness))
-                    .route("/live", web::get().to(services::liveness)),
-            )
-            // 认证 API（无需认证）
-            .service(
-                web::scope("/api/auth")
-                    .route("/register", web::post().to(services::register))
-                    .route("/login", web::post().to(services::login))
-                    .route("/verify-invite", web::post().to(services::invite_verify)),
-            )
-            // OAuth2 授权端点
-            .service(
-                web::scope("/oauth")
-                    .route("/authorize", web::get().to(services::oauth_authorize))
-                    .route("/token", web::post().to(services::oauth_token))
-                    .route(
-                        "/userinfo",
-                        web::get()
-                            .to(services::oidc_userinfo)
-                            .wrap(app_middleware::JwtAuth::new(
-                                ctx.jwt_manager.clone(),
-                                ctx.cache.clone(),
-                            )),
-                    ),
-            )
-            // OIDC Discovery 端点（无需认证）
-            .service(
-                web::scope("/.well-known")
-                    .route(
-                        "/openid-configuration",
-                        web::get().to(services::oidc_discovery),
-                    )
-                    .route("/jwks.json", web::get().to(services::oidc_jwks)),
-            )
-            // 用户 API（需要 JWT 认证）
-            .service(
-                web::scope("/api/user")
-                    .wrap(app_middleware::JwtAuth::new(
-                        ctx.jwt_manager.clone(),
-                        ctx.cache.clone(),
-                    ))
-                    .route("/me", web::get().to(services::user_get_profile))
-                    .route(
-                        "/authorizations",
-                        web::get().to(services::user_list_authorizations),
-                    )
-                    .route(
-                        "/authorizations/{client_id}",
-                        web::delete().to(services::user_revoke_authorization),
-                    ),
-            )
-            // 管理员 API（需要管理员权限）
-            .service(
-                web::scope("/api/admin")
-                    .wrap(app_middleware::AdminOnly::new(
-                        ctx.jwt_manager.clone(),
-                        ctx.cache.clone(),
-                        storage.clone(),
-                    ))
-                    // 配置管理
-                    .route(
-                        "/settings/registration",
-                        web::get().to(services::settings_get_registration_config),
-                    )
-                    .route(
-                        "/settings/registration",
-                        web::put().to(services::settings_update_registration_config),
-                    )
-                    .route(
-                        "/settings/audit-logs",
-                        web::get().to(services::settings_get_audit_logs),
-                    )
-                    // 认证策略配置
-                    .route(
-                        "/settings/auth",
-                        web::get().to(services::settings_get_auth_policy_config),
-                    )
-                    .route(
-                        "/settings/auth",
-                        web::put().to(services::settings_update_auth_policy_config),
-                    )
-                    // 缓存策略配置
-                    .route(
-                        "/settings/cache",
-                        web::get().to(services::settings_get_cache_policy_config),
-                    )
-                    .route(
-                        "/settings/cache",
-                        web::put().to(services::settings_update_cache_policy_config),
-                    )
-                    // 邀请码管理
-                    .route("/invites", web::post().to(services::invite_create))
-                    .route("/invites", web::get().to(services::invite_list))
-                    .route("/invites/stats", web::get().to(services::invite_get_stats))
-                    .route("/invites/{code}", web::delete().to(services::invite_revoke))
-                    // 用户管理
-                    .route("/users", web::get().to(services::admin_list_users))
-                    .route(
-                        "/users/stats",
-                        web::get().to(services::admin_get_user_stats),
-                    )
-                    .route("/users/{id}", web::get().to(services::admin_get_user))
-                    .route(
-                        "/users/{id}/role",
-                        web::patch().to(services::admin_update_role),
-                    )
-                    .route(
-                        "/users/{id}/status",
-                        web::patch().to(services::admin_update_status),
-                    )
-                    .route(
-                        "/users/{id}/reset-password",
-                        web::post().to(services::admin_reset_password),
-                    )
-                    .route("/users/{id}", web::delete().to(services::admin_delete_user)),
-            )
+            // 路由
+            .configure(|cfg| {
+                configure_routes(
+                    cfg,
+                    ctx.jwt_manager.clone(),
+                    ctx.cache.clone(),
+                    storage.clone(),
+                )
+            })
     })
     .bind(&bind_addr)?
     .run()
     .await
 }
+
+/// 注册全部路由（服务器和接口测试共用）
+pub fn configure_routes(
+    cfg: &mut web::ServiceConfig,
+    jwt_manager: Arc<JwtManager>,
+    cache: Arc<CompositeCache>,
+    storage: Arc<SeaOrmBackend>,
+) {
+    cfg
+        // 健康检查端点（无需认证）
+        .service(
+            web::scope("/health")
+                .route("", web::get().to(services::health_check))
+                .route("/ready", web::get().to(services::readiness))
+                .route("/live", web::get().to(services::liveness)),
+        )
+        // 认证 API（无需认证）
+        .service(
+            web::scope("/api/auth")
+                .route("/register", web::post().to(services::register))
+                .route("/login", web::post().to(services::login))
+                .route("/refresh", web::post().to(services::refresh))
+                .route("/verify-invite", web::post().to(services::invite_verify)),
+        )
+        // OAuth2 授权端点
+        .service(
+            web::scope("/oauth")
+                .route("/authorize", web::get().to(services::oauth_authorize))
+                .route(
+                    "/authorize/consent",
+                    web::post().to(services::oauth_consent),
+                )
+                .route(
+                    "/authorize/login",
+                    web::post().to(services::oauth_reauthenticate),
+                )
+                .route("/par", web::post().to(services::oauth_par))
+                .route("/token", web::post().to(services::oauth_token))
+                .route("/introspect", web::post().to(services::oauth_introspect))
+                .route("/revoke", web::post().to(services::oauth_revoke))
+                .route("/logout", web::get().to(services::oauth_logout))
+                .route("/logout", web::post().to(services::oauth_logout_form))
+                // 动态客户端注册（RFC 7591 / RFC 7592）
+                .route("/register", web::post().to(services::oauth_register))
+                .route(
+                    "/register/{client_id}",
+                    web::get().to(services::oauth_get_registration),
+                )
+                .route(
+                    "/register/{client_id}",
+                    web::put().to(services::oauth_update_registration),
+                )
+                .route(
+                    "/register/{client_id}",
+                    web::delete().to(services::oauth_delete_registration),
+                )
+                .route(
+                    "/device_authorization",
+                    web::post().to(services::device_authorize),
+                )
+                .route("/device", web::get().to(services::device_lookup))
+                .route("/device", web::post().to(services::device_verify))
+                .route(
+                    "/userinfo",
+                    web::get()
+                        .to(services::oidc_userinfo)
+                        .wrap(app_middleware::JwtAuth::new(
+                            jwt_manager.clone(),
+                            cache.clone(),
+                        )),
+                ),
+        )
+        // OIDC Discovery 端点（无需认证）
+        .service(
+            web::scope("/.well-known")
+                .route(
+                    "/openid-configuration",
+                    web::get().to(services::oidc_discovery),
+                )
+                .route("/jwks.json", web::get().to(services::oidc_jwks)),
+        )
+        // 用户 API（需要 JWT 认证）
+        .service(
+            web::scope("/api/user")
+                .wrap(app_middleware::JwtAuth::new(
+                    jwt_manager.clone(),
+                    cache.clone(),
+                ))
+                .route("/me", web::get().to(services::user_get_profile))
+                .route("/me", web::put().to(services::user_update_profile))
+                .route(
+                    "/authorizations",
+                    web::get().to(services::user_list_authorizations),
+                )
+                .route(
+                    "/authorizations/{client_id}",
+                    web::delete().to(services::user_revoke_authorization),
+                ),
+        )
+        // 管理员 API（需要管理员权限）
+        .service(
+            web::scope("/api/admin")
+                .wrap(app_middleware::AdminOnly::new(
+                    jwt_manager.clone(),
+                    cache.clone(),
+                    storage.clone(),
+                ))
+                // 配置管理
+                .route(
+                    "/settings/registration",
+                    web::get().to(services::settings_get_registration_config),
+                )
+                .route(
+                    "/settings/registration",
+                    web::put().to(services::settings_update_registration_config),
+                )
+                .route(
+                    "/settings/audit-logs",
+                    web::get().to(services::settings_get_audit_logs),
+                )
+                // 认证策略配置
+                .route(
+                    "/settings/auth",
+                    web::get().to(services::settings_get_auth_policy_config),
+                )
+                .route(
+                    "/settings/auth",
+                    web::put().to(services::settings_update_auth_policy_config),
+                )
+                // 缓存策略配置
+                .route(
+                    "/settings/cache",
+                    web::get().to(services::settings_get_cache_policy_config),
+                )
+                .route(
+                    "/settings/cache",
+                    web::put().to(services::settings_update_cache_policy_config),
+                )
+                // 动态客户端注册配置
+                .route(
+                    "/settings/client-registration",
+                    web::get().to(services::settings_get_client_registration_config),
+                )
+                .route(
+                    "/settings/client-registration",
+                    web::put().to(services::settings_update_client_registration_config),
+                )
+                .route(
+                    "/settings/client-registration/token",
+                    web::post().to(services::settings_rotate_initial_access_token),
+                )
+                // 邀请码管理
+                .route("/invites", web::post().to(services::invite_create))
+                .route("/invites", web::get().to(services::invite_list))
+                .route("/invites/stats", web::get().to(services::invite_get_stats))
+                .route("/invites/{code}", web::delete().to(services::invite_revoke))
+                // 用户管理
+                .route("/users", web::get().to(services::admin_list_users))
+                .route(
+                    "/users/stats",
+                    web::get().to(services::admin_get_user_stats),
+                )
+                .route("/users/{id}", web::get().to(services::admin_get_user))
+                .route(
+                    "/users/{id}/role",
+                    web::patch().to(services::admin_update_role),
+                )
+                .route(
+                    "/users/{id}/status",
+                    web::patch().to(services::admin_update_status),
+                )
+                .route(
+                    "/users/{id}/reset-password",
+                    web::post().to(services::admin_reset_password),
+                )
+                .route("/users/{id}", web::delete().to(services::admin_delete_user))
+                // 签名密钥管理
+                .route("/keys", web::get().to(services::admin_list_keys))
+                .route("/keys/rotate", web::post().to(services::admin_rotate_keys))
+                // 客户端管理
+                .route("/clients", web::get().to(services::admin_list_clients))
+                .route("/clients", web::post().to(services::admin_create_client))
+                .route(
+                    "/clients/{client_id}",
+                    web::get().to(services::admin_get_client),
+                )
+                .route(
+                    "/clients/{client_id}",
+                    web::put().to(services::admin_update_client),
+                )
+                .route(
+                    "/clients/{client_id}",
+                    web::delete().to(services::admin_delete_client),
+                )
+                .route(
+                    "/clients/{client_id}/rotate-secret",
+                    web::post().to(services::admin_rotate_client_secret),
+                ),
+        );
+}
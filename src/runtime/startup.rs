@@ -1,11 +1,16 @@
+use jsonwebtoken::Algorithm;
 use sea_orm::DatabaseConnection;
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 use tracing_appender::non_blocking::WorkerGuard;
 
+use crate::api::services::key_service::{init_signing_keys, spawn_signing_key_reload};
 use crate::cache::{CompositeCache, MemoryCache, RedisCache};
-use crate::config::{CacheConfig, RedisConfig, get_config};
+use crate::config::{AuthConfig, CacheConfig, RedisConfig, get_config};
 use crate::errors::AppError;
-use crate::security::JwtManager;
+use crate::security::keys::parse_algorithm;
+use crate::security::{ClientSecretManager, JwtManager, SigningKey};
 use crate::storage::{SeaOrmBackend, connect, run_migrations};
 
 /// 服务器启动上下文
@@ -47,14 +52,36 @@ pub async fn prepare_server() -> Result<StartupContext, AppError> {
     init_runtime_config(&db, config).await?;
     tracing::info!("Runtime configurations initialized");
 
+    // 5.6 哈希尚未迁移的明文客户端密钥
+    hash_legacy_client_secrets(&db).await?;
+
     // 6. 初始化缓存
     tracing::info!("Initializing cache system...");
     let cache = init_cache(&config.cache, &config.redis).await?;
     tracing::info!("Cache initialized");
 
-    // 7. 初始化 JWT 管理器
+    // 7. 初始化 JWT 管理器（非对称密钥由数据库中的密钥库管理，支持轮换）
     let jwt_manager = Arc::new(JwtManager::new(config.auth.jwt_secret.clone()));
-    tracing::info!("JWT manager initialized");
+    if parse_algorithm(&config.auth.signing_algorithm)? != Algorithm::HS256 {
+        let backend = SeaOrmBackend::new(Arc::new(db.clone()));
+        init_signing_keys(
+            &backend,
+            &jwt_manager,
+            &config.auth.signing_key_encryption_key,
+            || load_initial_signing_key(&config.auth),
+        )
+        .await?;
+        spawn_signing_key_reload(
+            backend,
+            jwt_manager.clone(),
+            config.auth.signing_key_encryption_key.clone(),
+        );
+    }
+    tracing::info!(
+        "JWT manager initialized ({}, kid: {:?})",
+        jwt_manager.algorithm(),
+        jwt_manager.active_kid()
+    );
 
     // 8. 检查并显示组件状态
     check_components_status();
@@ -95,6 +122,25 @@ async fn init_cache(
     ))
 }
 
+/// 密钥库为空时的初始签名密钥：优先导入已有的 PEM 文件，否则生成新密钥
+fn load_initial_signing_key(auth_config: &AuthConfig) -> Result<SigningKey, AppError> {
+    let algorithm = parse_algorithm(&auth_config.signing_algorithm)?;
+
+    let path = Path::new(&auth_config.signing_key_path);
+    if path.exists() {
+        let pem = fs::read_to_string(path)
+            .map_err(|e| AppError::Config(format!("无法读取签名密钥 {}: {}", path.display(), e)))?;
+        tracing::info!("Signing key imported from {}", path.display());
+        return SigningKey::from_pem(algorithm, &pem);
+    }
+
+    tracing::info!(
+        "Generated new {} signing key",
+        auth_config.signing_algorithm
+    );
+    SigningKey::generate(algorithm)
+}
+
 /// 检查并显示组件状态
 fn check_components_status() {
     let config = get_config();
@@ -150,10 +196,34 @@ fn check_components_status() {
     tracing::info!("  - /oauth/token        (Token 端点)");
     tracing::info!("  - /oauth/userinfo     (用户信息)");
     tracing::info!("  - /.well-known/openid-configuration (Discovery)");
+    tracing::info!(
+        "  - /.well-known/jwks.json (签名公钥, {})",
+        config.auth.signing_algorithm
+    );
 
     tracing::info!("==========================================");
 }
 
+/// 将明文存储的客户端密钥替换为 argon2 哈希
+async fn hash_legacy_client_secrets(db: &DatabaseConnection) -> Result<(), AppError> {
+    let backend = SeaOrmBackend::new(Arc::new(db.clone()));
+
+    for client in backend.list_clients().await? {
+        if client.client_secret.is_empty() || ClientSecretManager::is_hashed(&client.client_secret)
+        {
+            continue;
+        }
+
+        let secret_hash = ClientSecretManager::hash_secret(&client.client_secret)?;
+        backend
+            .update_client_secret(&client.client_id, &secret_hash)
+            .await?;
+        tracing::info!("Client secret of '{}' migrated to hash", client.client_id);
+    }
+
+    Ok(())
+}
+
 /// 初始化运行时配置（如果数据库中不存在，则从 TOML 配置写入）
 async fn init_runtime_config(
     db: &DatabaseConnection,
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// profile scope 对应的 claims（OpenID Connect Core 5.4，只包含用户模型中存在的字段）
+pub const PROFILE_CLAIMS: [&str; 8] = [
+    "name",
+    "given_name",
+    "family_name",
+    "preferred_username",
+    "picture",
+    "locale",
+    "zoneinfo",
+    "updated_at",
+];
+
+/// email scope 对应的 claims
+pub const EMAIL_CLAIMS: [&str; 2] = ["email", "email_verified"];
+
+/// phone scope 对应的 claims
+pub const PHONE_CLAIMS: [&str; 2] = ["phone_number", "phone_number_verified"];
+
+/// address scope 对应的 claims
+pub const ADDRESS_CLAIMS: [&str; 1] = ["address"];
+
+/// scope 授予的用户 claims
+pub fn scope_claims(scope: &str) -> &'static [&'static str] {
+    match scope {
+        "profile" => &PROFILE_CLAIMS,
+        "email" => &EMAIL_CLAIMS,
+        "phone" => &PHONE_CLAIMS,
+        "address" => &ADDRESS_CLAIMS,
+        _ => &[],
+    }
+}
+
+/// 覆盖指定 claim 的 scope
+pub fn claim_scope(claim: &str) -> Option<&'static str> {
+    ["profile", "email", "phone", "address"]
+        .into_iter()
+        .find(|scope| scope_claims(scope).contains(&claim))
+}
+
+/// 可以返回的全部用户 claims
+pub fn supported_user_claims() -> impl Iterator<Item = &'static str> {
+    ["profile", "email", "phone", "address"]
+        .into_iter()
+        .flat_map(scope_claims)
+        .copied()
+}
+
+/// 授权请求中的 claims 参数（OpenID Connect Core 5.5）
+///
+/// 每个成员为 claim 名称到 null 或 `{"essential", "value", "values"}` 的映射。
+/// 这里只使用 claim 名称，essential / value / values 不影响返回结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClaimsRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub userinfo: Option<Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<Map<String, Value>>,
+}
+
+impl ClaimsRequest {
+    /// 解析 claims 参数（JSON 对象），格式错误时返回 None
+    pub fn parse(claims: &str) -> Option<Self> {
+        let request: Self = serde_json::from_str(claims).ok()?;
+        let valid = [&request.userinfo, &request.id_token]
+            .into_iter()
+            .flatten()
+            .flat_map(|member| member.values())
+            .all(|value| value.is_null() || value.is_object());
+        valid.then_some(request)
+    }
+
+    /// 请求的用户 claims（保持固定顺序，不支持的名称被忽略）
+    pub fn requested_claims(&self) -> Vec<&'static str> {
+        supported_user_claims()
+            .filter(|claim| {
+                [&self.userinfo, &self.id_token]
+                    .into_iter()
+                    .flatten()
+                    .any(|member| member.contains_key(*claim))
+            })
+            .collect()
+    }
+
+    /// 覆盖请求的用户 claims 的 scope
+    ///
+    /// 请求的 claims 需要与这些 scope 一样经过客户端配置和用户同意
+    pub fn scopes(&self) -> Vec<&'static str> {
+        let mut scopes = Vec::new();
+        for scope in self.requested_claims().into_iter().filter_map(claim_scope) {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        scopes
+    }
+}
+
+/// 按授予的 scope 和 claims 参数中请求的名称确定返回的用户 claims（保持固定顺序）
+///
+/// claims 参数只能请求服务器支持的用户 claims，其他名称被忽略。请求的 claims 在授权时
+/// 已按 `ClaimsRequest::scopes` 检查客户端配置和用户同意
+pub fn released_claims(
+    scopes: &[String],
+    requested: Option<&Map<String, Value>>,
+) -> Vec<&'static str> {
+    supported_user_claims()
+        .filter(|claim| {
+            scopes
+                .iter()
+                .any(|scope| scope_claims(scope).contains(claim))
+                || requested.is_some_and(|requested| requested.contains_key(*claim))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|scope| scope.to_string()).collect()
+    }
+
+    #[test]
+    fn test_released_claims_by_scope() {
+        assert!(released_claims(&scopes(&["openid"]), None).is_empty());
+        assert_eq!(
+            released_claims(&scopes(&["openid", "email"]), None),
+            vec!["email", "email_verified"]
+        );
+        assert_eq!(
+            released_claims(&scopes(&["phone", "address"]), None),
+            vec!["phone_number", "phone_number_verified", "address"]
+        );
+        assert_eq!(
+            released_claims(&scopes(&["profile"]), None).len(),
+            PROFILE_CLAIMS.len()
+        );
+    }
+
+    #[test]
+    fn test_claims_request() {
+        let request = ClaimsRequest::parse(
+            r#"{"userinfo": {"email": {"essential": true}, "unknown": null},
+                "id_token": {"picture": null}}"#,
+        )
+        .unwrap();
+
+        // 请求的 claims 与 scope 授予的 claims 合并，不支持的名称被忽略
+        assert_eq!(
+            released_claims(&scopes(&["openid"]), request.userinfo.as_ref()),
+            vec!["email"]
+        );
+        assert_eq!(
+            released_claims(&scopes(&["openid", "phone"]), request.id_token.as_ref()),
+            vec!["picture", "phone_number", "phone_number_verified"]
+        );
+
+        // 请求的 claims 按覆盖它们的 scope 检查客户端配置和用户同意
+        assert_eq!(request.requested_claims(), vec!["picture", "email"]);
+        assert_eq!(request.scopes(), vec!["profile", "email"]);
+        assert!(
+            ClaimsRequest::parse(r#"{"userinfo": {"unknown": null}}"#)
+                .unwrap()
+                .scopes()
+                .is_empty()
+        );
+
+        // 格式错误
+        assert!(ClaimsRequest::parse("not json").is_none());
+        assert!(ClaimsRequest::parse(r#"["email"]"#).is_none());
+        assert!(ClaimsRequest::parse(r#"{"userinfo": {"email": true}}"#).is_none());
+        assert!(ClaimsRequest::parse(r#"{"userinfo": ["email"]}"#).is_none());
+    }
+}
@@ -0,0 +1,163 @@
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+
+use crate::errors::AppError;
+use crate::security::jwt::VALIDATION_LEEWAY;
+
+/// RFC 7523 客户端断言类型
+pub const JWT_BEARER_ASSERTION_TYPE: &str =
+    "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// 客户端断言允许的签名算法（只接受非对称算法）
+pub const SUPPORTED_ASSERTION_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// 客户端断言中的 claims
+#[derive(Debug, Deserialize)]
+pub struct AssertionClaims {
+    pub iss: String,
+    pub sub: String,
+    pub exp: i64,
+    pub jti: String,
+}
+
+/// 读取断言中的 sub（即 client_id），不做任何验证
+pub fn assertion_subject(assertion: &str) -> Result<String, AppError> {
+    #[derive(Deserialize)]
+    struct Subject {
+        sub: String,
+    }
+
+    jsonwebtoken::dangerous::insecure_decode::<Subject>(assertion)
+        .map(|data| data.claims.sub)
+        .map_err(|_| AppError::InvalidClient)
+}
+
+/// 按 kid 从客户端 JWKS 中选择公钥（未指定 kid 时 JWKS 必须只有一个密钥）
+pub(crate) fn client_public_key(jwks: &str, kid: Option<&str>) -> Result<DecodingKey, AppError> {
+    let jwks: JwkSet = serde_json::from_str(jwks)
+        .map_err(|e| AppError::BadRequest(format!("Invalid client JWKS: {}", e)))?;
+    let jwk = match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or(AppError::InvalidClient)?;
+
+    // 对称密钥不能作为客户端公钥
+    if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+        return Err(AppError::InvalidClient);
+    }
+    DecodingKey::from_jwk(jwk).map_err(|_| AppError::InvalidClient)
+}
+
+/// 使用客户端 JWKS 验证断言（签名、iss、sub、aud、exp）
+///
+/// `audiences` 为授权服务器可接受的 aud（issuer 或端点地址）
+pub fn verify_assertion(
+    assertion: &str,
+    jwks: &str,
+    client_id: &str,
+    audiences: &[String],
+) -> Result<AssertionClaims, AppError> {
+    // 1. 按 header 中的 alg / kid 选择客户端公钥
+    let header = decode_header(assertion).map_err(|_| AppError::InvalidClient)?;
+    if !SUPPORTED_ASSERTION_ALGORITHMS.contains(&header.alg) {
+        return Err(AppError::InvalidClient);
+    }
+
+    let key = client_public_key(jwks, header.kid.as_deref())?;
+
+    // 2. 验证签名、iss、aud、exp（iss 和 sub 都必须是 client_id）
+    let mut validation = Validation::new(header.alg);
+    validation.leeway = VALIDATION_LEEWAY;
+    validation.set_issuer(&[client_id]);
+    validation.set_audience(audiences);
+    validation.set_required_spec_claims(&["iss", "sub", "aud", "exp"]);
+
+    let claims = decode::<AssertionClaims>(assertion, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|_| AppError::InvalidClient)?;
+
+    if claims.sub != client_id {
+        return Err(AppError::InvalidClient);
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SigningKey;
+    use jsonwebtoken::{Header, encode};
+    use serde_json::json;
+
+    fn sign_assertion(key: &SigningKey, claims: serde_json::Value) -> String {
+        let mut header = Header::new(key.algorithm());
+        header.kid = key.kid().map(str::to_string);
+        encode(&header, &claims, key.encoding_key()).unwrap()
+    }
+
+    #[test]
+    fn test_verify_assertion() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        let jwks = json!({ "keys": [key.public_jwk().unwrap()] }).to_string();
+        let audiences = vec!["https://auth.example.com".to_string()];
+        let exp = chrono::Utc::now().timestamp() + 60;
+
+        let assertion = sign_assertion(
+            &key,
+            json!({
+                "iss": "client-a",
+                "sub": "client-a",
+                "aud": "https://auth.example.com",
+                "exp": exp,
+                "jti": "jti-1",
+            }),
+        );
+        assert_eq!(assertion_subject(&assertion).unwrap(), "client-a");
+        let claims = verify_assertion(&assertion, &jwks, "client-a", &audiences).unwrap();
+        assert_eq!(claims.jti, "jti-1");
+
+        // client_id 不匹配
+        assert!(verify_assertion(&assertion, &jwks, "client-b", &audiences).is_err());
+
+        // aud 不匹配
+        let wrong_aud = sign_assertion(
+            &key,
+            json!({
+                "iss": "client-a",
+                "sub": "client-a",
+                "aud": "https://other.example.com",
+                "exp": exp,
+                "jti": "jti-2",
+            }),
+        );
+        assert!(verify_assertion(&wrong_aud, &jwks, "client-a", &audiences).is_err());
+
+        // 使用其他密钥签名
+        let other = SigningKey::generate(Algorithm::ES256).unwrap();
+        let forged = sign_assertion(
+            &other,
+            json!({
+                "iss": "client-a",
+                "sub": "client-a",
+                "aud": "https://auth.example.com",
+                "exp": exp,
+                "jti": "jti-3",
+            }),
+        );
+        assert!(verify_assertion(&forged, &jwks, "client-a", &audiences).is_err());
+    }
+}
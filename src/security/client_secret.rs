@@ -0,0 +1,63 @@
+use crate::errors::AppError;
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+use aws_lc_rs::constant_time::verify_slices_are_equal;
+
+pub struct ClientSecretManager;
+
+impl ClientSecretManager {
+    /// 对客户端密钥进行哈希
+    pub fn hash_secret(secret: &str) -> Result<String, AppError> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        Argon2::default()
+            .hash_password(secret.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::Internal(format!("Client secret hash failed: {}", e)))
+    }
+
+    /// 验证客户端密钥是否匹配（兼容尚未迁移的明文密钥，使用常量时间比较）
+    pub fn verify_secret(secret: &str, stored: &str) -> bool {
+        match PasswordHash::new(stored) {
+            Ok(hash) => Argon2::default()
+                .verify_password(secret.as_bytes(), &hash)
+                .is_ok(),
+            Err(_) => verify_slices_are_equal(secret.as_bytes(), stored.as_bytes()).is_ok(),
+        }
+    }
+
+    /// 存储的密钥是否已经过哈希
+    pub fn is_hashed(stored: &str) -> bool {
+        PasswordHash::new(stored).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_secret_hash_and_verify() {
+        let secret = "client-secret-123";
+        let hash = ClientSecretManager::hash_secret(secret).unwrap();
+
+        assert!(ClientSecretManager::is_hashed(&hash));
+        assert!(ClientSecretManager::verify_secret(secret, &hash));
+        assert!(!ClientSecretManager::verify_secret("wrong-secret", &hash));
+    }
+
+    #[test]
+    fn test_plaintext_client_secret() {
+        assert!(!ClientSecretManager::is_hashed("test_secret_456"));
+        assert!(ClientSecretManager::verify_secret(
+            "test_secret_456",
+            "test_secret_456"
+        ));
+        assert!(!ClientSecretManager::verify_secret(
+            "test_secret_45",
+            "test_secret_456"
+        ));
+    }
+}
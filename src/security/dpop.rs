@@ -0,0 +1,255 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk as PublicJwk};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::errors::AppError;
+use crate::security::client_assertion::SUPPORTED_ASSERTION_ALGORITHMS;
+use crate::security::jwt::VALIDATION_LEEWAY;
+use crate::security::keys::Jwk;
+
+/// 携带 DPoP 证明的请求头
+pub const DPOP_HEADER: &str = "DPoP";
+
+/// 服务器下发 nonce 的响应头（RFC 9449 8）
+pub const DPOP_NONCE_HEADER: &str = "DPoP-Nonce";
+
+/// DPoP 证明 header 中的 typ（RFC 9449 4.2）
+pub const DPOP_PROOF_TYPE: &str = "dpop+jwt";
+
+/// 绑定了 DPoP 密钥的 token 的 token_type（RFC 9449 5）
+pub const DPOP_TOKEN_TYPE: &str = "DPoP";
+
+/// DPoP 证明允许的签名算法（只接受非对称算法）
+pub const SUPPORTED_DPOP_ALGORITHMS: &[Algorithm] = SUPPORTED_ASSERTION_ALGORITHMS;
+
+/// DPoP 证明的有效期（秒，从 iat 开始计算）
+pub const DPOP_PROOF_LIFETIME: i64 = 300;
+
+/// DPoP 证明中的 claims
+#[derive(Debug, Deserialize)]
+pub struct DpopClaims {
+    pub jti: String,
+    pub htm: String,
+    pub htu: String,
+    pub iat: i64,
+    /// access token 的哈希（访问资源时必需）
+    #[serde(default)]
+    pub ath: Option<String>,
+    /// 服务器下发的 nonce
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+/// 通过验证的 DPoP 证明
+#[derive(Debug)]
+pub struct DpopProof {
+    /// 证明公钥的 JWK Thumbprint（token 的 cnf.jkt）
+    pub jkt: String,
+    pub claims: DpopClaims,
+}
+
+/// 签发的 token 类型（绑定 DPoP 密钥时为 DPoP）
+pub fn token_type(jkt: Option<&str>) -> &'static str {
+    if jkt.is_some() {
+        DPOP_TOKEN_TYPE
+    } else {
+        "Bearer"
+    }
+}
+
+/// 计算 DPoP 证明中的 ath（access token 的 SHA-256，base64url 编码）
+pub fn access_token_hash(access_token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(access_token.as_bytes()))
+}
+
+/// 计算 DPoP 公钥的 JWK Thumbprint（RFC 7638）
+pub fn jwk_thumbprint(jwk: &PublicJwk) -> Result<String, AppError> {
+    let value = serde_json::to_value(jwk)
+        .map_err(|_| AppError::InvalidDpopProof("Invalid DPoP public key".into()))?;
+    let field = |name: &str| value.get(name).and_then(Value::as_str).map(str::to_string);
+
+    let jwk = Jwk {
+        kty: field("kty")
+            .ok_or_else(|| AppError::InvalidDpopProof("Invalid DPoP public key".into()))?,
+        kid: String::new(),
+        r#use: String::new(),
+        alg: String::new(),
+        n: field("n"),
+        e: field("e"),
+        crv: field("crv"),
+        x: field("x"),
+        y: field("y"),
+    };
+    Ok(jwk.thumbprint())
+}
+
+/// 验证 DPoP 证明（RFC 9449 4.3）：typ、alg、header 中的公钥及签名、htm、htu、iat
+///
+/// `htu` 为请求的地址（不含查询参数）；提供 `access_token` 时还要求 ath 与之匹配。
+/// jti 防重放和 nonce 由调用方检查
+pub fn verify_proof(
+    proof: &str,
+    htm: &str,
+    htu: &str,
+    access_token: Option<&str>,
+) -> Result<DpopProof, AppError> {
+    let invalid = |message: &str| AppError::InvalidDpopProof(message.to_string());
+
+    // 1. header 必须声明 dpop+jwt，使用非对称算法并携带公钥
+    let header = decode_header(proof).map_err(|_| invalid("Malformed DPoP proof"))?;
+    if header.typ.as_deref() != Some(DPOP_PROOF_TYPE) {
+        return Err(invalid("Invalid DPoP proof type"));
+    }
+    if !SUPPORTED_DPOP_ALGORITHMS.contains(&header.alg) {
+        return Err(invalid("Unsupported DPoP proof signing algorithm"));
+    }
+    let jwk = header.jwk.as_ref().ok_or_else(|| invalid("Missing jwk"))?;
+    if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+        return Err(invalid("Symmetric keys cannot be used for DPoP"));
+    }
+    let key = DecodingKey::from_jwk(jwk).map_err(|_| invalid("Invalid DPoP public key"))?;
+
+    // 2. 验证签名（证明没有 exp，有效期按 iat 计算）
+    let mut validation = Validation::new(header.alg);
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+
+    let claims = decode::<DpopClaims>(proof, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| AppError::InvalidDpopProof(e.to_string()))?;
+
+    // 3. 证明必须针对当前请求
+    if !claims.htm.eq_ignore_ascii_case(htm) {
+        return Err(invalid("htm does not match the request method"));
+    }
+    let proof_htu = claims.htu.split(['?', '#']).next().unwrap_or_default();
+    if proof_htu != htu {
+        return Err(invalid("htu does not match the request URI"));
+    }
+
+    // 4. 只接受最近签发的证明
+    let now = chrono::Utc::now().timestamp();
+    if claims.iat > now + VALIDATION_LEEWAY as i64 || claims.iat < now - DPOP_PROOF_LIFETIME {
+        return Err(invalid("DPoP proof is expired or not yet valid"));
+    }
+
+    // 5. 访问资源时证明必须绑定所提交的 access token
+    if let Some(access_token) = access_token
+        && claims.ath.as_deref() != Some(access_token_hash(access_token).as_str())
+    {
+        return Err(invalid("ath does not match the access token"));
+    }
+
+    Ok(DpopProof {
+        jkt: jwk_thumbprint(jwk)?,
+        claims,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SigningKey;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+
+    const HTU: &str = "https://auth.example.com/oauth/token";
+
+    fn sign_proof(key: &SigningKey, claims: serde_json::Value) -> String {
+        let mut header = Header::new(key.algorithm());
+        header.typ = Some(DPOP_PROOF_TYPE.to_string());
+        header.jwk = Some(serde_json::from_value(json!(key.public_jwk().unwrap())).unwrap());
+        encode(&header, &claims, key.encoding_key()).unwrap()
+    }
+
+    fn proof_claims(htm: &str, htu: &str) -> serde_json::Value {
+        json!({
+            "jti": uuid::Uuid::new_v4().to_string(),
+            "htm": htm,
+            "htu": htu,
+            "iat": chrono::Utc::now().timestamp(),
+        })
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        for algorithm in [Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA] {
+            let key = SigningKey::generate(algorithm).unwrap();
+            let proof = sign_proof(&key, proof_claims("POST", HTU));
+
+            // jkt 与密钥的 JWK Thumbprint 一致
+            let verified = verify_proof(&proof, "POST", HTU, None).unwrap();
+            assert_eq!(Some(verified.jkt.as_str()), key.kid());
+        }
+    }
+
+    #[test]
+    fn test_proof_must_match_request() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        let proof = sign_proof(&key, proof_claims("POST", &format!("{}?x=1", HTU)));
+
+        // htu 比较时忽略查询参数
+        assert!(verify_proof(&proof, "POST", HTU, None).is_ok());
+        assert!(verify_proof(&proof, "GET", HTU, None).is_err());
+        assert!(
+            verify_proof(
+                &proof,
+                "POST",
+                "https://auth.example.com/oauth/userinfo",
+                None
+            )
+            .is_err()
+        );
+
+        // 过期的证明
+        let mut claims = proof_claims("POST", HTU);
+        claims["iat"] = json!(chrono::Utc::now().timestamp() - DPOP_PROOF_LIFETIME - 1);
+        assert!(verify_proof(&sign_proof(&key, claims), "POST", HTU, None).is_err());
+    }
+
+    #[test]
+    fn test_proof_access_token_hash() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        let userinfo = "https://auth.example.com/oauth/userinfo";
+
+        let mut claims = proof_claims("GET", userinfo);
+        claims["ath"] = json!(access_token_hash("access-token"));
+        let proof = sign_proof(&key, claims);
+
+        assert!(verify_proof(&proof, "GET", userinfo, Some("access-token")).is_ok());
+        assert!(verify_proof(&proof, "GET", userinfo, Some("other-token")).is_err());
+
+        // 缺少 ath
+        let proof = sign_proof(&key, proof_claims("GET", userinfo));
+        assert!(verify_proof(&proof, "GET", userinfo, Some("access-token")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_proofs() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        let claims = proof_claims("POST", HTU);
+
+        // typ 不是 dpop+jwt
+        let mut header = Header::new(key.algorithm());
+        header.jwk = Some(serde_json::from_value(json!(key.public_jwk().unwrap())).unwrap());
+        let untyped = encode(&header, &claims, key.encoding_key()).unwrap();
+        assert!(verify_proof(&untyped, "POST", HTU, None).is_err());
+
+        // header 中的公钥与签名密钥不一致
+        let other = SigningKey::generate(Algorithm::ES256).unwrap();
+        header.typ = Some(DPOP_PROOF_TYPE.to_string());
+        let forged = encode(&header, &claims, other.encoding_key()).unwrap();
+        assert!(verify_proof(&forged, "POST", HTU, None).is_err());
+
+        // 不接受对称算法
+        let mut header = Header::new(Algorithm::HS256);
+        header.typ = Some(DPOP_PROOF_TYPE.to_string());
+        let hmac = encode(&header, &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+        assert!(verify_proof(&hmac, "POST", HTU, None).is_err());
+    }
+}
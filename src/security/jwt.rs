@@ -1,80 +1,350 @@
 use crate::errors::AppError;
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use crate::security::keys::{Jwk, SigningKey, algorithm_name};
+use jsonwebtoken::{Algorithm, DecodingKey, Header, Validation, decode, decode_header, encode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+/// refresh token 的 scope（refresh token 不能作为 access token 使用）
+pub const REFRESH_SCOPE: &str = "refresh";
+
+/// client_credentials 签发的 Token 使用的角色（sub 为 client_id）
+pub const CLIENT_ROLE: &str = "client";
+
+/// 验证 exp 时允许的时钟偏差（秒）
+pub const VALIDATION_LEEWAY: u64 = 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
-    pub sub: String,                // user_id
+    pub sub: String,                // 用户标识（客户端 Token 为 client_id）
     pub exp: i64,                   // 过期时间戳
     pub iat: i64,                   // 签发时间戳
     pub scope: Option<Vec<String>>, // 权限范围（可选）
     pub role: String,               // 用户角色
+    #[serde(default)]
+    pub jti: String, // Token 唯一标识（保证同一秒内签发的 Token 互不相同）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>, // 目标服务（Token Exchange 签发的 Token）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>, // 代表用户行事的一方（Token Exchange 委托）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>, // 绑定的 DPoP 公钥（持有对应私钥才能使用）
+}
+
+/// Token 中的 act claim（RFC 8693 4.1）
+///
+/// 嵌套的 act 表示之前的委托方，最外层为当前行事的一方
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    pub sub: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Box<Actor>>,
+}
+
+/// Token 中的 cnf claim（RFC 9449 6.1）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Confirmation {
+    /// DPoP 公钥的 JWK Thumbprint
+    pub jkt: String,
+}
+
+impl Confirmation {
+    fn from_jkt(jkt: Option<&str>) -> Option<Self> {
+        jkt.map(|jkt| Self {
+            jkt: jkt.to_string(),
+        })
+    }
+}
+
+impl Claims {
+    /// 是否为客户端自身（而非用户）持有的 Token
+    pub fn is_client(&self) -> bool {
+        self.role == CLIENT_ROLE
+    }
+
+    /// 是否为 refresh token
+    pub fn is_refresh(&self) -> bool {
+        self.scope
+            .as_ref()
+            .is_some_and(|scopes| scopes.iter().any(|s| s == REFRESH_SCOPE))
+    }
+}
+
+/// 作为 id_token_hint 提交的 ID Token 中的 claims
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenHint {
+    pub sub: String,
+    pub aud: String,
+}
+
+/// 仍可用于验证的非当前密钥
+#[derive(Clone)]
+pub struct VerificationKey {
+    pub key: SigningKey,
+    /// 验证截止时间戳（退役密钥的重叠期结束后不再接受，None 表示不限制）
+    pub not_after: Option<i64>,
+}
+
+/// 签名密钥集合
+struct KeyRing {
+    active: SigningKey,
+    others: Vec<VerificationKey>,
+    /// 切换到非对称密钥前签发的 HS256 Token 的验证截止时间戳（None 表示不接受）
+    legacy_not_after: Option<i64>,
+}
+
+impl KeyRing {
+    /// 当前可用于验证的密钥（已过重叠期的退役密钥除外）
+    fn usable(&self, now: i64) -> impl Iterator<Item = &SigningKey> {
+        std::iter::once(&self.active).chain(
+            self.others
+                .iter()
+                .filter(move |other| other.not_after.is_none_or(|not_after| now <= not_after))
+                .map(|other| &other.key),
+        )
+    }
+
+    /// 是否仍接受没有 kid 的 HS256 Token
+    fn accepts_legacy(&self, now: i64) -> bool {
+        self.legacy_not_after
+            .is_some_and(|not_after| now <= not_after)
+    }
+
+    /// 按 alg / kid 查找验证密钥
+    fn find(&self, algorithm: Algorithm, kid: Option<&str>, now: i64) -> Option<&SigningKey> {
+        self.usable(now)
+            .find(|key| key.algorithm() == algorithm && key.kid() == kid)
+    }
 }
 
 pub struct JwtManager {
-    secret: String,
+    keys: RwLock<KeyRing>,
+    legacy_key: DecodingKey,
 }
 
 impl JwtManager {
+    /// 使用 HS256 共享密钥签名
     pub fn new(secret: String) -> Self {
-        Self { secret }
+        Self::with_signing_key(&secret, SigningKey::hmac(&secret))
+    }
+
+    /// 使用指定密钥签名
+    ///
+    /// 切换前用 HS256 共享密钥签发的 Token 默认不再接受，需要通过 `set_signing_keys` 设置截止时间
+    pub fn with_signing_key(secret: &str, signing_key: SigningKey) -> Self {
+        Self {
+            keys: RwLock::new(KeyRing {
+                active: signing_key,
+                others: Vec::new(),
+                legacy_not_after: None,
+            }),
+            legacy_key: DecodingKey::from_secret(secret.as_bytes()),
+        }
+    }
+
+    /// 替换签名密钥（密钥轮换后调用，others 中的密钥仍可验证并公开）
+    ///
+    /// `legacy_not_after` 为 HS256 共享密钥签发的 Token 的验证截止时间戳
+    pub fn set_signing_keys(
+        &self,
+        active: SigningKey,
+        others: Vec<VerificationKey>,
+        legacy_not_after: Option<i64>,
+    ) {
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        *keys = KeyRing {
+            active,
+            others,
+            legacy_not_after,
+        };
     }
 
-    /// 生成 JWT Token
+    fn keys(&self) -> RwLockReadGuard<'_, KeyRing> {
+        self.keys.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 签名算法名称
+    pub fn algorithm(&self) -> &'static str {
+        algorithm_name(self.signing_algorithm())
+    }
+
+    /// 当前签名密钥的算法
+    pub fn signing_algorithm(&self) -> Algorithm {
+        self.keys().active.algorithm()
+    }
+
+    /// 当前签名密钥的 kid（HS256 返回 None）
+    pub fn active_kid(&self) -> Option<String> {
+        self.keys().active.kid().map(str::to_string)
+    }
+
+    /// 可公开的签名公钥（JWKS：当前密钥、待启用密钥及重叠期内的退役密钥）
+    pub fn public_jwks(&self) -> Vec<Jwk> {
+        let now = chrono::Utc::now().timestamp();
+        self.keys()
+            .usable(now)
+            .filter_map(|key| key.public_jwk().cloned())
+            .collect()
+    }
+
+    /// 使用当前签名密钥签发任意 claims（header 中携带 kid）
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        self.encode_typed(claims, "JWT")
+    }
+
+    /// 使用当前签名密钥签发指定 typ 的 JWT（如 logout+jwt）
+    pub fn encode_typed<T: Serialize>(&self, claims: &T, typ: &str) -> Result<String, AppError> {
+        let keys = self.keys();
+        let mut header = Header::new(keys.active.algorithm());
+        header.typ = Some(typ.to_string());
+        header.kid = keys.active.kid().map(str::to_string);
+
+        encode(&header, claims, keys.active.encoding_key())
+            .map_err(|e| AppError::Internal(format!("JWT encode failed: {}", e)))
+    }
+
+    /// 生成以用户为主体的 JWT Token（`sub` 为客户端看到的用户标识，`jkt` 为绑定的 DPoP 公钥）
     pub fn generate_token(
         &self,
-        user_id: i64,
+        sub: &str,
         expire_in: i64,
         scope: Option<Vec<String>>,
         role: &str,
+        jkt: Option<&str>,
     ) -> Result<String, AppError> {
         let now = chrono::Utc::now().timestamp();
         let claims = Claims {
-            sub: user_id.to_string(),
+            sub: sub.to_string(),
             exp: now + expire_in,
             iat: now,
             scope,
             role: role.to_string(),
+            jti: uuid::Uuid::new_v4().to_string(),
+            aud: None,
+            act: None,
+            cnf: Confirmation::from_jkt(jkt),
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_bytes()),
-        )
-        .map_err(|e| AppError::Internal(format!("JWT encode failed: {}", e)))
+        self.encode(&claims)
+    }
+
+    /// 生成以客户端为主体的 JWT Token（client_credentials）
+    pub fn generate_client_token(
+        &self,
+        client_id: &str,
+        expire_in: i64,
+        scope: Option<Vec<String>>,
+        jkt: Option<&str>,
+    ) -> Result<String, AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: client_id.to_string(),
+            exp: now + expire_in,
+            iat: now,
+            scope,
+            role: CLIENT_ROLE.to_string(),
+            jti: uuid::Uuid::new_v4().to_string(),
+            aud: None,
+            act: None,
+            cnf: Confirmation::from_jkt(jkt),
+        };
+
+        self.encode(&claims)
+    }
+
+    /// 生成 Token Exchange 签发的 Token（主体和角色与 subject_token 相同）
+    pub fn generate_exchanged_token(
+        &self,
+        subject: &Claims,
+        expire_in: i64,
+        scope: Option<Vec<String>>,
+        aud: Option<String>,
+        act: Option<Actor>,
+        jkt: Option<&str>,
+    ) -> Result<String, AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: subject.sub.clone(),
+            exp: now + expire_in,
+            iat: now,
+            scope,
+            role: subject.role.clone(),
+            jti: uuid::Uuid::new_v4().to_string(),
+            aud,
+            act,
+            cnf: Confirmation::from_jkt(jkt),
+        };
+
+        self.encode(&claims)
     }
 
     /// 验证并解析 Token
+    ///
+    /// aud 由接收 Token 的下游服务验证，这里不做检查
     pub fn verify_token(&self, token: &str) -> Result<Claims, AppError> {
-        let mut validation = Validation::new(Algorithm::HS256);
-        validation.validate_exp = true;
+        self.decode_claims(token, |validation| {
+            validation.validate_exp = true;
+            validation.validate_aud = false;
+        })
+    }
 
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.secret.as_bytes()),
-            &validation,
-        )
-        .map(|data| data.claims)
-        .map_err(|e| match e.kind() {
-            jsonwebtoken::errors::ErrorKind::ExpiredSignature => AppError::TokenExpired,
-            _ => AppError::InvalidToken,
+    /// 验证本服务签发的 ID Token（用于登出请求的 id_token_hint）
+    ///
+    /// 用户登出时 ID Token 可能已经过期，因此不验证 exp（OpenID Connect RP-Initiated Logout 2）
+    pub fn verify_id_token_hint(&self, token: &str, issuer: &str) -> Result<IdTokenHint, AppError> {
+        self.decode_claims(token, |validation| {
+            validation.validate_exp = false;
+            validation.validate_aud = false;
+            validation.set_issuer(&[issuer]);
+            validation.set_required_spec_claims(&["iss", "sub", "aud"]);
         })
     }
 
+    /// 按 header 中的 alg / kid 选择验证密钥并解析 claims
+    fn decode_claims<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        configure: impl FnOnce(&mut Validation),
+    ) -> Result<T, AppError> {
+        // 按 header 中的 alg / kid 选择验证密钥
+        let header = decode_header(token).map_err(|_| AppError::InvalidToken)?;
+        let keys = self.keys();
+        let now = chrono::Utc::now().timestamp();
+        let key = match keys.find(header.alg, header.kid.as_deref(), now) {
+            Some(key) => key.decoding_key(),
+            // 切换前签发的 HS256 Token 只在截止时间之前接受
+            None if header.alg == Algorithm::HS256
+                && header.kid.is_none()
+                && keys.accepts_legacy(now) =>
+            {
+                &self.legacy_key
+            }
+            None => return Err(AppError::InvalidToken),
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = VALIDATION_LEEWAY;
+        configure(&mut validation);
+
+        decode::<T>(token, key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => AppError::TokenExpired,
+                _ => AppError::InvalidToken,
+            })
+    }
+
     /// 提取 Token 中的 user_id
     pub fn extract_user_id(&self, token: &str) -> Result<i64, AppError> {
         let claims = self.verify_token(token)?;
+        if claims.is_client() {
+            return Err(AppError::InvalidToken);
+        }
         claims
             .sub
             .parse::<i64>()
             .map_err(|_| AppError::InvalidToken)
     }
-
-    /// 获取密钥（用于 ID Token 生成等场景）
-    pub fn secret(&self) -> &str {
-        &self.secret
-    }
 }
 
 #[cfg(test)]
@@ -84,7 +354,9 @@ mod tests {
     #[test]
     fn test_jwt_generate_and_verify() {
         let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
-        let token = manager.generate_token(123, 3600, None, "user").unwrap();
+        let token = manager
+            .generate_token("123", 3600, None, "user", None)
+            .unwrap();
 
         let claims = manager.verify_token(&token).unwrap();
         assert_eq!(claims.sub, "123");
@@ -94,11 +366,238 @@ mod tests {
         assert_eq!(user_id, 123);
     }
 
+    #[test]
+    fn test_client_token() {
+        let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
+        let token = manager
+            .generate_client_token("my_client", 3600, Some(vec!["read".to_string()]), None)
+            .unwrap();
+
+        let claims = manager.verify_token(&token).unwrap();
+        assert_eq!(claims.sub, "my_client");
+        assert!(claims.is_client());
+
+        // 客户端 Token 不能当作用户 Token 使用
+        assert!(matches!(
+            manager.extract_user_id(&token),
+            Err(AppError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_dpop_bound_token() {
+        let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
+        let token = manager
+            .generate_token("123", 3600, None, "user", Some("thumbprint"))
+            .unwrap();
+
+        let claims = manager.verify_token(&token).unwrap();
+        assert_eq!(claims.cnf.map(|cnf| cnf.jkt).as_deref(), Some("thumbprint"));
+
+        // 未绑定的 Token 不包含 cnf
+        let token = manager
+            .generate_token("123", 3600, None, "user", None)
+            .unwrap();
+        assert!(manager.verify_token(&token).unwrap().cnf.is_none());
+    }
+
+    #[test]
+    fn test_exchanged_token() {
+        let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
+        let subject = manager
+            .verify_token(
+                &manager
+                    .generate_token("123", 3600, None, "user", None)
+                    .unwrap(),
+            )
+            .unwrap();
+        let act = Actor {
+            sub: "gateway".to_string(),
+            act: Some(Box::new(Actor {
+                sub: "frontend".to_string(),
+                act: None,
+            })),
+        };
+
+        let token = manager
+            .generate_exchanged_token(
+                &subject,
+                600,
+                Some(vec!["read".to_string()]),
+                Some("orders-api".to_string()),
+                Some(act.clone()),
+                None,
+            )
+            .unwrap();
+
+        // 带 aud 的 Token 仍能通过验证，主体和角色与 subject_token 相同
+        let claims = manager.verify_token(&token).unwrap();
+        assert_eq!(claims.sub, "123");
+        assert_eq!(claims.role, "user");
+        assert_eq!(claims.aud.as_deref(), Some("orders-api"));
+        assert_eq!(claims.act, Some(act));
+        assert_eq!(manager.extract_user_id(&token).unwrap(), 123);
+    }
+
+    #[test]
+    fn test_id_token_hint() {
+        let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
+        let issuer = "https://auth.example.com";
+
+        // 已过期的 ID Token 仍可作为 id_token_hint
+        let id_token = manager
+            .encode(&serde_json::json!({
+                "iss": issuer,
+                "sub": "123",
+                "aud": "my_client",
+                "exp": chrono::Utc::now().timestamp() - 3600,
+            }))
+            .unwrap();
+        let hint = manager.verify_id_token_hint(&id_token, issuer).unwrap();
+        assert_eq!(hint.sub, "123");
+        assert_eq!(hint.aud, "my_client");
+
+        // issuer 不匹配
+        assert!(
+            manager
+                .verify_id_token_hint(&id_token, "https://other.example.com")
+                .is_err()
+        );
+
+        // access token 不是 ID Token
+        let access_token = manager
+            .generate_token("123", 3600, None, "user", None)
+            .unwrap();
+        assert!(manager.verify_id_token_hint(&access_token, issuer).is_err());
+    }
+
+    #[test]
+    fn test_jwt_tokens_are_unique() {
+        let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
+        let first = manager
+            .generate_token("123", 3600, None, "user", None)
+            .unwrap();
+        let second = manager
+            .generate_token("123", 3600, None, "user", None)
+            .unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_asymmetric_signing_keys() {
+        let secret = "test-secret-key-at-least-32-characters-long";
+        let legacy = JwtManager::new(secret.to_string())
+            .generate_token("1", 3600, None, "user", None)
+            .unwrap();
+
+        for algorithm in [Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA] {
+            let key = SigningKey::generate(algorithm).unwrap();
+            let kid = key.kid().unwrap().to_string();
+            let manager = JwtManager::with_signing_key(secret, key);
+
+            // header 中携带 alg 和 kid
+            let token = manager
+                .generate_token("123", 3600, None, "user", None)
+                .unwrap();
+            let header = decode_header(&token).unwrap();
+            assert_eq!(header.alg, algorithm);
+            assert_eq!(header.kid.as_deref(), Some(kid.as_str()));
+            assert_eq!(manager.verify_token(&token).unwrap().sub, "123");
+
+            // 公钥可公开
+            let jwks = manager.public_jwks();
+            assert_eq!(jwks.len(), 1);
+            assert_eq!(jwks[0].kid, kid);
+
+            // 切换前签发的 HS256 Token 默认不再接受，设置截止时间后在截止前仍然有效
+            assert!(matches!(
+                manager.verify_token(&legacy),
+                Err(AppError::InvalidToken)
+            ));
+            let now = chrono::Utc::now().timestamp();
+            manager.set_signing_keys(
+                SigningKey::generate(algorithm).unwrap(),
+                Vec::new(),
+                Some(now + 3600),
+            );
+            assert_eq!(manager.verify_token(&legacy).unwrap().sub, "1");
+            manager.set_signing_keys(
+                SigningKey::generate(algorithm).unwrap(),
+                Vec::new(),
+                Some(now - 1),
+            );
+            assert!(matches!(
+                manager.verify_token(&legacy),
+                Err(AppError::InvalidToken)
+            ));
+
+            // 其他密钥签发的 Token 无效
+            let other =
+                JwtManager::with_signing_key(secret, SigningKey::generate(algorithm).unwrap());
+            let forged = other
+                .generate_token("123", 3600, None, "admin", None)
+                .unwrap();
+            assert!(matches!(
+                manager.verify_token(&forged),
+                Err(AppError::InvalidToken)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_signing_key_rotation() {
+        let secret = "test-secret-key-at-least-32-characters-long";
+        let old = SigningKey::generate(Algorithm::ES256).unwrap();
+        let new = SigningKey::generate(Algorithm::ES256).unwrap();
+
+        let manager = JwtManager::with_signing_key(secret, old.clone());
+        let old_token = manager
+            .generate_token("1", 3600, None, "user", None)
+            .unwrap();
+
+        // 轮换后用新密钥签名，旧密钥在重叠期内仍可验证
+        let now = chrono::Utc::now().timestamp();
+        manager.set_signing_keys(
+            new.clone(),
+            vec![VerificationKey {
+                key: old.clone(),
+                not_after: Some(now + 3600),
+            }],
+            None,
+        );
+        assert_eq!(manager.active_kid().as_deref(), new.kid());
+
+        let new_token = manager
+            .generate_token("2", 3600, None, "user", None)
+            .unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), new.kid());
+        assert_eq!(manager.verify_token(&old_token).unwrap().sub, "1");
+        assert_eq!(manager.verify_token(&new_token).unwrap().sub, "2");
+        assert_eq!(manager.public_jwks().len(), 2);
+
+        // 重叠期结束后旧密钥不再公开，也不再接受
+        manager.set_signing_keys(
+            new,
+            vec![VerificationKey {
+                key: old,
+                not_after: Some(now - 1),
+            }],
+            None,
+        );
+        assert_eq!(manager.public_jwks().len(), 1);
+        assert!(matches!(
+            manager.verify_token(&old_token),
+            Err(AppError::InvalidToken)
+        ));
+    }
+
     #[test]
     fn test_jwt_expired_token() {
         let manager = JwtManager::new("test-secret-key-at-least-32-characters-long".to_string());
-        // 过期时间设为 -1 秒（已过期）
-        let token = manager.generate_token(123, -1, None, "user").unwrap();
+        // 过期时间设为 -120 秒（超过默认 60 秒的时钟偏差容忍）
+        let token = manager
+            .generate_token("123", -120, None, "user", None)
+            .unwrap();
 
         let result = manager.verify_token(&token);
         assert!(matches!(result, Err(AppError::TokenExpired)));
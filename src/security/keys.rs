@@ -0,0 +1,370 @@
+use aws_lc_rs::encoding::AsDer;
+use aws_lc_rs::rsa::{KeyPair as RsaKeyPair, KeySize};
+use aws_lc_rs::signature::{
+    ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair, Ed25519KeyPair, KeyPair,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::errors::AppError;
+
+/// 支持的 JWT 签名算法
+pub const SUPPORTED_SIGNING_ALGORITHMS: [&str; 4] = ["RS256", "ES256", "EdDSA", "HS256"];
+
+/// 解析签名算法名称
+pub fn parse_algorithm(name: &str) -> Result<Algorithm, AppError> {
+    match name {
+        "RS256" => Ok(Algorithm::RS256),
+        "ES256" => Ok(Algorithm::ES256),
+        "EdDSA" => Ok(Algorithm::EdDSA),
+        "HS256" => Ok(Algorithm::HS256),
+        _ => Err(AppError::Config(format!(
+            "Unsupported signing algorithm: {}",
+            name
+        ))),
+    }
+}
+
+/// 签名算法名称（与 JWT header 中的 alg 一致）
+pub fn algorithm_name(algorithm: Algorithm) -> &'static str {
+    match algorithm {
+        Algorithm::HS256 => "HS256",
+        Algorithm::HS384 => "HS384",
+        Algorithm::HS512 => "HS512",
+        Algorithm::RS256 => "RS256",
+        Algorithm::RS384 => "RS384",
+        Algorithm::RS512 => "RS512",
+        Algorithm::PS256 => "PS256",
+        Algorithm::PS384 => "PS384",
+        Algorithm::PS512 => "PS512",
+        Algorithm::ES256 => "ES256",
+        Algorithm::ES384 => "ES384",
+        Algorithm::EdDSA => "EdDSA",
+    }
+}
+
+/// 计算 ID Token 中的 at_hash / c_hash（OIDC Core 3.1.3.6）
+///
+/// 使用与签名算法对应的哈希（EdDSA 为 SHA-512，其余为 SHA-256），取左半部分 base64url 编码
+pub fn token_hash(algorithm: Algorithm, value: &str) -> String {
+    let digest = match algorithm {
+        Algorithm::EdDSA => Sha512::digest(value.as_bytes()).to_vec(),
+        _ => Sha256::digest(value.as_bytes()).to_vec(),
+    };
+
+    URL_SAFE_NO_PAD.encode(&digest[..digest.len() / 2])
+}
+
+/// 公钥的 JWK 表示（RFC 7517）
+#[derive(Debug, Clone, Serialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: String,
+    pub r#use: String,
+    pub alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+/// JWT 签名密钥
+#[derive(Clone)]
+pub struct SigningKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_jwk: Option<Jwk>,
+    private_pem: Option<String>,
+}
+
+impl SigningKey {
+    /// HS256 对称密钥（不公开，无 kid）
+    pub fn hmac(secret: &str) -> Self {
+        Self {
+            kid: None,
+            algorithm: Algorithm::HS256,
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            public_jwk: None,
+            private_pem: None,
+        }
+    }
+
+    /// 生成新的非对称密钥
+    pub fn generate(algorithm: Algorithm) -> Result<Self, AppError> {
+        let generate_failed = |_| AppError::Internal("Failed to generate signing key".into());
+
+        let der = match algorithm {
+            Algorithm::RS256 => RsaKeyPair::generate(KeySize::Rsa2048)
+                .map_err(generate_failed)?
+                .as_der()
+                .map_err(generate_failed)?
+                .as_ref()
+                .to_vec(),
+            Algorithm::ES256 => EcdsaKeyPair::generate(&ECDSA_P256_SHA256_FIXED_SIGNING)
+                .map_err(generate_failed)?
+                .to_pkcs8v1()
+                .map_err(generate_failed)?
+                .as_ref()
+                .to_vec(),
+            Algorithm::EdDSA => Ed25519KeyPair::generate()
+                .map_err(generate_failed)?
+                .to_pkcs8v1()
+                .map_err(generate_failed)?
+                .as_ref()
+                .to_vec(),
+            _ => {
+                return Err(AppError::Config(
+                    "Only asymmetric signing keys can be generated".into(),
+                ));
+            }
+        };
+
+        Self::from_pem(algorithm, &pem::encode(&pem::Pem::new("PRIVATE KEY", der)))
+    }
+
+    /// 从 PEM 私钥加载（PKCS#8，RSA 也接受 PKCS#1）
+    pub fn from_pem(algorithm: Algorithm, private_pem: &str) -> Result<Self, AppError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            AppError::Config(format!(
+                "Invalid {} signing key: {}",
+                algorithm_name(algorithm),
+                e
+            ))
+        };
+
+        let parsed = pem::parse(private_pem).map_err(|e| invalid(&e))?;
+        let der = parsed.contents();
+
+        let (encoding_key, decoding_key, mut jwk) = match algorithm {
+            Algorithm::RS256 => {
+                let key_pair = if parsed.tag() == "RSA PRIVATE KEY" {
+                    RsaKeyPair::from_der(der)
+                } else {
+                    RsaKeyPair::from_pkcs8(der)
+                }
+                .map_err(|e| invalid(&e))?;
+
+                let public = key_pair.public_key();
+                let n = public.modulus().big_endian_without_leading_zero();
+                let e = public.exponent().big_endian_without_leading_zero();
+
+                let jwk = Jwk {
+                    kty: "RSA".to_string(),
+                    n: Some(URL_SAFE_NO_PAD.encode(n)),
+                    e: Some(URL_SAFE_NO_PAD.encode(e)),
+                    ..Jwk::empty(algorithm)
+                };
+                (
+                    EncodingKey::from_rsa_pem(private_pem.as_bytes()).map_err(|e| invalid(&e))?,
+                    DecodingKey::from_rsa_raw_components(n, e),
+                    jwk,
+                )
+            }
+            Algorithm::ES256 => {
+                let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, der)
+                    .map_err(|e| invalid(&e))?;
+
+                // 未压缩格式: 0x04 || x || y
+                let point = key_pair.public_key().as_ref();
+                let x = URL_SAFE_NO_PAD.encode(&point[1..33]);
+                let y = URL_SAFE_NO_PAD.encode(&point[33..65]);
+
+                let decoding_key =
+                    DecodingKey::from_ec_components(&x, &y).map_err(|e| invalid(&e))?;
+                let jwk = Jwk {
+                    kty: "EC".to_string(),
+                    crv: Some("P-256".to_string()),
+                    x: Some(x),
+                    y: Some(y),
+                    ..Jwk::empty(algorithm)
+                };
+                (
+                    EncodingKey::from_ec_pem(private_pem.as_bytes()).map_err(|e| invalid(&e))?,
+                    decoding_key,
+                    jwk,
+                )
+            }
+            Algorithm::EdDSA => {
+                let key_pair = Ed25519KeyPair::from_pkcs8(der).map_err(|e| invalid(&e))?;
+                let x = URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref());
+
+                let decoding_key = DecodingKey::from_ed_components(&x).map_err(|e| invalid(&e))?;
+                let jwk = Jwk {
+                    kty: "OKP".to_string(),
+                    crv: Some("Ed25519".to_string()),
+                    x: Some(x),
+                    ..Jwk::empty(algorithm)
+                };
+                (
+                    EncodingKey::from_ed_pem(private_pem.as_bytes()).map_err(|e| invalid(&e))?,
+                    decoding_key,
+                    jwk,
+                )
+            }
+            _ => return Err(invalid(&"not an asymmetric algorithm")),
+        };
+
+        // kid 使用 JWK Thumbprint（RFC 7638），同一密钥总是得到相同的 kid
+        let kid = jwk.thumbprint();
+        jwk.kid = kid.clone();
+
+        Ok(Self {
+            kid: Some(kid),
+            algorithm,
+            encoding_key,
+            decoding_key,
+            public_jwk: Some(jwk),
+            private_pem: Some(private_pem.to_string()),
+        })
+    }
+
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+
+    /// 可公开的公钥（对称密钥返回 None）
+    pub fn public_jwk(&self) -> Option<&Jwk> {
+        self.public_jwk.as_ref()
+    }
+
+    /// PEM 格式私钥（用于持久化，对称密钥返回 None）
+    pub fn private_pem(&self) -> Option<&str> {
+        self.private_pem.as_deref()
+    }
+}
+
+impl Jwk {
+    fn empty(algorithm: Algorithm) -> Self {
+        Self {
+            kty: String::new(),
+            kid: String::new(),
+            r#use: "sig".to_string(),
+            alg: algorithm_name(algorithm).to_string(),
+            n: None,
+            e: None,
+            crv: None,
+            x: None,
+            y: None,
+        }
+    }
+
+    /// 计算 JWK Thumbprint（RFC 7638，SHA-256）
+    pub fn thumbprint(&self) -> String {
+        let field = |value: &Option<String>| value.clone().unwrap_or_default();
+
+        // 必需成员按字典序排列
+        let canonical = match self.kty.as_str() {
+            "RSA" => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                field(&self.e),
+                field(&self.n)
+            ),
+            "EC" => format!(
+                r#"{{"crv":"{}","kty":"EC","x":"{}","y":"{}"}}"#,
+                field(&self.crv),
+                field(&self.x),
+                field(&self.y)
+            ),
+            _ => format!(
+                r#"{{"crv":"{}","kty":"{}","x":"{}"}}"#,
+                field(&self.crv),
+                self.kty,
+                field(&self.x)
+            ),
+        };
+
+        URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_algorithm() {
+        for name in SUPPORTED_SIGNING_ALGORITHMS {
+            let algorithm = parse_algorithm(name).unwrap();
+            assert_eq!(algorithm_name(algorithm), name);
+        }
+        assert!(parse_algorithm("none").is_err());
+    }
+
+    #[test]
+    fn test_generated_key_roundtrip() {
+        for algorithm in [Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA] {
+            let key = SigningKey::generate(algorithm).unwrap();
+            let pem = key.private_pem().unwrap();
+
+            // 从 PEM 重新加载后 kid 不变
+            let reloaded = SigningKey::from_pem(algorithm, pem).unwrap();
+            assert_eq!(reloaded.kid(), key.kid());
+
+            let jwk = key.public_jwk().unwrap();
+            assert_eq!(jwk.kid, key.kid().unwrap());
+            assert_eq!(jwk.alg, algorithm_name(algorithm));
+            assert_eq!(jwk.r#use, "sig");
+        }
+    }
+
+    #[test]
+    fn test_rfc7638_thumbprint() {
+        // RFC 7638 3.1 示例
+        let jwk = Jwk {
+            kty: "RSA".to_string(),
+            n: Some("0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw".to_string()),
+            e: Some("AQAB".to_string()),
+            ..Jwk::empty(Algorithm::RS256)
+        };
+        assert_eq!(
+            jwk.thumbprint(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+
+    #[test]
+    fn test_token_hash() {
+        // OIDC Core A.3 示例中的 access_token 与 at_hash
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+        assert_eq!(
+            token_hash(Algorithm::RS256, access_token),
+            "77QmUPtjPfzWtF2AnpK9RQ"
+        );
+
+        // EdDSA 使用 SHA-512 的左半部分
+        assert_eq!(token_hash(Algorithm::EdDSA, access_token).len(), 43);
+    }
+
+    #[test]
+    fn test_invalid_pem() {
+        assert!(SigningKey::from_pem(Algorithm::RS256, "not a pem").is_err());
+
+        // 算法与密钥类型不匹配
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        assert!(SigningKey::from_pem(Algorithm::RS256, key.private_pem().unwrap()).is_err());
+    }
+}
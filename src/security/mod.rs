@@ -1,12 +1,26 @@
+pub mod claims;
+pub mod client_assertion;
+pub mod client_secret;
+pub mod dpop;
 pub mod jwt;
+pub mod key_encryption;
+pub mod keys;
+pub mod pairwise;
 pub mod password;
+pub mod pkce;
+pub mod request_object;
 pub mod token;
 
-pub use jwt::{Claims, JwtManager};
+pub use client_secret::ClientSecretManager;
+pub use jwt::{Actor, Claims, Confirmation, JwtManager, VerificationKey};
+pub use keys::SigningKey;
 pub use password::PasswordManager;
 pub use token::{
     generate_auth_code,
+    generate_client_id,
     generate_client_secret,
     generate_random_string,
     generate_random_string as generate_random_token, // 别名
+    generate_user_code,
+    normalize_user_code,
 };
@@ -0,0 +1,103 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// 所有客户端看到相同 sub 的 subject_type（sub 为用户 ID）
+pub const SUBJECT_TYPE_PUBLIC: &str = "public";
+
+/// 每个扇区看到不同 sub 的 subject_type（OpenID Connect Core 8.1）
+pub const SUBJECT_TYPE_PAIRWISE: &str = "pairwise";
+
+/// 支持的 subject_type
+pub const SUPPORTED_SUBJECT_TYPES: [&str; 2] = [SUBJECT_TYPE_PUBLIC, SUBJECT_TYPE_PAIRWISE];
+
+/// 确定客户端所属的扇区（主机名）
+///
+/// 登记了 sector_identifier_uri 时使用其主机名，否则所有 redirect_uri 必须使用同一主机名；
+/// 无法确定时返回 None（OpenID Connect Core 8.1）
+pub fn sector_identifier(
+    sector_identifier_uri: Option<&str>,
+    redirect_uris: &[String],
+) -> Option<String> {
+    let host = |uri: &str| Url::parse(uri).ok()?.host_str().map(str::to_string);
+
+    if let Some(uri) = sector_identifier_uri {
+        return host(uri);
+    }
+
+    let mut hosts = redirect_uris.iter().map(|uri| host(uri));
+    let first = hosts.next()??;
+    hosts
+        .all(|host| host.as_deref() == Some(first.as_str()))
+        .then_some(first)
+}
+
+/// 计算用户在扇区中的 pairwise 标识：base64url(SHA-256(sector || user_id || salt))
+pub fn pairwise_subject(sector_identifier: &str, user_id: i64, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sector_identifier.as_bytes());
+    hasher.update(user_id.to_string().as_bytes());
+    hasher.update(salt.as_bytes());
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uris(uris: &[&str]) -> Vec<String> {
+        uris.iter().map(|uri| uri.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sector_identifier() {
+        // 优先使用 sector_identifier_uri 的主机名
+        assert_eq!(
+            sector_identifier(
+                Some("https://sector.example.com/redirects.json"),
+                &uris(&["https://a.example.com/cb", "https://b.example.com/cb"]),
+            )
+            .as_deref(),
+            Some("sector.example.com")
+        );
+
+        // 所有 redirect_uri 使用同一主机名
+        assert_eq!(
+            sector_identifier(
+                None,
+                &uris(&[
+                    "https://app.example.com/cb",
+                    "https://app.example.com/other"
+                ]),
+            )
+            .as_deref(),
+            Some("app.example.com")
+        );
+
+        // 多个主机名、没有主机名或没有 redirect_uri 时无法确定
+        assert!(
+            sector_identifier(
+                None,
+                &uris(&["https://a.example.com/cb", "https://b.example.com/cb"]),
+            )
+            .is_none()
+        );
+        assert!(sector_identifier(None, &uris(&["com.example.app:/cb"])).is_none());
+        assert!(sector_identifier(None, &[]).is_none());
+    }
+
+    #[test]
+    fn test_pairwise_subject() {
+        let subject = pairwise_subject("app.example.com", 1, "salt");
+
+        // 同一扇区内稳定，不同扇区、用户或 salt 得到不同的标识
+        assert_eq!(subject, pairwise_subject("app.example.com", 1, "salt"));
+        assert_ne!(subject, pairwise_subject("other.example.com", 1, "salt"));
+        assert_ne!(subject, pairwise_subject("app.example.com", 2, "salt"));
+        assert_ne!(subject, pairwise_subject("app.example.com", 1, "pepper"));
+
+        // 不泄露用户 ID
+        assert_eq!(subject.len(), 43);
+        assert!(subject.parse::<i64>().is_err());
+    }
+}
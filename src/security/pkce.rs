@@ -0,0 +1,55 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/// 支持的 code_challenge_method
+pub const SUPPORTED_CHALLENGE_METHODS: [&str; 2] = ["S256", "plain"];
+
+/// 校验 code_challenge / code_verifier 的格式（RFC 7636: 43-128 位 unreserved 字符）
+pub fn is_valid_code_value(value: &str) -> bool {
+    (43..=128).contains(&value.len())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+}
+
+/// 根据 code_verifier 计算 code_challenge
+pub fn compute_code_challenge(code_verifier: &str, method: &str) -> Option<String> {
+    match method {
+        "S256" => Some(URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))),
+        "plain" => Some(code_verifier.to_string()),
+        _ => None,
+    }
+}
+
+/// 验证 code_verifier 是否与授权时保存的 code_challenge 匹配
+pub fn verify_code_verifier(code_verifier: &str, code_challenge: &str, method: &str) -> bool {
+    is_valid_code_value(code_verifier)
+        && compute_code_challenge(code_verifier, method).as_deref() == Some(code_challenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_s256_challenge() {
+        // RFC 7636 附录 B 示例
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let challenge = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+        assert_eq!(
+            compute_code_challenge(verifier, "S256").as_deref(),
+            Some(challenge)
+        );
+        assert!(verify_code_verifier(verifier, challenge, "S256"));
+        assert!(!verify_code_verifier(verifier, challenge, "plain"));
+    }
+
+    #[test]
+    fn test_invalid_code_verifier() {
+        // 长度不足 43 位
+        assert!(!verify_code_verifier("short", "short", "plain"));
+        // 不支持的方法
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        assert!(!verify_code_verifier(verifier, verifier, "S512"));
+    }
+}
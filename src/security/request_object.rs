@@ -0,0 +1,148 @@
+use jsonwebtoken::{Algorithm, Validation, decode, decode_header};
+use serde_json::{Map, Value};
+
+use crate::errors::AppError;
+use crate::security::client_assertion::{SUPPORTED_ASSERTION_ALGORITHMS, client_public_key};
+use crate::security::jwt::VALIDATION_LEEWAY;
+
+/// 请求对象允许的签名算法：客户端公钥支持的非对称算法
+///
+/// 客户端密钥只以哈希形式保存，无法验证 HS256 请求对象
+pub fn supported_request_object_algorithms() -> Vec<Algorithm> {
+    SUPPORTED_ASSERTION_ALGORITHMS.to_vec()
+}
+
+/// 读取请求对象 header 中的签名算法（不接受 alg=none）
+pub fn request_object_algorithm(request: &str) -> Result<Algorithm, AppError> {
+    let header = decode_header(request)
+        .map_err(|_| AppError::InvalidRequestObject("Malformed request object".into()))?;
+
+    if !supported_request_object_algorithms().contains(&header.alg) {
+        return Err(AppError::InvalidRequestObject(
+            "Unsupported request object signing algorithm".into(),
+        ));
+    }
+    Ok(header.alg)
+}
+
+/// 验证请求对象（RFC 9101）的签名、iss、aud，以及存在时的 exp、nbf
+///
+/// 使用客户端登记的 JWKS 验证签名，iss 必须是 client_id，aud 必须包含授权服务器的 issuer。
+/// 返回请求对象中的全部 claims
+pub fn verify_request_object(
+    request: &str,
+    jwks: &str,
+    client_id: &str,
+    issuer: &str,
+) -> Result<Map<String, Value>, AppError> {
+    // 1. 按 header 中的 kid 选择客户端公钥
+    let header = decode_header(request)
+        .map_err(|_| AppError::InvalidRequestObject("Malformed request object".into()))?;
+    if !SUPPORTED_ASSERTION_ALGORITHMS.contains(&header.alg) {
+        return Err(AppError::InvalidRequestObject(
+            "Unsupported request object signing algorithm".into(),
+        ));
+    }
+    let key = client_public_key(jwks, header.kid.as_deref())
+        .map_err(|_| AppError::InvalidRequestObject("No matching client key found".into()))?;
+
+    // 2. 验证签名、iss、aud（exp 和 nbf 存在时一并验证）
+    let mut validation = Validation::new(header.alg);
+    validation.leeway = VALIDATION_LEEWAY;
+    validation.validate_nbf = true;
+    validation.set_issuer(&[client_id]);
+    validation.set_audience(&[issuer]);
+    validation.set_required_spec_claims(&["iss", "aud"]);
+
+    let claims = decode::<Map<String, Value>>(request, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| AppError::InvalidRequestObject(e.to_string()))?;
+
+    // 3. 请求对象中的 client_id 必须与请求参数一致（RFC 9101 5）
+    if claims
+        .get("client_id")
+        .is_some_and(|id| id.as_str() != Some(client_id))
+    {
+        return Err(AppError::InvalidRequestObject("client_id mismatch".into()));
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SigningKey;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+
+    const ISSUER: &str = "https://auth.example.com";
+
+    fn sign(key: &SigningKey, claims: serde_json::Value) -> String {
+        let mut header = Header::new(key.algorithm());
+        header.kid = key.kid().map(str::to_string);
+        encode(&header, &claims, key.encoding_key()).unwrap()
+    }
+
+    #[test]
+    fn test_verify_request_object_with_jwks() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        let jwks = json!({ "keys": [key.public_jwk().unwrap()] }).to_string();
+
+        let request = sign(
+            &key,
+            json!({
+                "iss": "client-a",
+                "aud": ISSUER,
+                "client_id": "client-a",
+                "scope": "openid",
+                "state": "xyz",
+            }),
+        );
+        assert_eq!(
+            request_object_algorithm(&request).unwrap(),
+            Algorithm::ES256
+        );
+        let claims = verify_request_object(&request, &jwks, "client-a", ISSUER).unwrap();
+        assert_eq!(claims["state"], "xyz");
+
+        // iss 不是 client_id
+        assert!(verify_request_object(&request, &jwks, "client-b", ISSUER).is_err());
+
+        // client_id 与请求参数不一致
+        let mismatched = sign(
+            &key,
+            json!({ "iss": "client-a", "aud": ISSUER, "client_id": "client-b" }),
+        );
+        assert!(verify_request_object(&mismatched, &jwks, "client-a", ISSUER).is_err());
+
+        // 已过期
+        let expired = sign(
+            &key,
+            json!({
+                "iss": "client-a",
+                "aud": ISSUER,
+                "exp": chrono::Utc::now().timestamp() - 3600,
+            }),
+        );
+        assert!(verify_request_object(&expired, &jwks, "client-a", ISSUER).is_err());
+
+        // 不接受 HS256（客户端密钥只以哈希形式保存）
+        let hmac = encode(
+            &Header::new(Algorithm::HS256),
+            &json!({ "iss": "client-a", "aud": ISSUER }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        assert!(request_object_algorithm(&hmac).is_err());
+        assert!(verify_request_object(&hmac, &jwks, "client-a", ISSUER).is_err());
+
+        // 不接受未签名的请求对象
+        let unsigned = format!(
+            "{}.{}.",
+            "eyJhbGciOiJub25lIn0",
+            hmac.split('.').nth(1).unwrap()
+        );
+        assert!(request_object_algorithm(&unsigned).is_err());
+    }
+}
@@ -15,11 +15,43 @@ pub fn generate_auth_code() -> String {
     generate_random_string(32)
 }
 
+/// 生成客户端 ID（24字符）
+pub fn generate_client_id() -> String {
+    generate_random_string(24)
+}
+
 /// 生成客户端 Secret（48字符）
 pub fn generate_client_secret() -> String {
     generate_random_string(48)
 }
 
+/// 设备授权 user_code 字符集（去掉元音和易混淆字符，RFC 8628 6.1）
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+/// 生成设备授权 user_code（格式 XXXX-XXXX）
+pub fn generate_user_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..8)
+        .map(|_| USER_CODE_CHARSET[rng.random_range(0..USER_CODE_CHARSET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..4], &chars[4..])
+}
+
+/// 规范化用户输入的 user_code（忽略大小写、空格和连字符）
+pub fn normalize_user_code(input: &str) -> Option<String> {
+    let chars: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if chars.len() != 8 || !chars.bytes().all(|b| USER_CODE_CHARSET.contains(&b)) {
+        return None;
+    }
+
+    Some(format!("{}-{}", &chars[..4], &chars[4..]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,9 +63,37 @@ mod tests {
         assert!(code.chars().all(|c| c.is_alphanumeric()));
     }
 
+    #[test]
+    fn test_generate_client_id() {
+        let client_id = generate_client_id();
+        assert_eq!(client_id.len(), 24);
+        assert!(client_id.chars().all(|c| c.is_alphanumeric()));
+    }
+
     #[test]
     fn test_generate_client_secret() {
         let secret = generate_client_secret();
         assert_eq!(secret.len(), 48);
     }
+
+    #[test]
+    fn test_generate_user_code() {
+        let code = generate_user_code();
+        assert_eq!(code.len(), 9);
+        assert_eq!(normalize_user_code(&code), Some(code));
+    }
+
+    #[test]
+    fn test_normalize_user_code() {
+        assert_eq!(
+            normalize_user_code("wdjb mjht"),
+            Some("WDJB-MJHT".to_string())
+        );
+        assert_eq!(
+            normalize_user_code("WDJB-MJHT"),
+            Some("WDJB-MJHT".to_string())
+        );
+        assert_eq!(normalize_user_code("WDJB-MJH"), None);
+        assert_eq!(normalize_user_code("ABCD-EFGH"), None);
+    }
 }
@@ -48,14 +48,14 @@ mod tests {
             .get_registration_config()
             .await
             .expect("Failed to get config");
-        assert_eq!(config1.allow_registration, true);
+        assert!(config1.allow_registration);
 
         // 3. 再次读取配置（应该从缓存读取）
         let config2 = backend
             .get_registration_config()
             .await
             .expect("Failed to get config");
-        assert_eq!(config2.allow_registration, true);
+        assert!(config2.allow_registration);
 
         // 4. 验证缓存键存在
         let cached = cache.get("config:registration").await;
@@ -74,9 +74,11 @@ mod tests {
         let _ = backend.get_registration_config().await;
 
         // 3. 更新配置
-        let mut new_config = RegistrationConfig::default();
-        new_config.allow_registration = false;
-        new_config.min_password_length = 12;
+        let new_config = RegistrationConfig {
+            allow_registration: false,
+            min_password_length: 12,
+            ..Default::default()
+        };
 
         backend
             .update_registration_config(&new_config, user_id)
@@ -92,7 +94,7 @@ mod tests {
             .get_registration_config()
             .await
             .expect("Failed to get updated config");
-        assert_eq!(updated_config.allow_registration, false);
+        assert!(!updated_config.allow_registration);
         assert_eq!(updated_config.min_password_length, 12);
     }
 
@@ -104,8 +106,10 @@ mod tests {
         let user_id = create_test_user(&backend).await;
 
         // 2. 更新配置（应该自动记录审计日志）
-        let mut new_config = RegistrationConfig::default();
-        new_config.allow_registration = false;
+        let new_config = RegistrationConfig {
+            allow_registration: false,
+            ..Default::default()
+        };
 
         backend
             .update_registration_config(&new_config, user_id)
@@ -242,4 +246,755 @@ mod tests {
         let result = backend.verify_and_use_invite_code("EXPIRED", user_id).await;
         assert!(result.is_err(), "Using expired invite should fail");
     }
+
+    #[tokio::test]
+    async fn test_refresh_token_lookup_and_rotation() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAccessToken;
+        use chrono::{DateTime, Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 保存 access token 和 refresh token
+        let expires_at = Utc::now() + Duration::hours(1);
+        let access_token_id = backend
+            .save_access_token(
+                "ACCESS",
+                NewAccessToken {
+                    client_id: "client".to_string(),
+                    user_id: Some(user_id),
+                    scopes: "openid".to_string(),
+                    expires_at,
+                    claims: None,
+                    auth_time: DateTime::from_timestamp(1_700_000_000, 0),
+                },
+            )
+            .await
+            .expect("Failed to save access token");
+        backend
+            .save_refresh_token("REFRESH", access_token_id, expires_at)
+            .await
+            .expect("Failed to save refresh token");
+
+        // 3. 查找 refresh token（应同时返回关联的 access token）
+        let (refresh, access) = backend
+            .find_refresh_token("REFRESH")
+            .await
+            .expect("Failed to find refresh token")
+            .expect("Refresh token should exist");
+        assert_eq!(access.id, access_token_id);
+        assert_eq!(access.client_id, "client");
+        assert_eq!(access.user_id, Some(user_id));
+        assert_eq!(
+            access.auth_time.map(|at| at.timestamp()),
+            Some(1_700_000_000)
+        );
+
+        // 4. 删除后不可再次删除，也查不到
+        assert!(backend.delete_refresh_token(refresh.id).await.unwrap());
+        assert!(!backend.delete_refresh_token(refresh.id).await.unwrap());
+        assert!(
+            backend
+                .find_refresh_token("REFRESH")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_access_token_revokes_refresh_token() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAccessToken;
+        use chrono::{Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        let expires_at = Utc::now() + Duration::hours(1);
+        let access_token_id = backend
+            .save_access_token(
+                "ACCESS",
+                NewAccessToken {
+                    client_id: "client".to_string(),
+                    user_id: Some(user_id),
+                    scopes: "openid".to_string(),
+                    expires_at,
+                    claims: None,
+                    auth_time: None,
+                },
+            )
+            .await
+            .expect("Failed to save access token");
+        backend
+            .save_refresh_token("REFRESH", access_token_id, expires_at)
+            .await
+            .expect("Failed to save refresh token");
+
+        // 2. 撤销 access token 时关联的 refresh token 一并删除
+        assert_eq!(
+            backend.delete_access_token(access_token_id).await.unwrap(),
+            Some(vec!["REFRESH".to_string()])
+        );
+        assert!(
+            backend
+                .delete_access_token(access_token_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(backend.find_access_token("ACCESS").await.unwrap().is_none());
+        assert!(
+            backend
+                .find_refresh_token("REFRESH")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_user_tokens() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAccessToken;
+        use chrono::{Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        let expires_at = Utc::now() + Duration::hours(1);
+        for (token, client_id) in [("ACCESS_A", "client_a"), ("ACCESS_B", "ferrusgate")] {
+            let access_token_id = backend
+                .save_access_token(
+                    token,
+                    NewAccessToken {
+                        client_id: client_id.to_string(),
+                        user_id: Some(user_id),
+                        scopes: "openid".to_string(),
+                        expires_at,
+                        claims: None,
+                        auth_time: None,
+                    },
+                )
+                .await
+                .expect("Failed to save access token");
+            backend
+                .save_refresh_token(&format!("REFRESH_{}", token), access_token_id, expires_at)
+                .await
+                .expect("Failed to save refresh token");
+        }
+
+        // 2. 登出时删除用户在所有客户端的 Token
+        let revoked = backend
+            .revoke_user_tokens(user_id)
+            .await
+            .expect("Failed to revoke tokens");
+        let mut jwts: Vec<&str> = revoked.jwts().collect();
+        jwts.sort();
+        assert_eq!(
+            jwts,
+            vec![
+                "ACCESS_A",
+                "ACCESS_B",
+                "REFRESH_ACCESS_A",
+                "REFRESH_ACCESS_B"
+            ]
+        );
+        assert!(
+            backend
+                .find_access_token("ACCESS_B")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            backend
+                .find_refresh_token("REFRESH_ACCESS_A")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_tokens() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAccessToken;
+        use chrono::{DateTime, Duration, Utc};
+
+        // 1. 设置：同一客户端的两次登录会话，以及另一个客户端
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        let expires_at = Utc::now() + Duration::hours(1);
+        let first_login = DateTime::from_timestamp(1_700_000_000, 0);
+        let second_login = DateTime::from_timestamp(1_700_000_600, 0);
+        for (token, client_id, auth_time) in [
+            ("ACCESS_1", "client_a", first_login),
+            ("ACCESS_2", "client_a", second_login),
+            ("ACCESS_3", "client_b", first_login),
+        ] {
+            backend
+                .save_access_token(
+                    token,
+                    NewAccessToken {
+                        client_id: client_id.to_string(),
+                        user_id: Some(user_id),
+                        scopes: "openid".to_string(),
+                        expires_at,
+                        claims: None,
+                        auth_time,
+                    },
+                )
+                .await
+                .expect("Failed to save access token");
+        }
+
+        // 2. 只撤销该客户端中同一会话的 Token
+        let revoked = backend
+            .revoke_session_tokens(user_id, "client_a", first_login)
+            .await
+            .expect("Failed to revoke tokens");
+        assert_eq!(revoked.access_tokens.len(), 1);
+        assert_eq!(revoked.access_tokens[0].token, "ACCESS_1");
+        for token in ["ACCESS_2", "ACCESS_3"] {
+            assert!(backend.find_access_token(token).await.unwrap().is_some());
+        }
+
+        // 3. 未提供认证时间时撤销该客户端的全部 Token
+        let revoked = backend
+            .revoke_session_tokens(user_id, "client_a", None)
+            .await
+            .expect("Failed to revoke tokens");
+        assert_eq!(revoked.access_tokens.len(), 1);
+        assert!(
+            backend
+                .find_access_token("ACCESS_3")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_code_pkce_roundtrip() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAuthCode;
+        use chrono::{Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 保存带 PKCE challenge 的授权码
+        let auth_code = NewAuthCode {
+            client_id: "client".to_string(),
+            user_id,
+            redirect_uri: "http://localhost/callback".to_string(),
+            scopes: "openid".to_string(),
+            expires_at: Utc::now() + Duration::minutes(5),
+            code_challenge: Some("challenge".to_string()),
+            code_challenge_method: Some("S256".to_string()),
+            nonce: None,
+            max_age: None,
+            auth_time: None,
+            claims: Some(r#"{"userinfo":{"email":null}}"#.to_string()),
+        };
+        backend
+            .save_auth_code("CODE", auth_code)
+            .await
+            .expect("Failed to save auth code");
+
+        // 3. 消费授权码（应返回 PKCE 参数和 claims 参数）
+        let code = backend
+            .consume_auth_code("CODE")
+            .await
+            .expect("Failed to consume auth code")
+            .expect("Auth code should exist");
+        assert_eq!(code.code_challenge.as_deref(), Some("challenge"));
+        assert_eq!(code.code_challenge_method.as_deref(), Some("S256"));
+        assert_eq!(
+            code.claims.as_deref(),
+            Some(r#"{"userinfo":{"email":null}}"#)
+        );
+
+        // 4. 授权码只能使用一次
+        let reused = backend.consume_auth_code("CODE").await.unwrap();
+        assert!(reused.is_none(), "Auth code should be single-use");
+    }
+
+    #[tokio::test]
+    async fn test_client_access_token_without_user() {
+        use crate::storage::TokenRepository;
+        use crate::storage::repository::NewAccessToken;
+        use chrono::{Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+
+        // 2. client_credentials 签发的 token 不关联用户
+        let id = backend
+            .save_access_token(
+                "CLIENT_ACCESS",
+                NewAccessToken {
+                    client_id: "client".to_string(),
+                    user_id: None,
+                    scopes: "read".to_string(),
+                    expires_at: Utc::now() + Duration::hours(1),
+                    claims: None,
+                    auth_time: None,
+                },
+            )
+            .await
+            .expect("Access token without user should be saved");
+        assert!(id > 0);
+
+        // 3. 可按 token 查找（用于 introspection）
+        let stored = backend
+            .find_access_token("CLIENT_ACCESS")
+            .await
+            .unwrap()
+            .expect("Access token should be found");
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.user_id, None);
+        assert!(
+            backend
+                .find_access_token("MISSING")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_device_code_lifecycle() {
+        use crate::storage::{DEVICE_CODE_APPROVED, DEVICE_CODE_PENDING};
+        use chrono::{DateTime, Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 创建设备授权
+        let record = backend
+            .create_device_code(
+                "DEVICE",
+                "BCDF-GHJK",
+                "client",
+                "openid",
+                5,
+                Utc::now() + Duration::minutes(10),
+            )
+            .await
+            .expect("Failed to create device code");
+        assert_eq!(record.status, DEVICE_CODE_PENDING);
+
+        let found = backend
+            .find_device_code_by_user_code("BCDF-GHJK")
+            .await
+            .unwrap()
+            .expect("Device code should be found by user_code");
+        assert_eq!(found.device_code, "DEVICE");
+
+        // 3. 用户批准（只能处理一次），记录批准会话的认证时间
+        let auth_time = DateTime::from_timestamp(1_700_000_000, 0);
+        assert!(
+            backend
+                .resolve_device_code(record.id, user_id, true, auth_time)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !backend
+                .resolve_device_code(record.id, user_id, false, None)
+                .await
+                .unwrap()
+        );
+
+        // 4. 记录轮询
+        backend.record_device_poll(record.id, 10).await.unwrap();
+        let polled = backend.find_device_code("DEVICE").await.unwrap().unwrap();
+        assert_eq!(polled.status, DEVICE_CODE_APPROVED);
+        assert_eq!(polled.user_id, Some(user_id));
+        assert_eq!(
+            polled.auth_time.map(|at| at.timestamp()),
+            Some(1_700_000_000)
+        );
+        assert_eq!(polled.interval, 10);
+        assert!(polled.last_polled_at.is_some());
+
+        // 5. 删除后不能再次使用
+        assert!(backend.delete_device_code(record.id).await.unwrap());
+        assert!(!backend.delete_device_code(record.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_signing_key_rotation() {
+        use crate::storage::{
+            NewSigningKey, SIGNING_KEY_ACTIVE, SIGNING_KEY_NEXT, SIGNING_KEY_RETIRED,
+        };
+        use chrono::{Duration, Utc};
+
+        let new_key = |kid| NewSigningKey {
+            kid,
+            algorithm: "RS256",
+            private_key: "PEM",
+        };
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+
+        // 2. 没有 next 密钥时无法轮换
+        assert!(backend.rotate_signing_keys(new_key("k2")).await.is_err());
+
+        backend
+            .create_signing_key(new_key("k1"), SIGNING_KEY_ACTIVE)
+            .await
+            .expect("Failed to create active key");
+        backend
+            .create_signing_key(new_key("k2"), SIGNING_KEY_NEXT)
+            .await
+            .expect("Failed to create next key");
+
+        // 3. 轮换：k1 退役，k2 启用，k3 成为 next
+        let (retired, activated) = backend
+            .rotate_signing_keys(new_key("k3"))
+            .await
+            .expect("Failed to rotate keys");
+        assert_eq!(retired.map(|key| key.kid).as_deref(), Some("k1"));
+        assert_eq!(activated.kid, "k2");
+        assert!(activated.activated_at.is_some());
+
+        let keys = backend
+            .list_signing_keys(Utc::now() - Duration::hours(1))
+            .await
+            .unwrap();
+        let status = |kid: &str| {
+            keys.iter()
+                .find(|key| key.kid == kid)
+                .map(|key| key.status.clone())
+        };
+        assert_eq!(status("k1").as_deref(), Some(SIGNING_KEY_RETIRED));
+        assert_eq!(status("k2").as_deref(), Some(SIGNING_KEY_ACTIVE));
+        assert_eq!(status("k3").as_deref(), Some(SIGNING_KEY_NEXT));
+
+        // 4. 重叠期结束后退役密钥不再列出，并可被清理
+        let keys = backend
+            .list_signing_keys(Utc::now() + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 2);
+
+        let deleted = backend
+            .delete_retired_signing_keys(Utc::now() + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_consent_lifecycle() {
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        assert!(
+            backend
+                .find_consent(user_id, "client")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // 2. 保存同意记录
+        let first = backend
+            .save_consent(user_id, "client", "openid")
+            .await
+            .expect("Failed to save consent");
+
+        // 3. 再次保存时更新同一条记录
+        let updated = backend
+            .save_consent(user_id, "client", "openid profile")
+            .await
+            .expect("Failed to update consent");
+        assert_eq!(updated.id, first.id);
+        assert_eq!(updated.scopes, "openid profile");
+
+        // 4. 删除
+        assert!(backend.delete_consent(user_id, "client").await.unwrap());
+        assert!(!backend.delete_consent(user_id, "client").await.unwrap());
+        assert!(
+            backend
+                .find_consent(user_id, "client")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pairwise_subjects() {
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 保存扇区中的标识
+        let saved = backend
+            .save_pairwise_subject("app.example.com", user_id, "SUBJECT_A")
+            .await
+            .expect("Failed to save pairwise subject");
+        assert_eq!(saved.subject, "SUBJECT_A");
+
+        // 3. 已存在时保持原有标识不变
+        let again = backend
+            .save_pairwise_subject("app.example.com", user_id, "SUBJECT_B")
+            .await
+            .unwrap();
+        assert_eq!(again.id, saved.id);
+        assert_eq!(again.subject, "SUBJECT_A");
+
+        // 4. 按标识映射回用户
+        let found = backend
+            .find_pairwise_subject_by_sub("SUBJECT_A")
+            .await
+            .unwrap()
+            .expect("Pairwise subject not found");
+        assert_eq!(found.user_id, user_id);
+        assert!(
+            backend
+                .find_pairwise_subject_by_sub("SUBJECT_B")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // 5. 不同扇区使用各自的标识
+        let other = backend
+            .save_pairwise_subject("other.example.com", user_id, "SUBJECT_C")
+            .await
+            .unwrap();
+        assert_ne!(other.id, saved.id);
+    }
+
+    #[tokio::test]
+    async fn test_user_profile_fields() {
+        use crate::storage::UserRepository;
+        use crate::storage::repository::UserUpdateFields;
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 新用户没有资料，邮箱和手机号未验证
+        let user = backend.find_by_id(user_id).await.unwrap().unwrap();
+        assert!(user.given_name.is_none());
+        assert!(!user.email_verified);
+        assert!(!user.phone_number_verified);
+
+        // 3. 更新资料
+        let user = backend
+            .update_user(
+                user_id,
+                UserUpdateFields {
+                    given_name: Some(Some("San".to_string())),
+                    phone_number: Some(Some("+8613800000000".to_string())),
+                    address: Some(Some(r#"{"country":"CN"}"#.to_string())),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("Failed to update profile");
+        assert_eq!(user.given_name.as_deref(), Some("San"));
+        assert_eq!(user.phone_number.as_deref(), Some("+8613800000000"));
+        assert_eq!(user.address.as_deref(), Some(r#"{"country":"CN"}"#));
+
+        // 4. 未提供的字段保持不变，Some(None) 清空字段
+        let user = backend
+            .update_user(
+                user_id,
+                UserUpdateFields {
+                    given_name: Some(None),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(user.given_name.is_none());
+        assert_eq!(user.phone_number.as_deref(), Some("+8613800000000"));
+    }
+
+    #[tokio::test]
+    async fn test_client_lifecycle() {
+        use crate::storage::repository::NewAccessToken;
+        use crate::storage::{ClientRepository, ClientUpdateFields, NewClient, TokenRepository};
+        use chrono::{Duration, Utc};
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let backend = SeaOrmBackend::new(db);
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 创建客户端
+        let client = backend
+            .create_client(NewClient {
+                client_id: "managed".to_string(),
+                client_secret: "hash".to_string(),
+                name: "Managed".to_string(),
+                redirect_uris: r#"["https://app.example/cb"]"#.to_string(),
+                allowed_scopes: r#"["openid"]"#.to_string(),
+                require_pkce: false,
+                token_endpoint_auth_method: "client_secret_basic".to_string(),
+                jwks: None,
+                jwks_uri: None,
+                grant_types: None,
+                registration_access_token: None,
+                post_logout_redirect_uris: None,
+                backchannel_logout_uri: None,
+                response_modes: None,
+                require_pushed_authorization_requests: false,
+                require_signed_request_object: false,
+                token_exchange_policy: None,
+                subject_type: "public".to_string(),
+                sector_identifier_uri: None,
+            })
+            .await
+            .expect("Failed to create client");
+        assert_eq!(backend.list_clients().await.unwrap().len(), 1);
+
+        // 3. 部分更新
+        let updated = backend
+            .update_client(
+                client,
+                ClientUpdateFields {
+                    name: Some("Renamed".to_string()),
+                    jwks_uri: Some(Some("https://app.example/jwks".to_string())),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("Failed to update client");
+        assert_eq!(updated.name, "Renamed");
+        assert_eq!(updated.allowed_scopes, r#"["openid"]"#);
+        assert_eq!(
+            updated.jwks_uri.as_deref(),
+            Some("https://app.example/jwks")
+        );
+
+        // 4. 删除客户端时一并删除其 Token 和同意记录
+        backend
+            .save_access_token(
+                "MANAGED_ACCESS",
+                NewAccessToken {
+                    client_id: "managed".to_string(),
+                    user_id: Some(user_id),
+                    scopes: "openid".to_string(),
+                    expires_at: Utc::now() + Duration::hours(1),
+                    claims: None,
+                    auth_time: None,
+                },
+            )
+            .await
+            .unwrap();
+        backend
+            .save_consent(user_id, "managed", "openid")
+            .await
+            .unwrap();
+
+        let revoked = backend
+            .delete_client("managed")
+            .await
+            .expect("Failed to delete client");
+        assert_eq!(revoked, vec!["MANAGED_ACCESS".to_string()]);
+        assert!(
+            backend
+                .find_by_client_id("managed")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            backend
+                .find_access_token("MANAGED_ACCESS")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            backend
+                .find_consent(user_id, "managed")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_registration_settings() {
+        use crate::config::ClientRegistrationConfig;
+
+        // 1. 设置
+        let db = setup_test_db().await;
+        let cache = create_test_cache();
+        let backend = SeaOrmBackend::with_cache(db, cache.clone());
+        let user_id = create_test_user(&backend).await;
+
+        // 2. 默认不开放注册，也没有初始访问令牌
+        let config = backend.get_client_registration_config().await.unwrap();
+        assert!(!config.allow_open_registration);
+        assert!(
+            backend
+                .get_initial_access_token_hash()
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // 3. 开放注册后缓存失效
+        backend
+            .update_client_registration_config(
+                &ClientRegistrationConfig {
+                    allow_open_registration: true,
+                },
+                user_id,
+            )
+            .await
+            .expect("Failed to update config");
+        assert!(cache.get("config:client_registration").await.is_none());
+        assert!(
+            backend
+                .get_client_registration_config()
+                .await
+                .unwrap()
+                .allow_open_registration
+        );
+
+        // 4. 设置初始访问令牌
+        backend
+            .set_initial_access_token_hash("hash", user_id)
+            .await
+            .expect("Failed to set initial access token");
+        assert_eq!(
+            backend
+                .get_initial_access_token_hash()
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("hash")
+        );
+    }
 }
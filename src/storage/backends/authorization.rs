@@ -1,8 +1,9 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sea_orm::*;
 
 use crate::errors::AppError;
 use crate::storage::entities::{access_tokens, o_auth_clients, refresh_tokens};
+use crate::storage::repository::FIRST_PARTY_CLIENT_ID;
 
 use super::super::backend::SeaOrmBackend;
 
@@ -15,6 +16,23 @@ pub struct UserAuthorizationInfo {
     pub granted_at: sea_orm::prelude::DateTimeWithTimeZone,
 }
 
+/// 被撤销的 Token：access_token 记录和关联的 refresh_token
+#[derive(Debug, Default)]
+pub struct RevokedTokens {
+    pub access_tokens: Vec<access_tokens::Model>,
+    pub refresh_tokens: Vec<String>,
+}
+
+impl RevokedTokens {
+    /// 所有被撤销的 JWT（用于加入黑名单）
+    pub fn jwts(&self) -> impl Iterator<Item = &str> {
+        self.access_tokens
+            .iter()
+            .map(|token| token.token.as_str())
+            .chain(self.refresh_tokens.iter().map(String::as_str))
+    }
+}
+
 // 用户授权管理方法
 impl SeaOrmBackend {
     /// 获取用户的授权列表
@@ -22,9 +40,10 @@ impl SeaOrmBackend {
         &self,
         user_id: i64,
     ) -> Result<Vec<UserAuthorizationInfo>, AppError> {
-        // 查询用户的所有有效 access_tokens
+        // 查询用户的所有有效 access_tokens（第一方登录不属于第三方授权）
         let tokens = access_tokens::Entity::find()
             .filter(access_tokens::Column::UserId.eq(user_id))
+            .filter(access_tokens::Column::ClientId.ne(FIRST_PARTY_CLIENT_ID))
             .filter(access_tokens::Column::ExpiresAt.gt(Utc::now()))
             .all(self.db.as_ref())
             .await?;
@@ -82,32 +101,102 @@ impl SeaOrmBackend {
     }
 
     /// 撤销用户对某个应用的授权
+    ///
+    /// 返回被删除的 access_token 和 refresh_token，调用方负责将其加入黑名单
     pub async fn revoke_user_authorization(
         &self,
         user_id: i64,
         client_id: &str,
-    ) -> Result<(), AppError> {
+    ) -> Result<Vec<String>, AppError> {
+        let txn = self.db.begin().await?;
+
         // 1. 查找该用户对该应用的所有 access_tokens
         let tokens = access_tokens::Entity::find()
             .filter(access_tokens::Column::UserId.eq(user_id))
             .filter(access_tokens::Column::ClientId.eq(client_id))
-            .all(self.db.as_ref())
+            .all(&txn)
             .await?;
 
         // 2. 删除相关的 refresh_tokens 和 access_tokens
-        for token in tokens {
-            // 删除关联的 refresh_tokens
-            refresh_tokens::Entity::delete_many()
-                .filter(refresh_tokens::Column::AccessTokenId.eq(token.id))
-                .exec(self.db.as_ref())
-                .await?;
+        let revoked = delete_tokens(&txn, tokens).await?;
 
-            // 删除 access_token
-            access_tokens::Entity::delete_by_id(token.id)
-                .exec(self.db.as_ref())
-                .await?;
+        txn.commit().await?;
+        Ok(revoked.jwts().map(str::to_string).collect())
+    }
+
+    /// 撤销用户在客户端的某次登录会话中获得的 Token（RP 发起的登出）
+    ///
+    /// 会话由认证时间区分，未提供时撤销该用户在客户端的全部 Token。
+    /// 返回被删除的 access_token 记录和 refresh_token
+    pub async fn revoke_session_tokens(
+        &self,
+        user_id: i64,
+        client_id: &str,
+        auth_time: Option<DateTime<Utc>>,
+    ) -> Result<RevokedTokens, AppError> {
+        let txn = self.db.begin().await?;
+
+        // 1. 查找会话的 access_tokens
+        let mut query = access_tokens::Entity::find()
+            .filter(access_tokens::Column::UserId.eq(user_id))
+            .filter(access_tokens::Column::ClientId.eq(client_id));
+        if let Some(auth_time) = auth_time {
+            query = query.filter(access_tokens::Column::AuthTime.eq(auth_time));
         }
+        let tokens = query.all(&txn).await?;
 
-        Ok(())
+        // 2. 删除 refresh_tokens 和 access_tokens
+        let revoked = delete_tokens(&txn, tokens).await?;
+
+        txn.commit().await?;
+        Ok(revoked)
     }
+
+    /// 撤销用户的所有 Token（登出时使用，包括第一方登录签发的 Token）
+    ///
+    /// 返回被删除的 access_token 记录和 refresh_token，调用方负责将其加入黑名单并通知相关客户端
+    pub async fn revoke_user_tokens(&self, user_id: i64) -> Result<RevokedTokens, AppError> {
+        let txn = self.db.begin().await?;
+
+        // 1. 查找用户的所有 access_tokens
+        let tokens = access_tokens::Entity::find()
+            .filter(access_tokens::Column::UserId.eq(user_id))
+            .all(&txn)
+            .await?;
+
+        // 2. 删除 refresh_tokens 和 access_tokens
+        let revoked = delete_tokens(&txn, tokens).await?;
+
+        txn.commit().await?;
+        Ok(revoked)
+    }
+}
+
+/// 删除 access_tokens 及其 refresh_tokens（refresh_tokens 依赖 access_tokens）
+async fn delete_tokens(
+    txn: &DatabaseTransaction,
+    tokens: Vec<access_tokens::Model>,
+) -> Result<RevokedTokens, AppError> {
+    let token_ids: Vec<i64> = tokens.iter().map(|token| token.id).collect();
+    let refresh_tokens = refresh_tokens::Entity::find()
+        .filter(refresh_tokens::Column::AccessTokenId.is_in(token_ids.clone()))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|token| token.token)
+        .collect();
+
+    refresh_tokens::Entity::delete_many()
+        .filter(refresh_tokens::Column::AccessTokenId.is_in(token_ids.clone()))
+        .exec(txn)
+        .await?;
+    access_tokens::Entity::delete_many()
+        .filter(access_tokens::Column::Id.is_in(token_ids))
+        .exec(txn)
+        .await?;
+
+    Ok(RevokedTokens {
+        access_tokens: tokens,
+        refresh_tokens,
+    })
 }
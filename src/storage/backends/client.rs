@@ -0,0 +1,255 @@
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::errors::AppError;
+use crate::storage::entities::{
+    access_tokens, authorization_codes, consents, device_codes, o_auth_clients, refresh_tokens,
+};
+
+use super::super::backend::SeaOrmBackend;
+
+/// 新客户端数据（client_secret 为哈希后的密钥，公共客户端为空字符串）
+#[derive(Debug, Clone)]
+pub struct NewClient {
+    pub client_id: String,
+    pub client_secret: String,
+    pub name: String,
+    pub redirect_uris: String,
+    pub allowed_scopes: String,
+    pub require_pkce: bool,
+    pub token_endpoint_auth_method: String,
+    pub jwks: Option<String>,
+    pub jwks_uri: Option<String>,
+    pub grant_types: Option<String>,
+    pub registration_access_token: Option<String>,
+    pub post_logout_redirect_uris: Option<String>,
+    pub backchannel_logout_uri: Option<String>,
+    pub response_modes: Option<String>,
+    pub require_pushed_authorization_requests: bool,
+    pub require_signed_request_object: bool,
+    pub token_exchange_policy: Option<String>,
+    pub subject_type: String,
+    pub sector_identifier_uri: Option<String>,
+}
+
+/// 客户端更新字段（部分更新）
+#[derive(Debug, Clone, Default)]
+pub struct ClientUpdateFields {
+    pub name: Option<String>,
+    pub redirect_uris: Option<String>,
+    pub allowed_scopes: Option<String>,
+    pub require_pkce: Option<bool>,
+    pub token_endpoint_auth_method: Option<String>,
+    pub jwks: Option<Option<String>>,
+    pub jwks_uri: Option<Option<String>>,
+    pub grant_types: Option<Option<String>>,
+    pub post_logout_redirect_uris: Option<Option<String>>,
+    pub backchannel_logout_uri: Option<Option<String>>,
+    pub response_modes: Option<Option<String>>,
+    pub require_pushed_authorization_requests: Option<bool>,
+    pub require_signed_request_object: Option<bool>,
+    pub token_exchange_policy: Option<Option<String>>,
+    pub subject_type: Option<String>,
+    pub sector_identifier_uri: Option<Option<String>>,
+}
+
+// OAuth 客户端管理方法
+impl SeaOrmBackend {
+    /// 列出所有客户端
+    pub async fn list_clients(&self) -> Result<Vec<o_auth_clients::Model>, AppError> {
+        let clients = o_auth_clients::Entity::find()
+            .order_by_asc(o_auth_clients::Column::Id)
+            .all(self.db.as_ref())
+            .await?;
+        Ok(clients)
+    }
+
+    /// 创建客户端
+    pub async fn create_client(
+        &self,
+        client: NewClient,
+    ) -> Result<o_auth_clients::Model, AppError> {
+        let client =
+            o_auth_clients::ActiveModel {
+                client_id: Set(client.client_id),
+                client_secret: Set(client.client_secret),
+                name: Set(client.name),
+                redirect_uris: Set(client.redirect_uris),
+                allowed_scopes: Set(client.allowed_scopes),
+                created_at: Set(Utc::now().into()),
+                require_pkce: Set(client.require_pkce),
+                token_endpoint_auth_method: Set(client.token_endpoint_auth_method),
+                jwks: Set(client.jwks),
+                jwks_uri: Set(client.jwks_uri),
+                grant_types: Set(client.grant_types),
+                registration_access_token: Set(client.registration_access_token),
+                post_logout_redirect_uris: Set(client.post_logout_redirect_uris),
+                backchannel_logout_uri: Set(client.backchannel_logout_uri),
+                response_modes: Set(client.response_modes),
+                require_pushed_authorization_requests: Set(
+                    client.require_pushed_authorization_requests
+                ),
+                require_signed_request_object: Set(client.require_signed_request_object),
+                token_exchange_policy: Set(client.token_exchange_policy),
+                subject_type: Set(client.subject_type),
+                sector_identifier_uri: Set(client.sector_identifier_uri),
+                ..Default::default()
+            }
+            .insert(self.db.as_ref())
+            .await?;
+        Ok(client)
+    }
+
+    /// 更新客户端信息
+    pub async fn update_client(
+        &self,
+        client: o_auth_clients::Model,
+        fields: ClientUpdateFields,
+    ) -> Result<o_auth_clients::Model, AppError> {
+        let mut client: o_auth_clients::ActiveModel = client.into();
+
+        if let Some(name) = fields.name {
+            client.name = Set(name);
+        }
+        if let Some(redirect_uris) = fields.redirect_uris {
+            client.redirect_uris = Set(redirect_uris);
+        }
+        if let Some(allowed_scopes) = fields.allowed_scopes {
+            client.allowed_scopes = Set(allowed_scopes);
+        }
+        if let Some(require_pkce) = fields.require_pkce {
+            client.require_pkce = Set(require_pkce);
+        }
+        if let Some(method) = fields.token_endpoint_auth_method {
+            client.token_endpoint_auth_method = Set(method);
+        }
+        if let Some(jwks) = fields.jwks {
+            client.jwks = Set(jwks);
+        }
+        if let Some(jwks_uri) = fields.jwks_uri {
+            client.jwks_uri = Set(jwks_uri);
+        }
+        if let Some(grant_types) = fields.grant_types {
+            client.grant_types = Set(grant_types);
+        }
+        if let Some(uris) = fields.post_logout_redirect_uris {
+            client.post_logout_redirect_uris = Set(uris);
+        }
+        if let Some(uri) = fields.backchannel_logout_uri {
+            client.backchannel_logout_uri = Set(uri);
+        }
+        if let Some(response_modes) = fields.response_modes {
+            client.response_modes = Set(response_modes);
+        }
+        if let Some(require_par) = fields.require_pushed_authorization_requests {
+            client.require_pushed_authorization_requests = Set(require_par);
+        }
+        if let Some(require_signed) = fields.require_signed_request_object {
+            client.require_signed_request_object = Set(require_signed);
+        }
+        if let Some(policy) = fields.token_exchange_policy {
+            client.token_exchange_policy = Set(policy);
+        }
+        if let Some(subject_type) = fields.subject_type {
+            client.subject_type = Set(subject_type);
+        }
+        if let Some(uri) = fields.sector_identifier_uri {
+            client.sector_identifier_uri = Set(uri);
+        }
+
+        let client = client.update(self.db.as_ref()).await?;
+        Ok(client)
+    }
+
+    /// 更新客户端密钥（参数为哈希后的密钥）
+    pub async fn update_client_secret(
+        &self,
+        client_id: &str,
+        secret_hash: &str,
+    ) -> Result<(), AppError> {
+        o_auth_clients::Entity::update_many()
+            .col_expr(
+                o_auth_clients::Column::ClientSecret,
+                Expr::value(secret_hash),
+            )
+            .filter(o_auth_clients::Column::ClientId.eq(client_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    /// 撤销客户端签发的所有 access token 和 refresh token
+    ///
+    /// 返回被删除的 Token，调用方负责将其加入黑名单
+    pub async fn revoke_client_tokens(&self, client_id: &str) -> Result<Vec<String>, AppError> {
+        let txn = self.db.begin().await?;
+        let tokens = delete_client_tokens(&txn, client_id).await?;
+        txn.commit().await?;
+        Ok(tokens)
+    }
+
+    /// 删除客户端及其授权码、Token、同意记录和设备授权请求
+    ///
+    /// 返回被删除的 access token 和 refresh token，调用方负责将其加入黑名单
+    pub async fn delete_client(&self, client_id: &str) -> Result<Vec<String>, AppError> {
+        let txn = self.db.begin().await?;
+
+        // 1. 删除 Token
+        let tokens = delete_client_tokens(&txn, client_id).await?;
+
+        // 2. 删除授权码、同意记录和设备授权请求
+        authorization_codes::Entity::delete_many()
+            .filter(authorization_codes::Column::ClientId.eq(client_id))
+            .exec(&txn)
+            .await?;
+        consents::Entity::delete_many()
+            .filter(consents::Column::ClientId.eq(client_id))
+            .exec(&txn)
+            .await?;
+        device_codes::Entity::delete_many()
+            .filter(device_codes::Column::ClientId.eq(client_id))
+            .exec(&txn)
+            .await?;
+
+        // 3. 删除客户端
+        o_auth_clients::Entity::delete_many()
+            .filter(o_auth_clients::Column::ClientId.eq(client_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(tokens)
+    }
+}
+
+/// 删除客户端的 Token（refresh_tokens 依赖 access_tokens），返回被删除的 Token
+async fn delete_client_tokens(
+    txn: &DatabaseTransaction,
+    client_id: &str,
+) -> Result<Vec<String>, AppError> {
+    let access = access_tokens::Entity::find()
+        .filter(access_tokens::Column::ClientId.eq(client_id))
+        .all(txn)
+        .await?;
+    let token_ids: Vec<i64> = access.iter().map(|token| token.id).collect();
+    let refresh = refresh_tokens::Entity::find()
+        .filter(refresh_tokens::Column::AccessTokenId.is_in(token_ids.clone()))
+        .all(txn)
+        .await?;
+
+    refresh_tokens::Entity::delete_many()
+        .filter(refresh_tokens::Column::AccessTokenId.is_in(token_ids))
+        .exec(txn)
+        .await?;
+    access_tokens::Entity::delete_many()
+        .filter(access_tokens::Column::ClientId.eq(client_id))
+        .exec(txn)
+        .await?;
+
+    Ok(access
+        .into_iter()
+        .map(|token| token.token)
+        .chain(refresh.into_iter().map(|token| token.token))
+        .collect())
+}
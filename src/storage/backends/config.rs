@@ -452,4 +452,140 @@ impl SeaOrmBackend {
 
         Ok(())
     }
+
+    /// 获取动态客户端注册配置
+    pub async fn get_client_registration_config(
+        &self,
+    ) -> Result<crate::config::ClientRegistrationConfig, AppError> {
+        use crate::config::ClientRegistrationConfig;
+
+        const CACHE_KEY: &str = "config:client_registration";
+        const CACHE_TTL: u64 = 300; // 5 分钟
+
+        // 1. 尝试从缓存读取
+        if let Some(cache) = &self.cache
+            && let Some(cached) = cache.get(CACHE_KEY).await
+            && let Ok(config) = serde_json::from_str::<ClientRegistrationConfig>(&cached)
+        {
+            tracing::debug!("Client registration config loaded from cache");
+            return Ok(config);
+        }
+
+        // 2. 从数据库读取
+        let mut config = ClientRegistrationConfig::default();
+
+        if let Some((_, _, _, Some(v))) = self.get_setting("allow_open_client_registration").await?
+        {
+            config.allow_open_registration = v;
+        }
+
+        // 3. 写入缓存
+        if let Some(cache) = &self.cache
+            && let Ok(json) = serde_json::to_string(&config)
+        {
+            cache.set(CACHE_KEY, json, Some(CACHE_TTL)).await;
+            tracing::debug!("Client registration config cached");
+        }
+
+        Ok(config)
+    }
+
+    /// 更新动态客户端注册配置
+    pub async fn update_client_registration_config(
+        &self,
+        config: &crate::config::ClientRegistrationConfig,
+        updated_by: i64,
+    ) -> Result<(), AppError> {
+        // 获取旧配置用于审计日志
+        let old_config = self.get_client_registration_config().await?;
+
+        self.set_setting(
+            "allow_open_client_registration",
+            "bool",
+            None,
+            None,
+            Some(config.allow_open_registration),
+            Some(updated_by),
+        )
+        .await?;
+
+        // 记录审计日志
+        let old_json = serde_json::to_string(&old_config).unwrap_or_default();
+        let new_json = serde_json::to_string(&config).unwrap_or_default();
+        self.log_config_change(
+            "client_registration_config",
+            Some(old_json),
+            Some(new_json),
+            updated_by,
+            "update",
+        )
+        .await?;
+
+        // 清除缓存
+        if let Some(cache) = &self.cache {
+            cache.delete("config:client_registration").await;
+            tracing::debug!("Client registration config cache invalidated");
+        }
+
+        Ok(())
+    }
+
+    /// 获取动态客户端注册的初始访问令牌（哈希）
+    pub async fn get_initial_access_token_hash(&self) -> Result<Option<String>, AppError> {
+        Ok(self
+            .get_setting("client_registration_initial_token")
+            .await?
+            .and_then(|(_, value, _, _)| value)
+            .filter(|value| !value.is_empty()))
+    }
+
+    /// 更新动态客户端注册的初始访问令牌（参数为哈希后的令牌，旧令牌立即失效）
+    pub async fn set_initial_access_token_hash(
+        &self,
+        token_hash: &str,
+        updated_by: i64,
+    ) -> Result<(), AppError> {
+        self.set_setting(
+            "client_registration_initial_token",
+            "string",
+            Some(token_hash),
+            None,
+            None,
+            Some(updated_by),
+        )
+        .await?;
+
+        // 记录审计日志（不记录令牌本身）
+        self.log_config_change(
+            "client_registration_initial_token",
+            None,
+            None,
+            updated_by,
+            "rotate",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// 获取 HS256 共享密钥签发的 Token 的验证截止时间戳（切换到非对称密钥时记录）
+    pub async fn get_legacy_token_deadline(&self) -> Result<Option<i64>, AppError> {
+        Ok(self
+            .get_setting("legacy_token_deadline")
+            .await?
+            .and_then(|(_, _, value, _)| value))
+    }
+
+    /// 记录 HS256 共享密钥签发的 Token 的验证截止时间戳
+    pub async fn set_legacy_token_deadline(&self, deadline: i64) -> Result<(), AppError> {
+        self.set_setting(
+            "legacy_token_deadline",
+            "int",
+            None,
+            Some(deadline),
+            None,
+            None,
+        )
+        .await
+    }
 }
@@ -0,0 +1,67 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::errors::AppError;
+use crate::storage::entities::consents;
+
+use super::super::backend::SeaOrmBackend;
+
+// 用户授权同意记录管理方法
+impl SeaOrmBackend {
+    /// 查找用户对客户端的同意记录
+    pub async fn find_consent(
+        &self,
+        user_id: i64,
+        client_id: &str,
+    ) -> Result<Option<consents::Model>, AppError> {
+        let consent = consents::Entity::find()
+            .filter(consents::Column::UserId.eq(user_id))
+            .filter(consents::Column::ClientId.eq(client_id))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(consent)
+    }
+
+    /// 保存用户已同意的 scope（覆盖原有记录）
+    pub async fn save_consent(
+        &self,
+        user_id: i64,
+        client_id: &str,
+        scopes: &str,
+    ) -> Result<consents::Model, AppError> {
+        let now = Utc::now();
+
+        let consent = match self.find_consent(user_id, client_id).await? {
+            Some(existing) => {
+                let mut consent: consents::ActiveModel = existing.into();
+                consent.scopes = Set(scopes.to_string());
+                consent.updated_at = Set(now.into());
+                consent.update(self.db.as_ref()).await?
+            }
+            None => {
+                consents::ActiveModel {
+                    user_id: Set(user_id),
+                    client_id: Set(client_id.to_string()),
+                    scopes: Set(scopes.to_string()),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                    ..Default::default()
+                }
+                .insert(self.db.as_ref())
+                .await?
+            }
+        };
+
+        Ok(consent)
+    }
+
+    /// 删除用户对客户端的同意记录（返回是否确实删除了记录）
+    pub async fn delete_consent(&self, user_id: i64, client_id: &str) -> Result<bool, AppError> {
+        let result = consents::Entity::delete_many()
+            .filter(consents::Column::UserId.eq(user_id))
+            .filter(consents::Column::ClientId.eq(client_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::errors::AppError;
+use crate::storage::entities::device_codes;
+
+use super::super::backend::SeaOrmBackend;
+
+/// 设备授权状态
+pub const DEVICE_CODE_PENDING: &str = "pending";
+pub const DEVICE_CODE_APPROVED: &str = "approved";
+pub const DEVICE_CODE_DENIED: &str = "denied";
+
+// 设备授权（RFC 8628）管理方法
+impl SeaOrmBackend {
+    /// 创建设备授权请求
+    pub async fn create_device_code(
+        &self,
+        device_code: &str,
+        user_code: &str,
+        client_id: &str,
+        scopes: &str,
+        interval: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<device_codes::Model, AppError> {
+        let record = device_codes::ActiveModel {
+            device_code: Set(device_code.to_string()),
+            user_code: Set(user_code.to_string()),
+            client_id: Set(client_id.to_string()),
+            scopes: Set(scopes.to_string()),
+            user_id: Set(None),
+            status: Set(DEVICE_CODE_PENDING.to_string()),
+            interval: Set(interval),
+            last_polled_at: Set(None),
+            expires_at: Set(expires_at.into()),
+            created_at: Set(Utc::now().into()),
+            auth_time: Set(None),
+            ..Default::default()
+        };
+
+        let result = record.insert(self.db.as_ref()).await?;
+        Ok(result)
+    }
+
+    /// 通过 device_code 查找（设备轮询）
+    pub async fn find_device_code(
+        &self,
+        device_code: &str,
+    ) -> Result<Option<device_codes::Model>, AppError> {
+        let record = device_codes::Entity::find()
+            .filter(device_codes::Column::DeviceCode.eq(device_code))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(record)
+    }
+
+    /// 通过 user_code 查找（用户验证页面）
+    pub async fn find_device_code_by_user_code(
+        &self,
+        user_code: &str,
+    ) -> Result<Option<device_codes::Model>, AppError> {
+        let record = device_codes::Entity::find()
+            .filter(device_codes::Column::UserCode.eq(user_code))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(record)
+    }
+
+    /// 用户批准或拒绝设备授权（只能处理一次，返回是否成功）
+    ///
+    /// auth_time 为批准时所用会话的认证时间，签发 Token 时作为 ID Token 的 auth_time
+    pub async fn resolve_device_code(
+        &self,
+        id: i64,
+        user_id: i64,
+        approved: bool,
+        auth_time: Option<DateTime<Utc>>,
+    ) -> Result<bool, AppError> {
+        let status = if approved {
+            DEVICE_CODE_APPROVED
+        } else {
+            DEVICE_CODE_DENIED
+        };
+
+        let result = device_codes::Entity::update_many()
+            .col_expr(device_codes::Column::Status, Expr::value(status))
+            .col_expr(device_codes::Column::UserId, Expr::value(user_id))
+            .col_expr(
+                device_codes::Column::AuthTime,
+                Expr::value(auth_time.map(DateTimeWithTimeZone::from)),
+            )
+            .filter(device_codes::Column::Id.eq(id))
+            .filter(device_codes::Column::Status.eq(DEVICE_CODE_PENDING))
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 记录设备轮询时间及新的轮询间隔
+    pub async fn record_device_poll(&self, id: i64, interval: i64) -> Result<(), AppError> {
+        let now: DateTimeWithTimeZone = Utc::now().into();
+
+        device_codes::Entity::update_many()
+            .col_expr(device_codes::Column::LastPolledAt, Expr::value(now))
+            .col_expr(device_codes::Column::Interval, Expr::value(interval))
+            .filter(device_codes::Column::Id.eq(id))
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// 清理已过期的设备授权
+    pub async fn delete_expired_device_codes(&self) -> Result<u64, AppError> {
+        let now: DateTimeWithTimeZone = Utc::now().into();
+
+        let result = device_codes::Entity::delete_many()
+            .filter(device_codes::Column::ExpiresAt.lt(now))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 删除设备授权（返回是否确实删除了记录）
+    pub async fn delete_device_code(&self, id: i64) -> Result<bool, AppError> {
+        let result = device_codes::Entity::delete_by_id(id)
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -1,11 +1,19 @@
 // 子模块
 mod audit;
 mod authorization;
+mod client;
 mod config;
+mod consent;
+mod device;
 mod invite;
 mod oauth;
+mod pairwise;
+mod signing_key;
 mod user;
 
 // 重新导出公共结构体
-pub use authorization::UserAuthorizationInfo;
+pub use authorization::{RevokedTokens, UserAuthorizationInfo};
+pub use client::{ClientUpdateFields, NewClient};
+pub use device::{DEVICE_CODE_APPROVED, DEVICE_CODE_DENIED, DEVICE_CODE_PENDING};
 pub use invite::InviteStats;
+pub use signing_key::{NewSigningKey, SIGNING_KEY_ACTIVE, SIGNING_KEY_NEXT, SIGNING_KEY_RETIRED};
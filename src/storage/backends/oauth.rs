@@ -4,7 +4,7 @@ use sea_orm::*;
 
 use crate::errors::AppError;
 use crate::storage::entities::{prelude::*, *};
-use crate::storage::repository::{ClientRepository, TokenRepository};
+use crate::storage::repository::{ClientRepository, NewAccessToken, NewAuthCode, TokenRepository};
 
 use super::super::backend::SeaOrmBackend;
 
@@ -40,24 +40,22 @@ impl ClientRepository for SeaOrmBackend {
 
 #[async_trait]
 impl TokenRepository for SeaOrmBackend {
-    async fn save_auth_code(
-        &self,
-        code: &str,
-        client_id: &str,
-        user_id: i64,
-        redirect_uri: &str,
-        scopes: &str,
-        expires_at: chrono::DateTime<Utc>,
-    ) -> Result<(), AppError> {
+    async fn save_auth_code(&self, code: &str, auth_code: NewAuthCode) -> Result<(), AppError> {
         let auth_code = authorization_codes::ActiveModel {
             code: Set(code.to_string()),
-            client_id: Set(client_id.to_string()),
-            user_id: Set(user_id),
-            redirect_uri: Set(redirect_uri.to_string()),
-            scopes: Set(scopes.to_string()),
-            expires_at: Set(expires_at.into()),
+            client_id: Set(auth_code.client_id),
+            user_id: Set(auth_code.user_id),
+            redirect_uri: Set(auth_code.redirect_uri),
+            scopes: Set(auth_code.scopes),
+            expires_at: Set(auth_code.expires_at.into()),
             used: Set(false),
             created_at: Set(Utc::now().into()),
+            code_challenge: Set(auth_code.code_challenge),
+            code_challenge_method: Set(auth_code.code_challenge_method),
+            nonce: Set(auth_code.nonce),
+            max_age: Set(auth_code.max_age),
+            auth_time: Set(auth_code.auth_time.map(Into::into)),
+            claims: Set(auth_code.claims),
             ..Default::default()
         };
 
@@ -88,18 +86,17 @@ impl TokenRepository for SeaOrmBackend {
     async fn save_access_token(
         &self,
         token: &str,
-        client_id: &str,
-        user_id: i64,
-        scopes: &str,
-        expires_at: chrono::DateTime<Utc>,
+        access_token: NewAccessToken,
     ) -> Result<i64, AppError> {
         let access_token = access_tokens::ActiveModel {
             token: Set(token.to_string()),
             token_type: Set("Bearer".to_string()),
-            client_id: Set(client_id.to_string()),
-            user_id: Set(user_id),
-            scopes: Set(scopes.to_string()),
-            expires_at: Set(expires_at.into()),
+            client_id: Set(access_token.client_id),
+            user_id: Set(access_token.user_id),
+            scopes: Set(access_token.scopes),
+            claims: Set(access_token.claims),
+            auth_time: Set(access_token.auth_time.map(Into::into)),
+            expires_at: Set(access_token.expires_at.into()),
             created_at: Set(Utc::now().into()),
             ..Default::default()
         };
@@ -125,4 +122,60 @@ impl TokenRepository for SeaOrmBackend {
         refresh_token.insert(self.db.as_ref()).await?;
         Ok(())
     }
+
+    async fn find_access_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<access_tokens::Model>, AppError> {
+        let result = AccessTokens::find()
+            .filter(access_tokens::Column::Token.eq(token))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(result)
+    }
+
+    async fn find_refresh_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<(refresh_tokens::Model, access_tokens::Model)>, AppError> {
+        let result = RefreshTokens::find()
+            .filter(refresh_tokens::Column::Token.eq(token))
+            .find_also_related(AccessTokens)
+            .one(self.db.as_ref())
+            .await?;
+
+        // 关联的 access token 记录不存在视为无效
+        Ok(result.and_then(|(refresh, access)| access.map(|access| (refresh, access))))
+    }
+
+    async fn delete_refresh_token(&self, id: i64) -> Result<bool, AppError> {
+        let result = RefreshTokens::delete_by_id(id)
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete_access_token(&self, id: i64) -> Result<Option<Vec<String>>, AppError> {
+        let txn = self.db.begin().await?;
+
+        // 先删除关联的 refresh_tokens
+        let refresh_tokens = RefreshTokens::find()
+            .filter(refresh_tokens::Column::AccessTokenId.eq(id))
+            .all(&txn)
+            .await?;
+        RefreshTokens::delete_many()
+            .filter(refresh_tokens::Column::AccessTokenId.eq(id))
+            .exec(&txn)
+            .await?;
+
+        let result = AccessTokens::delete_by_id(id).exec(&txn).await?;
+        txn.commit().await?;
+
+        Ok((result.rows_affected > 0).then(|| {
+            refresh_tokens
+                .into_iter()
+                .map(|token| token.token)
+                .collect()
+        }))
+    }
 }
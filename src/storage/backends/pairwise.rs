@@ -0,0 +1,70 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::errors::AppError;
+use crate::storage::entities::pairwise_subjects;
+
+use super::super::backend::SeaOrmBackend;
+
+// pairwise 标识管理方法
+impl SeaOrmBackend {
+    /// 查找用户在扇区中的 pairwise 标识
+    pub async fn find_pairwise_subject(
+        &self,
+        sector_identifier: &str,
+        user_id: i64,
+    ) -> Result<Option<pairwise_subjects::Model>, AppError> {
+        let subject = pairwise_subjects::Entity::find()
+            .filter(pairwise_subjects::Column::SectorIdentifier.eq(sector_identifier))
+            .filter(pairwise_subjects::Column::UserId.eq(user_id))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(subject)
+    }
+
+    /// 按 pairwise 标识查找记录（用于将 sub 映射回用户）
+    pub async fn find_pairwise_subject_by_sub(
+        &self,
+        subject: &str,
+    ) -> Result<Option<pairwise_subjects::Model>, AppError> {
+        let subject = pairwise_subjects::Entity::find()
+            .filter(pairwise_subjects::Column::Subject.eq(subject))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(subject)
+    }
+
+    /// 保存用户在扇区中的 pairwise 标识（已存在时返回原有记录，保证标识不变）
+    pub async fn save_pairwise_subject(
+        &self,
+        sector_identifier: &str,
+        user_id: i64,
+        subject: &str,
+    ) -> Result<pairwise_subjects::Model, AppError> {
+        if let Some(existing) = self
+            .find_pairwise_subject(sector_identifier, user_id)
+            .await?
+        {
+            return Ok(existing);
+        }
+
+        let inserted = pairwise_subjects::ActiveModel {
+            user_id: Set(user_id),
+            sector_identifier: Set(sector_identifier.to_string()),
+            subject: Set(subject.to_string()),
+            created_at: Set(Utc::now().into()),
+            ..Default::default()
+        }
+        .insert(self.db.as_ref())
+        .await;
+
+        // 并发请求可能已经插入了同一条记录
+        match inserted {
+            Ok(subject) => Ok(subject),
+            Err(e) => self
+                .find_pairwise_subject(sector_identifier, user_id)
+                .await?
+                .ok_or(e.into()),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::errors::AppError;
+use crate::storage::entities::signing_keys;
+
+use super::super::backend::SeaOrmBackend;
+
+/// 签名密钥状态
+pub const SIGNING_KEY_NEXT: &str = "next";
+pub const SIGNING_KEY_ACTIVE: &str = "active";
+pub const SIGNING_KEY_RETIRED: &str = "retired";
+
+/// 待保存的签名密钥
+pub struct NewSigningKey<'a> {
+    pub kid: &'a str,
+    pub algorithm: &'a str,
+    pub private_key: &'a str,
+}
+
+impl NewSigningKey<'_> {
+    fn into_active_model(self, status: &str) -> signing_keys::ActiveModel {
+        let now = Utc::now();
+        signing_keys::ActiveModel {
+            kid: Set(self.kid.to_string()),
+            algorithm: Set(self.algorithm.to_string()),
+            private_key: Set(self.private_key.to_string()),
+            status: Set(status.to_string()),
+            created_at: Set(now.into()),
+            activated_at: Set((status == SIGNING_KEY_ACTIVE).then(|| now.into())),
+            retired_at: Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+// JWT 签名密钥管理方法
+impl SeaOrmBackend {
+    /// 保存签名密钥
+    pub async fn create_signing_key(
+        &self,
+        key: NewSigningKey<'_>,
+        status: &str,
+    ) -> Result<signing_keys::Model, AppError> {
+        let result = key
+            .into_active_model(status)
+            .insert(self.db.as_ref())
+            .await?;
+        Ok(result)
+    }
+
+    /// 列出仍需保留的签名密钥（next、active 及在 retired_after 之后退役的密钥）
+    pub async fn list_signing_keys(
+        &self,
+        retired_after: DateTime<Utc>,
+    ) -> Result<Vec<signing_keys::Model>, AppError> {
+        let retired_after: DateTimeWithTimeZone = retired_after.into();
+
+        let keys = signing_keys::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(signing_keys::Column::Status.ne(SIGNING_KEY_RETIRED))
+                    .add(signing_keys::Column::RetiredAt.gt(retired_after)),
+            )
+            .order_by_desc(signing_keys::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?;
+        Ok(keys)
+    }
+
+    /// 替换签名密钥记录中保存的私钥（迁移明文私钥时使用）
+    pub async fn update_signing_key_private_key(
+        &self,
+        id: i64,
+        private_key: &str,
+    ) -> Result<(), AppError> {
+        signing_keys::Entity::update_many()
+            .col_expr(
+                signing_keys::Column::PrivateKey,
+                Expr::value(private_key.to_string()),
+            )
+            .filter(signing_keys::Column::Id.eq(id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    /// 删除待启用的密钥（算法变更后需要重新生成）
+    pub async fn delete_next_signing_keys(&self) -> Result<u64, AppError> {
+        let result = signing_keys::Entity::delete_many()
+            .filter(signing_keys::Column::Status.eq(SIGNING_KEY_NEXT))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 删除在 retired_before 之前退役的密钥
+    pub async fn delete_retired_signing_keys(
+        &self,
+        retired_before: DateTime<Utc>,
+    ) -> Result<u64, AppError> {
+        let retired_before: DateTimeWithTimeZone = retired_before.into();
+
+        let result = signing_keys::Entity::delete_many()
+            .filter(signing_keys::Column::Status.eq(SIGNING_KEY_RETIRED))
+            .filter(signing_keys::Column::RetiredAt.lt(retired_before))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 轮换签名密钥：active → retired，next → active，并保存新的 next 密钥
+    ///
+    /// 返回 (退役的密钥, 新启用的密钥)
+    pub async fn rotate_signing_keys(
+        &self,
+        new_next: NewSigningKey<'_>,
+    ) -> Result<(Option<signing_keys::Model>, signing_keys::Model), AppError> {
+        let txn = self.db.begin().await?;
+        let now: DateTimeWithTimeZone = Utc::now().into();
+
+        // 1. 查找待启用的密钥
+        let next = signing_keys::Entity::find()
+            .filter(signing_keys::Column::Status.eq(SIGNING_KEY_NEXT))
+            .order_by_asc(signing_keys::Column::CreatedAt)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::BadRequest(
+                "No signing key is ready for rotation".into(),
+            ))?;
+
+        // 2. 退役当前密钥
+        let retired = signing_keys::Entity::find()
+            .filter(signing_keys::Column::Status.eq(SIGNING_KEY_ACTIVE))
+            .one(&txn)
+            .await?;
+
+        signing_keys::Entity::update_many()
+            .col_expr(
+                signing_keys::Column::Status,
+                Expr::value(SIGNING_KEY_RETIRED),
+            )
+            .col_expr(signing_keys::Column::RetiredAt, Expr::value(now))
+            .filter(signing_keys::Column::Status.eq(SIGNING_KEY_ACTIVE))
+            .exec(&txn)
+            .await?;
+
+        // 3. 启用 next 密钥
+        let mut activated: signing_keys::ActiveModel = next.into();
+        activated.status = Set(SIGNING_KEY_ACTIVE.to_string());
+        activated.activated_at = Set(Some(now));
+        let activated = activated.update(&txn).await?;
+
+        // 4. 预先生成下一把密钥
+        new_next
+            .into_active_model(SIGNING_KEY_NEXT)
+            .insert(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok((retired, activated))
+    }
+}
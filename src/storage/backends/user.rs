@@ -141,6 +141,33 @@ impl UserRepository for SeaOrmBackend {
         if let Some(is_active) = fields.is_active {
             active.is_active = Set(is_active);
         }
+        if let Some(given_name) = fields.given_name {
+            active.given_name = Set(given_name);
+        }
+        if let Some(family_name) = fields.family_name {
+            active.family_name = Set(family_name);
+        }
+        if let Some(picture) = fields.picture {
+            active.picture = Set(picture);
+        }
+        if let Some(locale) = fields.locale {
+            active.locale = Set(locale);
+        }
+        if let Some(zoneinfo) = fields.zoneinfo {
+            active.zoneinfo = Set(zoneinfo);
+        }
+        if let Some(phone_number) = fields.phone_number {
+            active.phone_number = Set(phone_number);
+        }
+        if let Some(address) = fields.address {
+            active.address = Set(address);
+        }
+        if let Some(verified) = fields.email_verified {
+            active.email_verified = Set(verified);
+        }
+        if let Some(verified) = fields.phone_number_verified {
+            active.phone_number_verified = Set(verified);
+        }
 
         // 保存更新
         let updated_user = active.update(self.db.as_ref()).await?;
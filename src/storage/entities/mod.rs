@@ -6,7 +6,11 @@ pub mod access_tokens;
 pub mod app_settings;
 pub mod authorization_codes;
 pub mod config_audit_logs;
+pub mod consents;
+pub mod device_codes;
 pub mod invite_codes;
 pub mod o_auth_clients;
+pub mod pairwise_subjects;
 pub mod refresh_tokens;
+pub mod signing_keys;
 pub mod users;
@@ -17,6 +17,25 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub allowed_scopes: String,
     pub created_at: DateTimeWithTimeZone,
+    pub require_pkce: bool,
+    pub token_endpoint_auth_method: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub jwks: Option<String>,
+    pub jwks_uri: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub grant_types: Option<String>,
+    pub registration_access_token: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub post_logout_redirect_uris: Option<String>,
+    pub backchannel_logout_uri: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub response_modes: Option<String>,
+    pub require_pushed_authorization_requests: bool,
+    pub require_signed_request_object: bool,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub token_exchange_policy: Option<String>,
+    pub subject_type: String,
+    pub sector_identifier_uri: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
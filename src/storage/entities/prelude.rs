@@ -4,7 +4,11 @@ pub use super::access_tokens::Entity as AccessTokens;
 pub use super::app_settings::Entity as AppSettings;
 pub use super::authorization_codes::Entity as AuthorizationCodes;
 pub use super::config_audit_logs::Entity as ConfigAuditLogs;
+pub use super::consents::Entity as Consents;
+pub use super::device_codes::Entity as DeviceCodes;
 pub use super::invite_codes::Entity as InviteCodes;
 pub use super::o_auth_clients::Entity as OAuthClients;
+pub use super::pairwise_subjects::Entity as PairwiseSubjects;
 pub use super::refresh_tokens::Entity as RefreshTokens;
+pub use super::signing_keys::Entity as SigningKeys;
 pub use super::users::Entity as Users;
@@ -20,6 +20,17 @@ pub struct Model {
     pub deleted_at: Option<DateTimeWithTimeZone>,
     pub last_login_at: Option<DateTimeWithTimeZone>,
     pub login_count: i64,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub picture: Option<String>,
+    pub locale: Option<String>,
+    pub zoneinfo: Option<String>,
+    pub phone_number: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub address: Option<String>,
+    pub email_verified: bool,
+    pub phone_number_verified: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
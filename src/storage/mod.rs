@@ -8,6 +8,10 @@ pub mod repository;
 mod backend_tests;
 
 pub use backend::SeaOrmBackend;
-pub use backends::{InviteStats, UserAuthorizationInfo};
+pub use backends::{
+    ClientUpdateFields, DEVICE_CODE_APPROVED, DEVICE_CODE_DENIED, DEVICE_CODE_PENDING, InviteStats,
+    NewClient, NewSigningKey, RevokedTokens, SIGNING_KEY_ACTIVE, SIGNING_KEY_NEXT,
+    SIGNING_KEY_RETIRED, UserAuthorizationInfo,
+};
 pub use connection::{connect, run_migrations};
 pub use repository::{ClientRepository, TokenRepository, UserRepository};
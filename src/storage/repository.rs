@@ -2,9 +2,12 @@ use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
-use super::entities::{authorization_codes, o_auth_clients, users};
+use super::entities::{access_tokens, authorization_codes, o_auth_clients, refresh_tokens, users};
 use crate::errors::AppError;
 
+/// 第一方登录（/api/auth/login）签发的 Token 所使用的 client_id
+pub const FIRST_PARTY_CLIENT_ID: &str = "ferrusgate";
+
 /// 用户列表查询过滤器
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct UserListFilter {
@@ -55,6 +58,52 @@ pub struct UserUpdateFields {
     pub password_hash: Option<String>,
     pub role: Option<String>,
     pub is_active: Option<bool>,
+    /// OIDC 资料字段（Some(None) 表示清空）
+    pub given_name: Option<Option<String>>,
+    pub family_name: Option<Option<String>>,
+    pub picture: Option<Option<String>>,
+    pub locale: Option<Option<String>>,
+    pub zoneinfo: Option<Option<String>>,
+    pub phone_number: Option<Option<String>>,
+    pub address: Option<Option<String>>,
+    pub email_verified: Option<bool>,
+    pub phone_number_verified: Option<bool>,
+}
+
+/// 新授权码数据
+#[derive(Debug, Clone)]
+pub struct NewAuthCode {
+    pub client_id: String,
+    pub user_id: i64,
+    pub redirect_uri: String,
+    pub scopes: String,
+    pub expires_at: chrono::DateTime<Utc>,
+    /// PKCE code_challenge
+    pub code_challenge: Option<String>,
+    /// PKCE code_challenge_method (S256 / plain)
+    pub code_challenge_method: Option<String>,
+    /// OIDC nonce
+    pub nonce: Option<String>,
+    /// OIDC max_age（秒）
+    pub max_age: Option<i64>,
+    /// 用户完成认证的时间
+    pub auth_time: Option<chrono::DateTime<Utc>>,
+    /// OIDC claims 参数（JSON）
+    pub claims: Option<String>,
+}
+
+/// 新 access token 数据
+#[derive(Debug, Clone)]
+pub struct NewAccessToken {
+    pub client_id: String,
+    /// client_credentials 签发的 token 没有 user_id
+    pub user_id: Option<i64>,
+    pub scopes: String,
+    pub expires_at: chrono::DateTime<Utc>,
+    /// OIDC claims 参数（JSON），供 userinfo 端点使用
+    pub claims: Option<String>,
+    /// 用户完成认证的时间（刷新 token 时沿用）
+    pub auth_time: Option<chrono::DateTime<Utc>>,
 }
 
 /// 用户统计数据
@@ -136,28 +185,18 @@ pub trait ClientRepository: Send + Sync {
 /// Token 仓储
 #[async_trait]
 pub trait TokenRepository: Send + Sync {
-    async fn save_auth_code(
-        &self,
-        code: &str,
-        client_id: &str,
-        user_id: i64,
-        redirect_uri: &str,
-        scopes: &str,
-        expires_at: chrono::DateTime<Utc>,
-    ) -> Result<(), AppError>;
+    async fn save_auth_code(&self, code: &str, auth_code: NewAuthCode) -> Result<(), AppError>;
 
     async fn consume_auth_code(
         &self,
         code: &str,
     ) -> Result<Option<authorization_codes::Model>, AppError>;
 
+    /// 保存 access token，返回记录 id
     async fn save_access_token(
         &self,
         token: &str,
-        client_id: &str,
-        user_id: i64,
-        scopes: &str,
-        expires_at: chrono::DateTime<Utc>,
+        access_token: NewAccessToken,
     ) -> Result<i64, AppError>;
 
     async fn save_refresh_token(
@@ -166,4 +205,24 @@ pub trait TokenRepository: Send + Sync {
         access_token_id: i64,
         expires_at: chrono::DateTime<Utc>,
     ) -> Result<(), AppError>;
+
+    /// 查找 access token 记录（不存在说明已撤销）
+    async fn find_access_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<access_tokens::Model>, AppError>;
+
+    /// 查找 refresh token 及其关联的 access token 记录
+    async fn find_refresh_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<(refresh_tokens::Model, access_tokens::Model)>, AppError>;
+
+    /// 删除 refresh token（轮换后旧 token 立即失效），返回是否确实删除了记录
+    async fn delete_refresh_token(&self, id: i64) -> Result<bool, AppError>;
+
+    /// 删除 access token 及其关联的 refresh token
+    ///
+    /// 返回被删除的 refresh token，调用方负责将其加入黑名单（记录不存在时返回 None）
+    async fn delete_access_token(&self, id: i64) -> Result<Option<Vec<String>>, AppError>;
 }
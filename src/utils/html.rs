@@ -0,0 +1,68 @@
+/// 转义 HTML 文本和属性值中的特殊字符
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 生成加载后自动以 POST 提交的表单页面（OAuth 2.0 Form Post Response Mode）
+pub fn auto_submit_form(action: &str, params: &[(&str, &str)]) -> String {
+    let inputs: String = params
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                r#"<input type="hidden" name="{}" value="{}"/>"#,
+                escape(name),
+                escape(value)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Submit This Form</title></head>
+<body onload="document.forms[0].submit()">
+<form method="post" action="{}">{}<noscript><button type="submit">Continue</button></noscript></form>
+</body>
+</html>
+"#,
+        escape(action),
+        inputs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#x27;&amp;&#x27;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_auto_submit_form() {
+        let html = auto_submit_form(
+            "https://app.example/cb?a=1&b=2",
+            &[("code", "abc"), ("state", r#""><script>"#)],
+        );
+
+        assert!(html.contains(r#"action="https://app.example/cb?a=1&amp;b=2""#));
+        assert!(html.contains(r#"<input type="hidden" name="code" value="abc"/>"#));
+        assert!(html.contains(r#"value="&quot;&gt;&lt;script&gt;""#));
+        assert!(!html.contains("<script>"));
+    }
+}
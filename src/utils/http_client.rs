@@ -0,0 +1,231 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header;
+use reqwest::redirect::{Attempt, Policy};
+use url::{Host, Url};
+
+use crate::errors::AppError;
+
+/// 出站请求超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 响应体大小上限（1 MiB）
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 3;
+
+/// 出站 HTTP 响应
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// 是否为 2xx 响应
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// 发送 GET 请求
+pub async fn get(url: &str) -> Result<Response, AppError> {
+    send(client().get(validate_url(url)?)).await
+}
+
+/// 发送表单编码的 POST 请求
+pub async fn post_form(url: &str, body: &str) -> Result<Response, AppError> {
+    send(
+        client()
+            .post(validate_url(url)?)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body.to_string()),
+    )
+    .await
+}
+
+/// 校验由客户端登记的出站地址（防止 SSRF）：
+/// 只允许 https，主机不能是 localhost 或内网、回环、链路本地等非公网 IP
+pub fn validate_url(url: &str) -> Result<Url, AppError> {
+    let url = Url::parse(url).map_err(|e| AppError::BadRequest(format!("Invalid URL: {}", e)))?;
+    check_url(&url).map_err(AppError::BadRequest)?;
+    Ok(url)
+}
+
+fn check_url(url: &Url) -> Result<(), String> {
+    if url.scheme() != "https" {
+        return Err(format!("URL must use https: {}", url));
+    }
+
+    let public = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        None => false,
+    };
+    if !public {
+        return Err(format!("URL must point to a public host: {}", url));
+    }
+    Ok(())
+}
+
+/// 发送请求并读取响应体（超过大小上限时中止）
+async fn send(request: reqwest::RequestBuilder) -> Result<Response, AppError> {
+    let failed = |e: reqwest::Error| AppError::Internal(format!("Outbound request failed: {}", e));
+
+    let mut response = request.send().await.map_err(failed)?;
+    let status = response.status().as_u16();
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(failed)? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_RESPONSE_SIZE {
+            return Err(AppError::Internal("Response too large".into()));
+        }
+    }
+
+    Ok(Response { status, body })
+}
+
+/// 共享的 HTTP 客户端（复用连接，使用系统根证书）
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent("FerrusGate-Lite")
+            .default_headers(header::HeaderMap::from_iter([(
+                header::ACCEPT,
+                header::HeaderValue::from_static("application/json"),
+            )]))
+            .https_only(true)
+            .redirect(Policy::custom(redirect_policy))
+            .dns_resolver(std::sync::Arc::new(PublicResolver))
+            .build()
+            .expect("Failed to build HTTP client")
+    })
+}
+
+/// 重定向目标同样需要通过出站地址校验
+fn redirect_policy(attempt: Attempt) -> reqwest::redirect::Action {
+    if attempt.previous().len() > MAX_REDIRECTS {
+        return attempt.error("Too many redirects");
+    }
+    match check_url(attempt.url()) {
+        Ok(()) => attempt.follow(),
+        Err(reason) => attempt.error(reason),
+    }
+}
+
+/// 只返回公网地址的 DNS 解析器（防止域名解析到内网地址）
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 是否为公网地址
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // 100.64.0.0/10 运营商级 NAT
+        || (a == 100 && (b & 0xc0) == 64)
+        // 192.0.0.0/24 IETF 协议分配
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15 基准测试
+        || (a == 198 && (b & 0xfe) == 18)
+        // 240.0.0.0/4 保留地址
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 唯一本地地址
+        || (segments[0] & 0xfe00) == 0xfc00
+        // fe80::/10 链路本地地址
+        || (segments[0] & 0xffc0) == 0xfe80
+        // 2001:db8::/32 文档地址
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // 64:ff9b::/96 NAT64 及 ::/96 IPv4 兼容地址可能指向内网
+        || (segments[0] == 0x64 && segments[1] == 0xff9b)
+        || segments[..6] == [0; 6])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://app.example/jwks").is_ok());
+        assert!(validate_url("https://93.184.216.34/logout").is_ok());
+
+        // 只允许 https
+        assert!(validate_url("http://app.example/jwks").is_err());
+        assert!(validate_url("file:///etc/passwd").is_err());
+
+        // 本机及内网地址
+        assert!(validate_url("https://localhost/logout").is_err());
+        assert!(validate_url("https://api.localhost/logout").is_err());
+        assert!(validate_url("https://127.0.0.1/logout").is_err());
+        assert!(validate_url("https://10.0.0.8/logout").is_err());
+        assert!(validate_url("https://169.254.169.254/latest/meta-data").is_err());
+        assert!(validate_url("https://[::1]/logout").is_err());
+        assert!(validate_url("https://[::ffff:192.168.1.1]/logout").is_err());
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        let public = |ip: &str| is_public_ip(ip.parse().unwrap());
+
+        assert!(public("8.8.8.8"));
+        assert!(public("2606:4700:4700::1111"));
+
+        assert!(!public("0.0.0.0"));
+        assert!(!public("172.16.0.1"));
+        assert!(!public("100.64.0.1"));
+        assert!(!public("fd00::1"));
+        assert!(!public("fe80::1"));
+        assert!(!public("64:ff9b::7f00:1"));
+    }
+}
@@ -1 +1,3 @@
 // 工具函数模块
+pub mod html;
+pub mod http_client;